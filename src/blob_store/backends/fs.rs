@@ -0,0 +1,171 @@
+use actix_web::web::{self, Bytes};
+use async_trait::async_trait;
+use futures_util::stream;
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, SeekFrom};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tracing::debug;
+
+use crate::blob_store::{BlobStore, BlobStream, validate_key};
+use crate::declare_blob_store_plugin;
+use crate::errors::{HWSystemError, Result};
+use crate::system::app_config::AppConfig;
+
+declare_blob_store_plugin!("fs", FsBlobStore);
+
+// 流式读取时每次读取的字节数
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// 将对象保存在本地目录中
+///
+/// 按对象键哈希的前两个字节分为两级子目录，避免单个目录下文件过多，
+/// 例如键 `abc` 保存在 `{upload.dir}/ba/78/abc`。
+/// 旧版本直接保存在 `{upload.dir}/{key}.bin` 的文件仍可读取与删除。
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new() -> std::result::Result<Self, String> {
+        let config = AppConfig::get();
+        let root = PathBuf::from(&config.upload.dir);
+        std::fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create upload directory: {e}"))?;
+
+        debug!("FsBlobStore initialized with directory: {}", root.display());
+        Ok(Self { root })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = format!("{:x}", Sha256::digest(key.as_bytes()));
+        self.root.join(&hash[0..2]).join(&hash[2..4]).join(key)
+    }
+
+    fn legacy_path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{key}.bin"))
+    }
+}
+
+fn blocking_error(e: actix_web::error::BlockingError) -> HWSystemError {
+    HWSystemError::blob_store(format!("Blocking task failed: {e}"))
+}
+
+// 先写入同目录下的临时文件再重命名，读取方不会看到写了一半的文件
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let temp = dir.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    if let Err(e) = std::fs::write(&temp, data).and_then(|_| std::fs::rename(&temp, path)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+async fn open_optional(path: &Path) -> std::io::Result<Option<tokio::fs::File>> {
+    match tokio::fs::File::open(path).await {
+        Ok(file) => Ok(Some(file)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// 依次复制各个文件到同目录下的临时文件再重命名
+fn compose_atomic(path: &Path, parts: &[PathBuf]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let temp = dir.join(format!(".{}.tmp", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let mut output = std::fs::File::create(&temp)?;
+        for part in parts {
+            std::io::copy(&mut std::fs::File::open(part)?, &mut output)?;
+        }
+        std::fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+fn remove_optional(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<()> {
+        validate_key(key)?;
+        let path = self.path(key);
+        web::block(move || write_atomic(&path, &data))
+            .await
+            .map_err(blocking_error)??;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, range: Option<(u64, u64)>) -> Result<Option<BlobStream>> {
+        validate_key(key)?;
+        let file = match open_optional(&self.path(key)).await? {
+            Some(file) => Some(file),
+            None => open_optional(&self.legacy_path(key)).await?,
+        };
+        let Some(mut file) = file else {
+            return Ok(None);
+        };
+
+        let remaining = match range {
+            Some((start, end)) => {
+                file.seek(SeekFrom::Start(start)).await?;
+                (end + 1).saturating_sub(start)
+            }
+            None => u64::MAX,
+        };
+
+        let stream = stream::unfold((file, remaining), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+            let mut buf = vec![0u8; READ_CHUNK_SIZE.min(remaining) as usize];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), (file, remaining - n as u64)))
+                }
+                Err(e) => Some((Err(e.into()), (file, 0))),
+            }
+        });
+        Ok(Some(Box::pin(stream)))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        validate_key(key)?;
+        let path = self.path(key);
+        let legacy_path = self.legacy_path(key);
+        web::block(move || {
+            remove_optional(&path)?;
+            remove_optional(&legacy_path)
+        })
+        .await
+        .map_err(blocking_error)??;
+        Ok(())
+    }
+
+    async fn compose(&self, key: &str, parts: &[String]) -> Result<()> {
+        validate_key(key)?;
+        let mut paths = Vec::with_capacity(parts.len());
+        for part in parts {
+            validate_key(part)?;
+            paths.push(self.path(part));
+        }
+        let path = self.path(key);
+        web::block(move || compose_atomic(&path, &paths))
+            .await
+            .map_err(blocking_error)??;
+        Ok(())
+    }
+}
@@ -0,0 +1,497 @@
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use futures_util::{StreamExt, stream};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{ClientOptions, GetOptions, GetRange, ObjectStore, WriteMultipart};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+use crate::blob_store::{BlobStore, BlobStream, MIN_COMPOSE_PART_SIZE, validate_key};
+use crate::declare_blob_store_plugin;
+use crate::errors::{HWSystemError, Result};
+use crate::system::app_config::{AppConfig, S3Config};
+
+declare_blob_store_plugin!("s3", S3BlobStore);
+
+// 响应体读取中断时最多续读的次数
+const MAX_RESUMES: usize = 3;
+// 拼接对象时同时上传的分段数
+const COMPOSE_CONCURRENCY: usize = 4;
+
+/// 将对象保存在 S3 兼容的对象存储中，请求由 object_store 签名与重试
+///
+/// 读取时只发送一个范围请求并以流的形式转发响应体，不会一次性读入整个对象；
+/// 拼接对象时依次读取各对象，以分段上传的方式流式写入。
+#[derive(Clone)]
+pub struct S3BlobStore {
+    store: Arc<AmazonS3>,
+    prefix: String,
+}
+
+impl S3BlobStore {
+    pub fn new() -> std::result::Result<Self, String> {
+        Self::from_config(&AppConfig::get().upload.s3)
+    }
+
+    fn from_config(s3: &S3Config) -> std::result::Result<Self, String> {
+        let mut endpoint =
+            Url::parse(&s3.endpoint).map_err(|e| format!("Invalid S3 endpoint: {e}"))?;
+        if !matches!(endpoint.scheme(), "http" | "https") || endpoint.host_str().is_none() {
+            return Err(format!("Invalid S3 endpoint: {}", s3.endpoint));
+        }
+        if s3.bucket.is_empty() {
+            return Err("upload.s3.bucket is not configured".to_string());
+        }
+        if s3.access_key.is_empty() || s3.secret_key.is_empty() {
+            return Err("upload.s3.access_key and upload.s3.secret_key are required".to_string());
+        }
+        let prefix = s3.prefix.trim_matches('/');
+        if !prefix.is_empty() {
+            validate_key(prefix).map_err(|_| format!("Invalid S3 prefix: {}", s3.prefix))?;
+        }
+
+        // 虚拟主机风格时 object_store 要求 endpoint 中已包含存储桶
+        if !s3.path_style {
+            let host = format!("{}.{}", s3.bucket, endpoint.host_str().unwrap_or_default());
+            endpoint
+                .set_host(Some(&host))
+                .map_err(|e| format!("Invalid bucket name: {e}"))?;
+        }
+
+        let store = AmazonS3Builder::new()
+            .with_endpoint(endpoint.as_str().trim_end_matches('/'))
+            .with_region(&s3.region)
+            .with_bucket_name(&s3.bucket)
+            .with_access_key_id(&s3.access_key)
+            .with_secret_access_key(&s3.secret_key)
+            .with_virtual_hosted_style_request(!s3.path_style)
+            .with_client_options(
+                ClientOptions::new()
+                    .with_allow_http(endpoint.scheme() == "http")
+                    .with_timeout(Duration::from_secs(s3.timeout)),
+            )
+            .build()
+            .map_err(|e| format!("Failed to create S3 client: {e}"))?;
+
+        debug!(
+            "S3BlobStore initialized with endpoint: {}, bucket: {}",
+            s3.endpoint, s3.bucket
+        );
+        Ok(Self {
+            store: Arc::new(store),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{prefix}/")
+            },
+        })
+    }
+
+    fn path(&self, key: &str) -> Result<Path> {
+        validate_key(key)?;
+        Ok(Path::from(format!("{}{key}", self.prefix)))
+    }
+
+    // 依次读取各对象写入分段上传，写满一段即上传，同时进行的上传数有上限
+    async fn copy_parts(&self, upload: &mut WriteMultipart, parts: &[String]) -> Result<()> {
+        for part in parts {
+            let Some(mut content) = self.get(part, None).await? else {
+                return Err(HWSystemError::blob_store(format!(
+                    "S3 object {part} does not exist"
+                )));
+            };
+            while let Some(chunk) = content.next().await {
+                upload
+                    .wait_for_capacity(COMPOSE_CONCURRENCY)
+                    .await
+                    .map_err(|e| s3_error("PUT", part, e))?;
+                upload.write(&chunk?);
+            }
+        }
+        Ok(())
+    }
+}
+
+// 读取中的对象，响应体中断时从断点续读剩余范围
+struct ObjectRead {
+    store: Arc<AmazonS3>,
+    key: String,
+    path: Path,
+    etag: Option<String>,
+    next: u64, // 下一个字节的位置
+    end: u64,  // 读取范围的结束位置（不含）
+    resumes: usize,
+    body: BoxStream<'static, object_store::Result<Bytes>>,
+}
+
+impl ObjectRead {
+    // 返回下一段内容，出错后结束读取
+    async fn next_chunk(mut self) -> Option<(Result<Bytes>, Option<Self>)> {
+        loop {
+            match self.body.next().await? {
+                Ok(chunk) => {
+                    self.next += chunk.len() as u64;
+                    return Some((Ok(chunk), Some(self)));
+                }
+                // 续读请求带 If-Match，对象在读取过程中被替换时拒绝拼接新旧内容
+                Err(_)
+                    if self.resumes < MAX_RESUMES
+                        && self.etag.is_some()
+                        && self.next < self.end =>
+                {
+                    self.resumes += 1;
+                    let options = GetOptions {
+                        range: Some(GetRange::Bounded(self.next..self.end)),
+                        if_match: self.etag.clone(),
+                        ..GetOptions::default()
+                    };
+                    match self.store.get_opts(&self.path, options).await {
+                        Ok(result) => self.body = result.into_stream(),
+                        Err(object_store::Error::Precondition { .. }) => {
+                            let error = HWSystemError::blob_store(format!(
+                                "S3 object {} changed while being read",
+                                self.key
+                            ));
+                            return Some((Err(error), None));
+                        }
+                        Err(e) => return Some((Err(s3_error("GET", &self.key, e)), None)),
+                    }
+                }
+                Err(e) => return Some((Err(s3_error("GET", &self.key, e)), None)),
+            }
+        }
+    }
+}
+
+fn s3_error(method: &str, key: &str, e: object_store::Error) -> HWSystemError {
+    HWSystemError::blob_store(format!("S3 {method} {key} failed: {e}"))
+}
+
+#[async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<()> {
+        self.store
+            .put(&self.path(key)?, data.into())
+            .await
+            .map_err(|e| s3_error("PUT", key, e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, range: Option<(u64, u64)>) -> Result<Option<BlobStream>> {
+        let path = self.path(key)?;
+        let options = GetOptions {
+            range: range.map(|(start, end)| GetRange::Bounded(start..end.saturating_add(1))),
+            ..GetOptions::default()
+        };
+
+        // 先发送请求，对象不存在或请求失败时可以在发送响应前返回错误
+        let result = match self.store.get_opts(&path, options).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => {
+                // 起始位置超出对象大小 (包括空对象) 时存储服务拒绝范围请求
+                let start = range.map_or(0, |(start, _)| start);
+                return match self.store.head(&path).await {
+                    Ok(meta) if start >= meta.size => Ok(Some(Box::pin(stream::empty()))),
+                    Err(object_store::Error::NotFound { .. }) => Ok(None),
+                    _ => Err(s3_error("GET", key, e)),
+                };
+            }
+        };
+
+        let read = ObjectRead {
+            store: self.store.clone(),
+            key: key.to_string(),
+            path,
+            etag: result.meta.e_tag.clone(),
+            next: result.range.start,
+            end: result.range.end,
+            resumes: 0,
+            body: result.into_stream(),
+        };
+        Ok(Some(Box::pin(stream::unfold(
+            Some(read),
+            |read| async move { read?.next_chunk().await },
+        ))))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match self.store.delete(&self.path(key)?).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(s3_error("DELETE", key, e)),
+        }
+    }
+
+    async fn compose(&self, key: &str, parts: &[String]) -> Result<()> {
+        if parts.is_empty() {
+            return self.put(key, Bytes::new()).await;
+        }
+
+        let upload = self
+            .store
+            .put_multipart(&self.path(key)?)
+            .await
+            .map_err(|e| s3_error("POST", key, e))?;
+        let mut upload = WriteMultipart::new_with_chunk_size(upload, MIN_COMPOSE_PART_SIZE);
+
+        if let Err(e) = self.copy_parts(&mut upload, parts).await {
+            // 放弃分段上传，释放已上传的分段
+            let _ = upload.abort().await;
+            return Err(e);
+        }
+        upload
+            .finish()
+            .await
+            .map_err(|e| s3_error("POST", key, e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Mutex;
+    use wiremock::matchers::any;
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const BUCKET: &str = "hw-files";
+
+    // 上传 ID 到已上传分段 (分段号 -> 内容)
+    type Uploads = HashMap<String, BTreeMap<u32, Vec<u8>>>;
+
+    // 内存中的 S3 替身，支持对象读写、范围读取与分段上传，不校验签名
+    #[derive(Clone, Default)]
+    struct FakeS3 {
+        objects: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+        uploads: Arc<Mutex<Uploads>>,
+        ranges: Arc<Mutex<Vec<Option<String>>>>, // 每个 GET 请求的 Range 头
+    }
+
+    fn etag(data: &[u8]) -> String {
+        format!("\"{:x}\"", Sha256::digest(data))
+    }
+
+    impl Respond for FakeS3 {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let key = request
+                .url
+                .path()
+                .strip_prefix(&format!("/{BUCKET}/"))
+                .unwrap_or_default()
+                .to_string();
+            let query: HashMap<String, String> = request.url.query_pairs().into_owned().collect();
+            let mut objects = self.objects.lock().unwrap();
+            let mut uploads = self.uploads.lock().unwrap();
+
+            match (request.method.as_str(), query.get("uploadId")) {
+                ("POST", None) => {
+                    let id = format!("upload-{}", uploads.len());
+                    uploads.insert(id.clone(), BTreeMap::new());
+                    ResponseTemplate::new(200).set_body_string(format!(
+                        "<InitiateMultipartUploadResult><UploadId>{id}</UploadId></InitiateMultipartUploadResult>"
+                    ))
+                }
+                ("PUT", Some(id)) => {
+                    // 上传已放弃时进行中的分段请求失败
+                    let Some(parts) = uploads.get_mut(id) else {
+                        return ResponseTemplate::new(404);
+                    };
+                    let number = query["partNumber"].parse().unwrap();
+                    parts.insert(number, request.body.clone());
+                    ResponseTemplate::new(200).insert_header("ETag", etag(&request.body))
+                }
+                ("POST", Some(id)) => {
+                    let Some(parts) = uploads.remove(id) else {
+                        return ResponseTemplate::new(404);
+                    };
+                    let data: Vec<u8> = parts.into_values().flatten().collect();
+                    let body = format!(
+                        "<CompleteMultipartUploadResult><ETag>{}</ETag></CompleteMultipartUploadResult>",
+                        etag(&data)
+                    );
+                    objects.insert(key, data);
+                    ResponseTemplate::new(200).set_body_string(body)
+                }
+                ("DELETE", Some(id)) => {
+                    uploads.remove(id);
+                    ResponseTemplate::new(204)
+                }
+                ("PUT", None) => {
+                    let tag = etag(&request.body);
+                    objects.insert(key, request.body.clone());
+                    ResponseTemplate::new(200).insert_header("ETag", tag)
+                }
+                ("DELETE", None) => {
+                    objects.remove(&key);
+                    ResponseTemplate::new(204)
+                }
+                (method, None) => {
+                    let Some(data) = objects.get(&key) else {
+                        return ResponseTemplate::new(404)
+                            .set_body_string("<Error><Code>NoSuchKey</Code></Error>");
+                    };
+                    if request
+                        .headers
+                        .get("If-Match")
+                        .is_some_and(|value| value.as_bytes() != etag(data).as_bytes())
+                    {
+                        return ResponseTemplate::new(412);
+                    }
+                    let response = ResponseTemplate::new(200).insert_header("ETag", etag(data));
+                    if method == "HEAD" {
+                        return response.insert_header("Content-Length", data.len().to_string());
+                    }
+                    let range = request
+                        .headers
+                        .get("Range")
+                        .and_then(|value| value.to_str().ok());
+                    self.ranges.lock().unwrap().push(range.map(str::to_string));
+                    let Some(range) = range.and_then(|value| value.strip_prefix("bytes=")) else {
+                        return response.set_body_bytes(data.clone());
+                    };
+                    let (start, end) = range.split_once('-').unwrap();
+                    let start: usize = start.parse().unwrap();
+                    let end = end
+                        .parse::<usize>()
+                        .unwrap()
+                        .min(data.len().saturating_sub(1));
+                    if start >= data.len() {
+                        return ResponseTemplate::new(416);
+                    }
+                    ResponseTemplate::new(206)
+                        .insert_header("ETag", etag(data))
+                        .insert_header(
+                            "Content-Range",
+                            format!("bytes {start}-{end}/{}", data.len()),
+                        )
+                        .set_body_bytes(data[start..=end].to_vec())
+                }
+                _ => ResponseTemplate::new(400),
+            }
+        }
+    }
+
+    async fn start_store() -> (MockServer, FakeS3, S3BlobStore) {
+        let server = MockServer::start().await;
+        let fake = FakeS3::default();
+        Mock::given(any())
+            .respond_with(fake.clone())
+            .mount(&server)
+            .await;
+        let store = S3BlobStore::from_config(&S3Config {
+            endpoint: server.uri(),
+            region: "us-east-1".to_string(),
+            bucket: BUCKET.to_string(),
+            access_key: "AKID".to_string(),
+            secret_key: "SECRET".to_string(),
+            prefix: "/submissions/".to_string(),
+            path_style: true,
+            timeout: 10,
+        })
+        .unwrap();
+        (server, fake, store)
+    }
+
+    async fn read(store: &S3BlobStore, key: &str, range: Option<(u64, u64)>) -> Option<Vec<u8>> {
+        let mut stream = store.get(key, range).await.unwrap()?;
+        let mut data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(&chunk.unwrap());
+        }
+        Some(data)
+    }
+
+    fn content(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn put_get_and_delete_objects() {
+        let (_server, fake, store) = start_store().await;
+        let data = content(MIN_COMPOSE_PART_SIZE * 2 + 100);
+
+        store
+            .put("a/blob", Bytes::from(data.clone()))
+            .await
+            .unwrap();
+        assert!(
+            fake.objects
+                .lock()
+                .unwrap()
+                .contains_key("submissions/a/blob")
+        );
+        assert_eq!(read(&store, "a/blob", None).await.unwrap(), data);
+        assert_eq!(
+            read(&store, "a/blob", Some((10, 4_200_000))).await.unwrap(),
+            data[10..=4_200_000]
+        );
+        // 范围超出对象大小时只返回实际存在的部分
+        assert_eq!(
+            read(
+                &store,
+                "a/blob",
+                Some((data.len() as u64 - 5, u64::MAX - 1))
+            )
+            .await
+            .unwrap(),
+            data[data.len() - 5..]
+        );
+
+        store.put("empty", Bytes::new()).await.unwrap();
+        assert_eq!(read(&store, "empty", None).await.unwrap(), Vec::<u8>::new());
+
+        store.delete("a/blob").await.unwrap();
+        assert!(read(&store, "a/blob", None).await.is_none());
+        store.delete("a/blob").await.unwrap();
+        assert!(store.get("../escape", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn read_uses_one_request_with_the_callers_range() {
+        let (_server, fake, store) = start_store().await;
+        let data = content(MIN_COMPOSE_PART_SIZE * 2);
+        store.put("big", Bytes::from(data.clone())).await.unwrap();
+
+        assert_eq!(read(&store, "big", None).await.unwrap(), data);
+        assert_eq!(
+            read(&store, "big", Some((100, 9_000_000))).await.unwrap(),
+            data[100..=9_000_000]
+        );
+        assert_eq!(
+            *fake.ranges.lock().unwrap(),
+            vec![None, Some("bytes=100-9000000".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn compose_concatenates_parts_with_multipart_upload() {
+        let (_server, fake, store) = start_store().await;
+        let first = content(MIN_COMPOSE_PART_SIZE);
+        let second = content(1000);
+        store.put("p/1", Bytes::from(first.clone())).await.unwrap();
+        store.put("p/2", Bytes::from(second.clone())).await.unwrap();
+
+        store
+            .compose("joined", &["p/1".to_string(), "p/2".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            read(&store, "joined", None).await.unwrap(),
+            [first, second].concat()
+        );
+
+        // 任一对象不存在时放弃分段上传
+        let result = store
+            .compose("broken", &["p/1".to_string(), "p/missing".to_string()])
+            .await;
+        assert!(result.is_err());
+        assert!(read(&store, "broken", None).await.is_none());
+        assert!(fake.uploads.lock().unwrap().is_empty());
+    }
+}
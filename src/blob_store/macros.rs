@@ -0,0 +1,24 @@
+#[macro_export]
+macro_rules! declare_blob_store_plugin {
+    ($name:expr, $ty:ty) => {
+        #[ctor::ctor]
+        fn __register_blob_store_plugin() {
+            use std::sync::Arc;
+            use $crate::blob_store::register::register_blob_store_plugin;
+
+            register_blob_store_plugin(
+                $name,
+                Arc::new(|| {
+                    Box::pin(async {
+                        match <$ty>::new() {
+                            Ok(store) => {
+                                Ok(Box::new(store) as Box<dyn $crate::blob_store::BlobStore>)
+                            }
+                            Err(e) => Err($crate::errors::HWSystemError::blob_store(e)),
+                        }
+                    })
+                }),
+            );
+        }
+    };
+}
@@ -0,0 +1,58 @@
+pub mod backends;
+pub mod macros;
+pub mod register;
+
+use actix_web::web::Bytes;
+use async_trait::async_trait;
+use futures_util::Stream;
+use std::pin::Pin;
+
+use crate::errors::{HWSystemError, Result};
+
+/// `compose` 拼接的对象中，除最后一个外每个对象的最小字节数 (S3 分段上传的限制)
+pub const MIN_COMPOSE_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// 对象内容的字节流
+pub type BlobStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+/// 上传文件内容的存储 trait，具体实现通过 `declare_blob_store_plugin!` 注册
+///
+/// 对象键由调用方生成，只能包含字母、数字与 `.`、`_`、`-`，可用 `/` 分段。
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// 写入对象，已存在时覆盖
+    async fn put(&self, key: &str, data: Bytes) -> Result<()>;
+
+    /// 以流的形式读取对象，`range` 为包含两端的字节范围，为 `None` 时读取全部内容
+    ///
+    /// 对象不存在时返回 `None`，范围超出对象大小时只返回实际存在的部分。
+    async fn get(&self, key: &str, range: Option<(u64, u64)>) -> Result<Option<BlobStream>>;
+
+    /// 删除对象，不存在时视为成功
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// 按顺序拼接 `parts` 中的对象写入 `key`，已存在时覆盖，不会将内容读入内存
+    ///
+    /// 除最后一个外，每个对象不能小于 `MIN_COMPOSE_PART_SIZE`；任一对象不存在时返回错误。
+    async fn compose(&self, key: &str, parts: &[String]) -> Result<()>;
+}
+
+/// 校验对象键，防止通过键访问存储范围之外的路径
+pub fn validate_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && key.len() <= 512
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && !segment.starts_with('.')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(HWSystemError::blob_store(format!(
+            "Invalid object key: {key}"
+        )))
+    }
+}
@@ -0,0 +1,37 @@
+use crate::blob_store::BlobStore;
+use crate::errors::Result;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+pub type BoxedBlobStoreFuture = Pin<Box<dyn Future<Output = Result<Box<dyn BlobStore>>> + Send>>;
+pub type BlobStoreConstructor = Arc<dyn Fn() -> BoxedBlobStoreFuture + Send + Sync>;
+
+static BLOB_STORE_REGISTRY: Lazy<RwLock<HashMap<String, BlobStoreConstructor>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub fn register_blob_store_plugin<S: Into<String>>(name: S, constructor: BlobStoreConstructor) {
+    let name = name.into();
+    let mut registry = BLOB_STORE_REGISTRY.write().unwrap();
+    registry.insert(name, constructor);
+}
+
+pub fn get_blob_store_plugin(name: &str) -> Option<BlobStoreConstructor> {
+    BLOB_STORE_REGISTRY.read().unwrap().get(name).cloned()
+}
+
+pub fn debug_blob_store_registry() {
+    let registry = BLOB_STORE_REGISTRY.read().unwrap();
+    if registry.is_empty() {
+        tracing::debug!("No blob store plugins registered.");
+    } else {
+        tracing::debug!("Registered blob store plugins:");
+        for key in registry.keys() {
+            tracing::debug!(" - {}", key);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use moka::future::Cache;
+use moka::ops::compute::Op;
 use tracing::debug;
 
 use crate::cache::{CacheResult, ObjectCache};
@@ -18,6 +19,13 @@ impl Default for MokaCacheWrapper {
     }
 }
 
+// 计数器以 "计数:过期时间戳" 保存，Moka 不支持单条 TTL，过期由时间戳判断
+fn parse_counter(value: &str, now: i64) -> Option<(u64, i64)> {
+    let (count, expires_at) = value.split_once(':')?;
+    let expires_at: i64 = expires_at.parse().ok()?;
+    (expires_at > now).then_some((count.parse().ok()?, expires_at))
+}
+
 impl MokaCacheWrapper {
     pub fn new() -> Result<Self, String> {
         let config = AppConfig::get();
@@ -65,4 +73,32 @@ impl ObjectCache for MokaCacheWrapper {
     async fn invalidate_all(&self) {
         self.inner.invalidate_all();
     }
+
+    async fn increment(&self, key: &str, ttl: u64) -> crate::errors::Result<u64> {
+        let now = chrono::Utc::now().timestamp();
+        // ttl 为 0 时只受全局 TTL 约束
+        let new_expires_at = if ttl == 0 {
+            i64::MAX
+        } else {
+            now.saturating_add(ttl as i64)
+        };
+        // and_compute_with 对同一个键串行执行，读取与写入之间不会被其他调用插入
+        let result = self
+            .inner
+            .entry_by_ref(key)
+            .and_compute_with(|entry| {
+                let (count, expires_at) = entry
+                    .and_then(|entry| parse_counter(entry.value(), now))
+                    .map_or((1, new_expires_at), |(count, expires_at)| {
+                        (count + 1, expires_at)
+                    });
+                std::future::ready(Op::Put(format!("{count}:{expires_at}")))
+            })
+            .await;
+
+        Ok(result
+            .into_entry()
+            .and_then(|entry| parse_counter(entry.value(), now))
+            .map_or(1, |(count, _)| count))
+    }
 }
@@ -4,10 +4,20 @@ use tracing::{debug, error, warn};
 
 use crate::cache::{CacheResult, ObjectCache};
 use crate::declare_object_cache_plugin;
+use crate::errors::HWSystemError;
 use crate::system::app_config::AppConfig;
 
 declare_object_cache_plugin!("redis", RedisObjectCache);
 
+// INCR 与 EXPIRE 在同一个脚本中执行，避免计数器创建后未设置过期时间
+const INCREMENT_SCRIPT: &str = r"
+local count = redis.call('INCR', KEYS[1])
+if count == 1 then
+    redis.call('EXPIRE', KEYS[1], ARGV[1])
+end
+return count
+";
+
 pub struct RedisObjectCache {
     client: redis::Client,
     key_prefix: String,
@@ -158,4 +168,25 @@ impl ObjectCache for RedisObjectCache {
         warn!("RedisObjectCache does not implement invalidate_all");
         return;
     }
+
+    async fn increment(&self, key: &str, ttl: u64) -> crate::errors::Result<u64> {
+        let redis_key = self.make_key(key);
+
+        let mut conn = self.get_connection().await.map_err(|e| {
+            error!("Failed to get Redis connection: {}", e);
+            HWSystemError::cache_connection(format!("Failed to get Redis connection: {e}"))
+        })?;
+
+        let effective_ttl = if ttl == 0 { self.ttl } else { ttl };
+
+        redis::Script::new(INCREMENT_SCRIPT)
+            .key(redis_key)
+            .arg(effective_ttl)
+            .invoke_async::<u64>(&mut conn)
+            .await
+            .map_err(|e| {
+                error!("Failed to increment key '{}': {}", key, e);
+                HWSystemError::cache_connection(format!("Failed to increment key '{key}': {e}"))
+            })
+    }
 }
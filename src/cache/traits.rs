@@ -27,6 +27,12 @@ pub trait ObjectCache: Send + Sync {
 
     /// 清空所有缓存
     async fn invalidate_all(&self);
+
+    /// 原子递增计数器并返回递增后的值
+    ///
+    /// 键不存在或已过期时从 1 开始计数，并设置 `ttl` 秒后过期；过期时间不随递增延长。
+    /// 缓存不可用时返回错误，调用方据此拒绝请求而不是当作未计数放行
+    async fn increment(&self, key: &str, ttl: u64) -> crate::errors::Result<u64>;
 }
 
 /// 类型安全的缓存扩展 trait
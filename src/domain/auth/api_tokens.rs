@@ -0,0 +1,214 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::{
+        requests::CreateApiTokenRequest,
+        responses::{ApiTokenListResponse, ApiTokenResponse, CreatedApiTokenResponse},
+    },
+};
+use crate::utils::api_token;
+
+use super::AuthService;
+
+// 令牌名称最大长度
+const MAX_NAME_LENGTH: usize = 100;
+
+fn bad_request(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::error_empty(
+        ErrorCode::BadRequest,
+        message.into(),
+    ))
+}
+
+fn internal_error(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        message.into(),
+    ))
+}
+
+// 令牌管理只接受登录获得的 JWT，避免泄露的个人访问令牌为自己续期或签发新令牌
+fn current_user_id(request: &HttpRequest) -> Result<i64, HttpResponse> {
+    if let Some(context) = RequireJWT::extract_api_token(request) {
+        info!(
+            "API token {} attempted to manage personal access tokens",
+            context.token_id
+        );
+        return Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ApiTokenNotAllowed,
+            "Personal access tokens cannot be used to manage tokens, please login",
+        )));
+    }
+
+    RequireJWT::extract_user_id(request).ok_or_else(|| {
+        HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Unauthorized access, please login",
+        ))
+    })
+}
+
+pub async fn handle_list_api_tokens(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user_id = match current_user_id(request) {
+        Ok(user_id) => user_id,
+        Err(response) => return Ok(response),
+    };
+
+    match storage.list_user_api_tokens(user_id).await {
+        Ok(tokens) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            ApiTokenListResponse {
+                items: tokens.into_iter().map(ApiTokenResponse::from).collect(),
+            },
+            "API tokens retrieved successfully",
+        ))),
+        Err(e) => {
+            error!("Failed to list API tokens: {}", e);
+            Ok(internal_error(format!("Failed to list API tokens: {e}")))
+        }
+    }
+}
+
+pub async fn handle_create_api_token(
+    service: &AuthService,
+    create_request: CreateApiTokenRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = &service.get_config().api_token;
+
+    let user_id = match current_user_id(request) {
+        Ok(user_id) => user_id,
+        Err(response) => return Ok(response),
+    };
+
+    let name = create_request.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Ok(bad_request(format!(
+            "Token name must be 1-{MAX_NAME_LENGTH} characters"
+        )));
+    }
+
+    if let Some(scope) = create_request
+        .scopes
+        .iter()
+        .find(|scope| !api_token::SCOPES.contains(&scope.as_str()))
+    {
+        return Ok(bad_request(format!(
+            "Invalid scope '{scope}', allowed scopes: {}",
+            api_token::SCOPES.join(", ")
+        )));
+    }
+    // 去重后按固定顺序保存权限范围
+    let scopes: Vec<&str> = api_token::SCOPES
+        .iter()
+        .copied()
+        .filter(|scope| create_request.scopes.iter().any(|s| s == scope))
+        .collect();
+
+    let expires_in_days = match create_request.expires_in_days {
+        Some(days) if days <= 0 => {
+            return Ok(bad_request("expires_in_days must be a positive number"));
+        }
+        Some(days) if config.max_expiry_days > 0 && days > config.max_expiry_days => {
+            return Ok(bad_request(format!(
+                "expires_in_days must not exceed {}",
+                config.max_expiry_days
+            )));
+        }
+        None if config.max_expiry_days > 0 => {
+            return Ok(bad_request(format!(
+                "expires_in_days is required and must not exceed {}",
+                config.max_expiry_days
+            )));
+        }
+        days => days,
+    };
+    let expires_at = match expires_in_days {
+        Some(days) => match chrono::Duration::try_days(days)
+            .and_then(|duration| chrono::Utc::now().checked_add_signed(duration))
+        {
+            Some(expires_at) => Some(expires_at),
+            None => return Ok(bad_request("expires_in_days is too large")),
+        },
+        None => None,
+    };
+
+    match storage.list_user_api_tokens(user_id).await {
+        Ok(tokens) if tokens.len() >= config.max_per_user => {
+            return Ok(bad_request(format!(
+                "A user can have at most {} API tokens",
+                config.max_per_user
+            )));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to list API tokens: {}", e);
+            return Ok(internal_error(format!("Failed to create API token: {e}")));
+        }
+    }
+
+    let (token, token_prefix, token_hash) = api_token::generate();
+    match storage
+        .create_api_token(
+            user_id,
+            name,
+            &token_prefix,
+            &token_hash,
+            &scopes.join(" "),
+            expires_at,
+        )
+        .await
+    {
+        Ok(api_token) => {
+            info!("User {} created API token {}", user_id, api_token.id);
+            Ok(HttpResponse::Created().json(ApiResponse::success(
+                CreatedApiTokenResponse {
+                    api_token: ApiTokenResponse::from(api_token),
+                    token,
+                },
+                "API token created successfully, it will only be shown once",
+            )))
+        }
+        Err(e) => {
+            error!("Failed to create API token: {}", e);
+            Ok(internal_error(format!("Failed to create API token: {e}")))
+        }
+    }
+}
+
+pub async fn handle_revoke_api_token(
+    service: &AuthService,
+    request: &HttpRequest,
+    token_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user_id = match current_user_id(request) {
+        Ok(user_id) => user_id,
+        Err(response) => return Ok(response),
+    };
+
+    match storage.revoke_api_token(user_id, token_id).await {
+        Ok(true) => {
+            info!("User {} revoked API token {}", user_id, token_id);
+            Ok(HttpResponse::Ok()
+                .json(ApiResponse::success_empty("API token revoked successfully")))
+        }
+        Ok(false) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::ApiTokenNotFound,
+            "API token not found",
+        ))),
+        Err(e) => {
+            error!("Failed to revoke API token: {}", e);
+            Ok(internal_error(format!("Failed to revoke API token: {e}")))
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::cache::ObjectCache;
+use crate::errors::HWSystemError;
+use crate::models::users::entities::User;
+use crate::repository::Storage;
+use crate::system::app_config::AppConfig;
+use crate::utils::password::verify_password;
+
+use super::ldap::LdapAuthenticator;
+
+// 按配置顺序构建的认证方式，配置在启动后不会变化
+static AUTHENTICATORS: Lazy<Vec<Box<dyn Authenticator>>> =
+    Lazy::new(|| build_authenticators(AppConfig::get()));
+
+/// 认证时可用的依赖
+pub struct AuthContext<'a> {
+    pub storage: &'a Arc<dyn Storage>,
+    pub cache: &'a dyn ObjectCache,
+    pub config: &'a AppConfig,
+}
+
+/// 单个认证方式的结果
+pub enum AuthOutcome {
+    /// 认证通过，返回对应的本地用户
+    Authenticated(User),
+    /// 用户存在但密码错误，计入登录失败次数
+    Rejected,
+    /// 该认证方式中不存在此用户，交给下一个认证方式处理
+    UnknownUser,
+    /// 认证通过但无法对应到本地用户，例如未开启自动创建
+    NotProvisioned(String),
+    /// 认证服务不可用
+    Unavailable(HWSystemError),
+}
+
+/// 用户名密码登录的认证方式
+#[async_trait]
+pub trait Authenticator: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// `local_user` 为按登录名（用户名或邮箱）找到的本地用户
+    async fn authenticate(
+        &self,
+        ctx: &AuthContext<'_>,
+        username: &str,
+        password: &str,
+        local_user: Option<&User>,
+    ) -> AuthOutcome;
+}
+
+/// 使用本地存储的 Argon2 密码哈希认证
+pub struct LocalAuthenticator;
+
+#[async_trait]
+impl Authenticator for LocalAuthenticator {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn authenticate(
+        &self,
+        _ctx: &AuthContext<'_>,
+        _username: &str,
+        password: &str,
+        local_user: Option<&User>,
+    ) -> AuthOutcome {
+        match local_user {
+            Some(user) if verify_password(password, &user.password_hash) => {
+                AuthOutcome::Authenticated(user.clone())
+            }
+            Some(_) => AuthOutcome::Rejected,
+            None => AuthOutcome::UnknownUser,
+        }
+    }
+}
+
+/// 按配置顺序返回启用的认证方式
+pub fn authenticators() -> &'static [Box<dyn Authenticator>] {
+    &AUTHENTICATORS
+}
+
+/// 是否启用了指定的认证方式
+pub fn is_enabled(config: &AppConfig, name: &str) -> bool {
+    config
+        .auth
+        .authenticators
+        .iter()
+        .any(|configured| configured.eq_ignore_ascii_case(name))
+}
+
+fn build_authenticators(config: &AppConfig) -> Vec<Box<dyn Authenticator>> {
+    let mut authenticators: Vec<Box<dyn Authenticator>> = Vec::new();
+    for name in &config.auth.authenticators {
+        if authenticators
+            .iter()
+            .any(|authenticator| authenticator.name().eq_ignore_ascii_case(name))
+        {
+            continue;
+        }
+        match name.to_ascii_lowercase().as_str() {
+            "local" => authenticators.push(Box::new(LocalAuthenticator)),
+            "ldap" => authenticators.push(Box::new(LdapAuthenticator)),
+            _ => warn!(
+                "Unknown authenticator '{}' in auth.authenticators, ignored",
+                name
+            ),
+        }
+    }
+
+    // 未配置任何可用的认证方式时回退到本地认证，避免所有用户都无法登录
+    if authenticators.is_empty() {
+        warn!("No authenticator configured, falling back to local authentication");
+        authenticators.push(Box::new(LocalAuthenticator));
+    }
+    authenticators
+}
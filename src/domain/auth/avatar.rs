@@ -0,0 +1,265 @@
+use actix_multipart::Multipart;
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use futures_util::TryStreamExt;
+use futures_util::stream::StreamExt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::errors::HWSystemError;
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::responses::UserInfoResponse,
+    users::{entities::UserProfile, requests::UpdateUserRequest},
+};
+use crate::utils::avatar;
+
+use super::AuthService;
+
+// 上传当前用户的头像，生成各尺寸的正方形图片并自动更新 avatar_url
+pub async fn handle_upload_avatar(
+    service: &AuthService,
+    request: &HttpRequest,
+    mut payload: Multipart,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+    let config = service.get_config();
+    let avatar_config = &config.avatar;
+
+    let Some(current_user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Unauthorized access, please login",
+        )));
+    };
+
+    // 头像较小，直接读入内存处理
+    let mut data: Option<Vec<u8>> = None;
+    while let Ok(Some(mut field)) = payload.try_next().await {
+        let name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_name())
+            .unwrap_or_default()
+            .to_string();
+        if name != "file" {
+            continue;
+        }
+        if data.is_some() {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MuitifileUploadNotAllowed,
+                "Only one file can be uploaded at a time",
+            )));
+        }
+
+        let mut buf = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk?;
+            if buf.len() + chunk.len() > avatar_config.max_size {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                    ErrorCode::FileSizeExceeded,
+                    "File size exceeds the limit",
+                )));
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        data = Some(buf);
+    }
+
+    let Some(data) = data else {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::FileNotFound,
+            "No file found in upload payload",
+        )));
+    };
+
+    // 按文件头判断格式，而不是客户端声明的 Content-Type
+    let Some(format) = avatar::detect_format(&data) else {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::FileTypeNotAllowed,
+            "Only PNG and JPEG images are allowed",
+        )));
+    };
+
+    // 解码与缩放较耗 CPU，放到阻塞线程池中执行
+    let max_dimension = avatar_config.max_dimension;
+    let sizes = avatar_config.sizes.clone();
+    let images = match web::block(move || {
+        avatar::process_avatar(&data, format, max_dimension, &sizes)
+    })
+    .await
+    {
+        Ok(Ok(images)) if !images.is_empty() => images,
+        Ok(Ok(_)) => {
+            error!("No avatar sizes configured");
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Avatar sizes are not configured",
+                )),
+            );
+        }
+        Ok(Err(e)) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::ImageInvalid,
+                format!("Invalid image: {e}"),
+            )));
+        }
+        Err(e) => {
+            error!("Avatar processing task failed: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Avatar processing failed",
+                )),
+            );
+        }
+    };
+
+    let avatar_dir = Path::new(&config.upload.dir).join("avatars");
+    let version = Uuid::new_v4().simple().to_string();
+    let ext = avatar::extension(format);
+
+    // 最大尺寸作为 avatar_url，其他尺寸只需替换文件名中的尺寸部分
+    let largest = images
+        .iter()
+        .map(|(size, _)| *size)
+        .max()
+        .unwrap_or_default();
+    let avatar_url = format!(
+        "{}{}",
+        avatar::AVATAR_URL_PREFIX,
+        avatar::file_name(current_user.id, &version, largest, ext)
+    );
+
+    let files: Vec<_> = images
+        .into_iter()
+        .map(|(size, bytes)| {
+            let path = avatar_dir.join(avatar::file_name(current_user.id, &version, size, ext));
+            (path, bytes)
+        })
+        .collect();
+    let written: Vec<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+    let dir = avatar_dir.clone();
+    let saved = match web::block(move || write_files(&dir, &files)).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = saved {
+        error!("{}", HWSystemError::file_operation(e));
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                "Failed to save avatar",
+            )),
+        );
+    }
+
+    // 缓存中的用户信息可能已过期，只替换头像，其余资料以存储中的为准
+    let stored_user = match storage.get_user_by_id(current_user.id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            remove_files(written).await;
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::UserNotFound,
+                "User not found",
+            )));
+        }
+        Err(e) => {
+            remove_files(written).await;
+            return Ok(update_failed(e));
+        }
+    };
+
+    let update = UpdateUserRequest {
+        email: None,
+        password: None,
+        role: None,
+        status: None,
+        profile: Some(UserProfile {
+            profile_name: stored_user.profile.profile_name,
+            avatar_url: Some(avatar_url),
+        }),
+    };
+
+    match storage.update_user(current_user.id, update).await {
+        Ok(Some(user)) => {
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+
+            // 删除上一次上传的头像文件
+            if let Some((prefix, old_ext)) = stored_user
+                .profile
+                .avatar_url
+                .as_deref()
+                .and_then(avatar::uploaded_version)
+            {
+                let old_files = avatar_config
+                    .sizes
+                    .iter()
+                    .map(|size| avatar_dir.join(format!("{prefix}-{size}.{old_ext}")))
+                    .collect();
+                remove_files(old_files).await;
+            }
+
+            info!("User {} uploaded a new avatar", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                UserInfoResponse { user },
+                "Avatar uploaded successfully",
+            )))
+        }
+        Ok(None) => {
+            remove_files(written).await;
+            Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::UserNotFound,
+                "User not found",
+            )))
+        }
+        Err(e) => {
+            remove_files(written).await;
+            Ok(update_failed(e))
+        }
+    }
+}
+
+fn update_failed(e: HWSystemError) -> HttpResponse {
+    error!("Failed to update avatar: {}", e);
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::UserUpdateFailed,
+        format!("Failed to update avatar: {e}"),
+    ))
+}
+
+// 写入各尺寸的头像文件，任一文件写入失败时删除已写入的文件
+fn write_files(dir: &Path, files: &[(PathBuf, Vec<u8>)]) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (index, (path, bytes)) in files.iter().enumerate() {
+        if let Err(e) = fs::write(path, bytes) {
+            let partial: Vec<_> = files[..=index]
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect();
+            delete_files(&partial);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+// 在阻塞线程池中删除头像文件
+async fn remove_files(paths: Vec<PathBuf>) {
+    if let Err(e) = web::block(move || delete_files(&paths)).await {
+        warn!("Failed to remove avatar files: {}", e);
+    }
+}
+
+fn delete_files(paths: &[PathBuf]) {
+    for path in paths {
+        if let Err(e) = fs::remove_file(path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove avatar file {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use crate::errors::{HWSystemError, Result};
+use crate::mailer::{MailMessage, Mailer};
+use crate::middlewares::RequireJWT;
+use crate::models::{ApiResponse, ErrorCode, auth::VerifyEmailRequest, users::entities::User};
+use crate::system::app_config::AppConfig;
+use crate::utils::email_token::{EmailTokenPurpose, EmailTokenUtils};
+
+use super::AuthService;
+
+/// 向用户当前邮箱发送验证邮件
+pub(crate) async fn send_verification_email(mailer: &dyn Mailer, user: &User) -> Result<()> {
+    let config = AppConfig::get();
+    let token = EmailTokenUtils::generate(
+        user,
+        EmailTokenPurpose::VerifyEmail,
+        chrono::Duration::minutes(config.auth.email_verification_expiry),
+    )
+    .map_err(|e| HWSystemError::mail(format!("Failed to generate verification token: {e}")))?;
+
+    let link = format!(
+        "{}/verify-email?token={}",
+        config.mail.link_base_url.trim_end_matches('/'),
+        token
+    );
+
+    mailer
+        .send(MailMessage {
+            to: user.email.clone(),
+            subject: format!("[{}] 验证你的邮箱", config.app.system_name),
+            body: format!(
+                "{}，你好：\n\n请打开以下链接验证你的邮箱地址，链接在 {} 分钟内有效：\n\n{}\n\n如果这不是你本人的操作，请忽略此邮件。",
+                user.profile.profile_name, config.auth.email_verification_expiry, link
+            ),
+        })
+        .await
+}
+
+pub async fn handle_verify_email(
+    service: &AuthService,
+    verify_request: VerifyEmailRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let invalid_token = || {
+        HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::EmailTokenInvalid,
+            "Verification link is invalid or has expired",
+        ))
+    };
+
+    let Ok(claims) = EmailTokenUtils::decode(&verify_request.token, EmailTokenPurpose::VerifyEmail)
+    else {
+        return Ok(invalid_token());
+    };
+    let Ok(user_id) = claims.sub.parse::<i64>() else {
+        return Ok(invalid_token());
+    };
+
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(invalid_token()),
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to verify email: {e}"),
+                )),
+            );
+        }
+    };
+
+    if user.email_verified && claims.data.email == user.email {
+        return Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::EmailAlreadyVerified,
+            "Email has already been verified",
+        )));
+    }
+    if !EmailTokenUtils::matches_user(&claims, &user, EmailTokenPurpose::VerifyEmail) {
+        return Ok(invalid_token());
+    }
+
+    match storage.mark_email_verified(user.id, &user.email).await {
+        Ok(true) => {
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+            info!("Email verified for user {}", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty("Email verified successfully")))
+        }
+        Ok(false) => Ok(invalid_token()),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to verify email: {e}"),
+            )),
+        ),
+    }
+}
+
+pub async fn handle_resend_verification(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let mailer = service.get_mailer(request);
+
+    let Some(user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Unauthorized access, please login",
+        )));
+    };
+
+    if user.email_verified {
+        return Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::EmailAlreadyVerified,
+            "Email has already been verified",
+        )));
+    }
+
+    match send_verification_email(mailer.as_ref(), &user).await {
+        Ok(()) => Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+            "Verification email sent successfully",
+        ))),
+        Err(e) => {
+            error!("Failed to send verification email: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Failed to send verification email, please try again later",
+                )),
+            )
+        }
+    }
+}
@@ -0,0 +1,858 @@
+use async_trait::async_trait;
+use ldap3::{
+    Ldap, LdapConnAsync, LdapConnSettings, Scope, SearchEntry, SearchOptions, ldap_escape,
+};
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::cache::ObjectCache;
+use crate::errors::{HWSystemError, Result};
+use crate::middlewares::RequireJWT;
+use crate::models::users::{
+    entities::{User, UserProfile, UserRole, UserStatus},
+    requests::{CreateUserRequest, UpdateUserRequest},
+};
+use crate::repository::Storage;
+use crate::system::app_config::{AppConfig, LdapConfig};
+use crate::utils::password::hash_password;
+use crate::utils::random_code::generate_random_code;
+use crate::utils::validate::validate_email;
+
+use super::authenticator::{self, AuthContext, AuthOutcome, Authenticator};
+use super::oidc::{RANDOM_PASSWORD_LENGTH, available_username};
+
+// 外部身份表中 LDAP 账号的提供方名称
+const PROVIDER: &str = "ldap";
+
+// 结果码 (RFC 4511 附录 A)
+const RESULT_SUCCESS: u32 = 0;
+const RESULT_SIZE_LIMIT_EXCEEDED: u32 = 4;
+const RESULT_NO_SUCH_OBJECT: u32 = 32;
+const RESULT_INVALID_CREDENTIALS: u32 = 49;
+
+/// 使用 LDAP 简单绑定认证，认证通过后关联或自动创建本地用户并同步属性
+pub struct LdapAuthenticator;
+
+// 目录中的用户属性
+struct DirectoryUser {
+    subject: String,
+    username: String,
+    email: Option<String>,
+    profile_name: Option<String>,
+    // 未配置组映射时为空，不管理用户角色
+    role: Option<UserRole>,
+}
+
+enum BindResult {
+    Verified(SearchEntry),
+    Rejected,
+    UnknownUser,
+}
+
+#[async_trait]
+impl Authenticator for LdapAuthenticator {
+    fn name(&self) -> &'static str {
+        PROVIDER
+    }
+
+    async fn authenticate(
+        &self,
+        ctx: &AuthContext<'_>,
+        username: &str,
+        password: &str,
+        _local_user: Option<&User>,
+    ) -> AuthOutcome {
+        let config = &ctx.config.ldap;
+        let username = username.trim();
+        if username.is_empty() {
+            return AuthOutcome::UnknownUser;
+        }
+        // 空密码在 LDAP 中表示匿名绑定，总是成功
+        if password.is_empty() {
+            return AuthOutcome::Rejected;
+        }
+
+        let entry = match bind_as_user(config, username, password).await {
+            Ok(BindResult::Verified(entry)) => entry,
+            Ok(BindResult::Rejected) => return AuthOutcome::Rejected,
+            Ok(BindResult::UnknownUser) => return AuthOutcome::UnknownUser,
+            Err(e) => {
+                error!("LDAP authentication for {} failed: {}", username, e);
+                return AuthOutcome::Unavailable(e);
+            }
+        };
+
+        let directory_user = match DirectoryUser::from_entry(config, &entry, username) {
+            Ok(directory_user) => directory_user,
+            Err(e) => {
+                error!("LDAP entry {} cannot be mapped: {}", entry.dn, e);
+                return AuthOutcome::Unavailable(e);
+            }
+        };
+
+        match resolve_user(ctx.storage, ctx.cache, config, &directory_user).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                error!(
+                    "Failed to resolve local user for LDAP entry {}: {}",
+                    entry.dn, e
+                );
+                AuthOutcome::Unavailable(e)
+            }
+        }
+    }
+}
+
+impl DirectoryUser {
+    fn from_entry(config: &LdapConfig, entry: &SearchEntry, login_name: &str) -> Result<Self> {
+        let subject = if config.id_attribute.is_empty() {
+            entry.dn.clone()
+        } else {
+            entry
+                .first_value(&config.id_attribute)
+                .ok_or_else(|| {
+                    HWSystemError::ldap(format!("Entry has no '{}' attribute", config.id_attribute))
+                })?
+                .to_string()
+        };
+
+        let non_empty = |attribute: &str| {
+            entry
+                .first_value(attribute)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        Ok(Self {
+            subject,
+            username: non_empty(&config.username_attribute)
+                .unwrap_or_else(|| login_name.to_string()),
+            email: non_empty(&config.email_attribute).filter(|email| validate_email(email).is_ok()),
+            profile_name: non_empty(&config.profile_name_attribute),
+            role: map_role(config, entry.all_values(&config.group_attribute)),
+        })
+    }
+}
+
+// 按组映射计算角色，属于多个组时取权限最高的角色
+fn map_role(config: &LdapConfig, groups: &[String]) -> Option<UserRole> {
+    if config.group_roles.is_empty() {
+        return None;
+    }
+
+    let rank = |role: &UserRole| match role {
+        UserRole::User => 0,
+        UserRole::Teacher => 1,
+        UserRole::Admin => 2,
+    };
+    let role = config
+        .group_roles
+        .iter()
+        .filter(|(group, _)| {
+            groups
+                .iter()
+                .any(|member_of| member_of.trim().eq_ignore_ascii_case(group.trim()))
+        })
+        .map(|(_, role)| role)
+        .max_by_key(|role| rank(role))
+        .unwrap_or(&config.default_role);
+    Some(role.clone())
+}
+
+fn attributes(config: &LdapConfig) -> Vec<&str> {
+    [
+        config.id_attribute.as_str(),
+        config.username_attribute.as_str(),
+        config.email_attribute.as_str(),
+        config.profile_name_attribute.as_str(),
+        config.group_attribute.as_str(),
+    ]
+    .into_iter()
+    .filter(|attribute| !attribute.is_empty())
+    .collect()
+}
+
+// 按名称读取条目属性，属性名不区分大小写
+trait EntryExt {
+    fn all_values(&self, attribute: &str) -> &[String];
+
+    fn first_value(&self, attribute: &str) -> Option<&str> {
+        self.all_values(attribute).first().map(String::as_str)
+    }
+}
+
+impl EntryExt for SearchEntry {
+    fn all_values(&self, attribute: &str) -> &[String] {
+        self.attrs
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(attribute))
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// LDAP 连接，每次认证或同步使用独立连接，用完后调用 `unbind` 关闭
+struct LdapConnection {
+    ldap: Ldap,
+    timeout: Duration,
+}
+
+impl LdapConnection {
+    /// `tls_ca_file` 不为空时使用该 PEM 文件中的证书验证 ldaps 服务器，否则使用系统根证书
+    async fn connect(config: &LdapConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout);
+        let mut settings = LdapConnSettings::new().set_conn_timeout(timeout);
+        if !config.tls_ca_file.is_empty() {
+            settings = settings.set_config(tls_config(&config.tls_ca_file)?);
+        }
+
+        let (connection, ldap) = LdapConnAsync::with_settings(settings, &config.url)
+            .await
+            .map_err(|e| {
+                HWSystemError::ldap(format!("Failed to connect to {}: {e}", config.url))
+            })?;
+        ldap3::drive!(connection);
+        Ok(Self { ldap, timeout })
+    }
+
+    /// 简单绑定，凭据错误时返回 `Ok(false)`
+    ///
+    /// 空密码在 LDAP 中表示匿名绑定且总是成功，调用方需自行拒绝用户输入的空密码。
+    async fn simple_bind(&mut self, dn: &str, password: &str) -> Result<bool> {
+        let result = self
+            .ldap
+            .with_timeout(self.timeout)
+            .simple_bind(dn, password)
+            .await
+            .map_err(|e| HWSystemError::ldap(format!("Bind failed: {e}")))?;
+        match result.rc {
+            RESULT_SUCCESS => Ok(true),
+            RESULT_INVALID_CREDENTIALS => Ok(false),
+            rc => Err(HWSystemError::ldap(format!(
+                "Bind failed with result {rc}: {}",
+                result.text
+            ))),
+        }
+    }
+
+    /// 搜索条目，忽略搜索引用 (referral)
+    async fn search(
+        &mut self,
+        base_dn: &str,
+        scope: Scope,
+        filter: &str,
+        attributes: &[&str],
+        size_limit: i32,
+    ) -> Result<Vec<SearchEntry>> {
+        let ldap3::SearchResult(entries, result) = self
+            .ldap
+            .with_timeout(self.timeout)
+            .with_search_options(SearchOptions::new().sizelimit(size_limit))
+            .search(base_dn, scope, filter, attributes.to_vec())
+            .await
+            .map_err(|e| HWSystemError::ldap(format!("Search failed: {e}")))?;
+
+        match result.rc {
+            // 超出条数限制时已返回的条目足以判断是否唯一
+            RESULT_SUCCESS | RESULT_SIZE_LIMIT_EXCEEDED => Ok(entries
+                .into_iter()
+                .filter(|entry| !entry.is_ref() && !entry.is_intermediate())
+                .map(SearchEntry::construct)
+                .collect()),
+            // 基准对象不存在视为没有结果
+            RESULT_NO_SUCH_OBJECT => Ok(Vec::new()),
+            rc => Err(HWSystemError::ldap(format!(
+                "Search failed with result {rc}: {}",
+                result.text
+            ))),
+        }
+    }
+
+    /// 发送解绑请求并关闭连接
+    async fn unbind(mut self) {
+        let _ = self.ldap.unbind().await;
+    }
+}
+
+// ldap3 使用 rustls 0.21，CA 证书以 DER 形式传入
+fn tls_config(ca_file: &str) -> Result<Arc<ldap_rustls::ClientConfig>> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        HWSystemError::ldap(format!("Invalid certificate in {ca_file}: {e}"))
+    };
+
+    let mut roots = ldap_rustls::RootCertStore::empty();
+    let certs = CertificateDer::pem_file_iter(ca_file)
+        .map_err(|e| HWSystemError::ldap(format!("Failed to read CA file {ca_file}: {e}")))?;
+    for cert in certs {
+        let cert = cert.map_err(|e| invalid(&e))?;
+        roots
+            .add(&ldap_rustls::Certificate(cert.to_vec()))
+            .map_err(|e| invalid(&e))?;
+    }
+
+    Ok(Arc::new(
+        ldap_rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+// 建立连接，配置了服务账号时先以服务账号绑定
+async fn connect(config: &LdapConfig) -> Result<LdapConnection> {
+    let mut connection = LdapConnection::connect(config).await?;
+
+    if !config.bind_dn.is_empty()
+        && !connection
+            .simple_bind(&config.bind_dn, &config.bind_password)
+            .await?
+    {
+        connection.unbind().await;
+        return Err(HWSystemError::ldap(
+            "Service account credentials were rejected",
+        ));
+    }
+    Ok(connection)
+}
+
+// 先搜索用户条目，再使用条目的 DN 与用户输入的密码绑定
+async fn bind_as_user(config: &LdapConfig, username: &str, password: &str) -> Result<BindResult> {
+    let mut connection = connect(config).await?;
+
+    let filter = config
+        .user_filter
+        .replace("{username}", &ldap_escape(username));
+    let mut entries = match connection
+        .search(
+            &config.base_dn,
+            Scope::Subtree,
+            &filter,
+            &attributes(config),
+            2,
+        )
+        .await
+    {
+        Ok(entries) => entries,
+        Err(e) => {
+            connection.unbind().await;
+            return Err(e);
+        }
+    };
+
+    let entry = match entries.len() {
+        0 => {
+            connection.unbind().await;
+            return Ok(BindResult::UnknownUser);
+        }
+        1 => entries.remove(0),
+        _ => {
+            connection.unbind().await;
+            return Err(HWSystemError::ldap(format!(
+                "User filter matched multiple entries for {username}"
+            )));
+        }
+    };
+
+    let verified = connection.simple_bind(&entry.dn, password).await;
+    connection.unbind().await;
+    Ok(if verified? {
+        BindResult::Verified(entry)
+    } else {
+        BindResult::Rejected
+    })
+}
+
+// 按外部身份、同名本地用户的顺序查找用户，均未找到时按配置自动创建
+async fn resolve_user(
+    storage: &Arc<dyn Storage>,
+    cache: &dyn ObjectCache,
+    config: &LdapConfig,
+    directory_user: &DirectoryUser,
+) -> Result<AuthOutcome> {
+    let email = directory_user.email.as_deref();
+
+    // 1. 已关联的外部身份
+    if let Some(identity) = storage
+        .get_user_identity(PROVIDER, &directory_user.subject)
+        .await?
+    {
+        let _ = storage.touch_user_identity(identity.id, email).await;
+        return match storage.get_user_by_id(identity.user_id).await? {
+            Some(user) => Ok(AuthOutcome::Authenticated(
+                sync_user(storage, cache, user, directory_user).await?,
+            )),
+            None => Ok(AuthOutcome::NotProvisioned(
+                "The linked user no longer exists".to_string(),
+            )),
+        };
+    }
+
+    // 2. 关联同名的本地用户，需显式开启，目录管理员可借此接管本地账号
+    if config.link_existing
+        && let Some(user) = storage
+            .get_user_by_username(&directory_user.username)
+            .await?
+    {
+        storage
+            .create_user_identity(user.id, PROVIDER, &directory_user.subject, email)
+            .await?;
+        info!(
+            "Linked LDAP entry {} to user {}",
+            directory_user.subject, user.username
+        );
+        return Ok(AuthOutcome::Authenticated(
+            sync_user(storage, cache, user, directory_user).await?,
+        ));
+    }
+
+    // 3. 自动创建用户
+    if !config.auto_provision {
+        return Ok(AuthOutcome::NotProvisioned(
+            "This account is not linked to any user, please contact the administrator".to_string(),
+        ));
+    }
+    let Some(email) = email else {
+        return Ok(AuthOutcome::NotProvisioned(
+            "Directory entry has no valid email, unable to create user".to_string(),
+        ));
+    };
+    if storage.get_user_by_email(email).await?.is_some() {
+        return Ok(AuthOutcome::NotProvisioned(
+            "Email already belongs to another user, please contact the administrator".to_string(),
+        ));
+    }
+
+    let username = available_username(storage, &directory_user.username).await?;
+    let password = hash_password(&generate_random_code(RANDOM_PASSWORD_LENGTH))?;
+    let user = storage
+        .create_user(CreateUserRequest {
+            username: username.clone(),
+            email: email.to_string(),
+            password,
+            role: directory_user
+                .role
+                .clone()
+                .unwrap_or_else(|| config.default_role.clone()),
+            status: Some(UserStatus::Active),
+            profile: UserProfile {
+                profile_name: directory_user
+                    .profile_name
+                    .clone()
+                    .unwrap_or_else(|| username.clone()),
+                avatar_url: None,
+            },
+        })
+        .await?;
+
+    // 目录中的邮箱由管理员维护，视为已验证
+    let _ = storage.mark_email_verified(user.id, email).await;
+    storage
+        .create_user_identity(user.id, PROVIDER, &directory_user.subject, Some(email))
+        .await?;
+
+    info!(
+        "Provisioned user {} for LDAP entry {}",
+        user.username, directory_user.subject
+    );
+
+    // 重新读取以获取 email_verified 的最新值
+    Ok(AuthOutcome::Authenticated(
+        storage.get_user_by_id(user.id).await?.unwrap_or(user),
+    ))
+}
+
+// 将目录中的邮箱、显示名称与角色同步到本地用户
+async fn sync_user(
+    storage: &Arc<dyn Storage>,
+    cache: &dyn ObjectCache,
+    user: User,
+    directory_user: &DirectoryUser,
+) -> Result<User> {
+    let mut update = UpdateUserRequest {
+        email: None,
+        password: None,
+        role: None,
+        status: None,
+        profile: None,
+    };
+
+    if let Some(email) = &directory_user.email
+        && *email != user.email
+    {
+        match storage.get_user_by_email(email).await? {
+            Some(other) if other.id != user.id => warn!(
+                "LDAP email {} of user {} belongs to user {}, not synced",
+                email, user.username, other.username
+            ),
+            _ => update.email = Some(email.clone()),
+        }
+    }
+    if let Some(profile_name) = &directory_user.profile_name
+        && *profile_name != user.profile.profile_name
+    {
+        update.profile = Some(UserProfile {
+            profile_name: profile_name.clone(),
+            avatar_url: user.profile.avatar_url.clone(),
+        });
+    }
+    if let Some(role) = &directory_user.role
+        && *role != user.role
+    {
+        update.role = Some(role.clone());
+    }
+
+    let email_changed = update.email.is_some();
+    let changed = email_changed || update.profile.is_some() || update.role.is_some();
+    let user = if changed {
+        storage.update_user(user.id, update).await?.unwrap_or(user)
+    } else {
+        user
+    };
+
+    // 邮箱变更后存储层会重置验证状态，目录中的邮箱视为已验证
+    if directory_user.email.as_deref() == Some(user.email.as_str()) && !user.email_verified {
+        let _ = storage.mark_email_verified(user.id, &user.email).await;
+    } else if !changed {
+        return Ok(user);
+    }
+
+    RequireJWT::invalidate_user_cache(cache, user.id).await;
+    Ok(storage.get_user_by_id(user.id).await?.unwrap_or(user))
+}
+
+/// 启用 LDAP 认证且配置了同步间隔时，启动定期同步用户属性的后台任务
+pub fn start_sync(storage: Arc<dyn Storage>, cache: Arc<dyn ObjectCache>) {
+    let config = AppConfig::get();
+    if !authenticator::is_enabled(config, PROVIDER) || config.ldap.sync_interval == 0 {
+        return;
+    }
+
+    let period = Duration::from_secs(config.ldap.sync_interval);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(period);
+        // 第一次触发是立即的，跳过以免与启动过程争抢资源
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = sync_all(&storage, cache.as_ref(), &AppConfig::get().ldap).await {
+                error!("LDAP attribute sync failed: {}", e);
+            }
+        }
+    });
+    info!(
+        "LDAP attribute sync scheduled every {} seconds",
+        config.ldap.sync_interval
+    );
+}
+
+// 同步所有已关联的 LDAP 账号，目录查询出错时中止，避免把查询失败当作用户已删除
+async fn sync_all(
+    storage: &Arc<dyn Storage>,
+    cache: &dyn ObjectCache,
+    config: &LdapConfig,
+) -> Result<()> {
+    let identities = storage.list_user_identities(PROVIDER).await?;
+    if identities.is_empty() {
+        return Ok(());
+    }
+
+    let mut connection = connect(config).await?;
+    let attributes = attributes(config);
+    let (mut synced, mut suspended) = (0usize, 0usize);
+
+    for identity in identities {
+        let entries = if config.id_attribute.is_empty() {
+            connection
+                .search(
+                    &identity.subject,
+                    Scope::Base,
+                    "(objectClass=*)",
+                    &attributes,
+                    1,
+                )
+                .await
+        } else {
+            let filter = format!(
+                "({}={})",
+                config.id_attribute,
+                ldap_escape(&identity.subject)
+            );
+            connection
+                .search(&config.base_dn, Scope::Subtree, &filter, &attributes, 2)
+                .await
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                connection.unbind().await;
+                return Err(e);
+            }
+        };
+
+        let Some(user) = storage.get_user_by_id(identity.user_id).await? else {
+            continue;
+        };
+
+        match entries.as_slice() {
+            [entry] => {
+                let directory_user = DirectoryUser::from_entry(config, entry, &user.username)?;
+                sync_user(storage, cache, user, &directory_user).await?;
+                synced += 1;
+            }
+            [] if config.suspend_missing && user.status == UserStatus::Active => {
+                storage
+                    .update_user(
+                        user.id,
+                        UpdateUserRequest {
+                            email: None,
+                            password: None,
+                            role: None,
+                            status: Some(UserStatus::Suspended),
+                            profile: None,
+                        },
+                    )
+                    .await?;
+                RequireJWT::invalidate_user_cache(cache, user.id).await;
+                warn!(
+                    "Suspended user {}: LDAP entry {} no longer exists",
+                    user.username, identity.subject
+                );
+                suspended += 1;
+            }
+            [] => {}
+            _ => warn!(
+                "LDAP entry {} matched multiple entries, not synced",
+                identity.subject
+            ),
+        }
+    }
+
+    connection.unbind().await;
+    info!(
+        "LDAP attribute sync completed: {} synced, {} suspended",
+        synced, suspended
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web::BytesMut;
+    use ldap3::asn1::{PL, StructureTag, TagClass, parse_tag, write};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    const BASE_DN: &str = "ou=people,dc=example,dc=org";
+    const USER_DN: &str = "uid=alice,ou=people,dc=example,dc=org";
+    const USER_PASSWORD: &str = "correct horse";
+
+    // 只包含 alice 一个用户的目录替身，记录每次搜索中 uid 的比较值 (存在性判断记为 "*")
+    async fn start_directory() -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ldap://{}", listener.local_addr().unwrap());
+        let assertions = Arc::new(Mutex::new(Vec::new()));
+        let recorded = assertions.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, recorded.clone()));
+            }
+        });
+        (url, assertions)
+    }
+
+    async fn serve(mut stream: TcpStream, assertions: Arc<Mutex<Vec<String>>>) {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let (consumed, message) = match parse_tag(&buffer) {
+                Ok((rest, tag)) => (buffer.len() - rest.len(), tag),
+                Err(_) => match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => {
+                        buffer.extend_from_slice(&chunk[..n]);
+                        continue;
+                    }
+                },
+            };
+            buffer.drain(..consumed);
+
+            let mut parts = message.expect_constructed().unwrap().into_iter();
+            let id = parts.next().unwrap();
+            let op = parts.next().unwrap();
+            let responses = match op.id {
+                // BindRequest: [version, name, [0] password]
+                0 => {
+                    let fields = op.expect_constructed().unwrap();
+                    let name = primitive(&fields[1]);
+                    let password = primitive(&fields[2]);
+                    let rc = if name == USER_DN.as_bytes() && password == USER_PASSWORD.as_bytes() {
+                        RESULT_SUCCESS
+                    } else {
+                        RESULT_INVALID_CREDENTIALS
+                    };
+                    vec![application(1, result_fields(rc))]
+                }
+                // UnbindRequest
+                2 => return,
+                // SearchRequest: [base, scope, deref, size, time, typesOnly, filter, attributes]
+                3 => {
+                    let fields = op.expect_constructed().unwrap();
+                    let mut values = Vec::new();
+                    collect_uid_assertions(&fields[6], &mut values);
+                    let matches =
+                        !values.is_empty() && values.iter().all(|v| v == "alice" || v == "*");
+                    assertions.lock().unwrap().extend(values);
+
+                    let mut responses = Vec::new();
+                    if matches {
+                        responses.push(application(
+                            4,
+                            vec![
+                                octets(USER_DN.as_bytes()),
+                                universal(
+                                    16,
+                                    vec![
+                                        attribute("uid", "alice"),
+                                        attribute("mail", "alice@example.com"),
+                                    ],
+                                ),
+                            ],
+                        ));
+                    }
+                    responses.push(application(5, result_fields(RESULT_SUCCESS)));
+                    responses
+                }
+                _ => return,
+            };
+
+            for response in responses {
+                let envelope = universal(16, vec![id.clone(), response]);
+                let mut out = BytesMut::new();
+                write::encode_into(&mut out, envelope).unwrap();
+                if stream.write_all(&out).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    // 过滤器: [0] and, [3] equalityMatch, [7] present
+    fn collect_uid_assertions(filter: &StructureTag, values: &mut Vec<String>) {
+        match (filter.id, &filter.payload) {
+            (0, PL::C(children)) => children
+                .iter()
+                .for_each(|child| collect_uid_assertions(child, values)),
+            (3, PL::C(pair)) if primitive(&pair[0]).eq_ignore_ascii_case(b"uid") => {
+                values.push(String::from_utf8_lossy(primitive(&pair[1])).into_owned())
+            }
+            (7, PL::P(name)) if name.eq_ignore_ascii_case(b"uid") => values.push("*".to_string()),
+            _ => {}
+        }
+    }
+
+    fn primitive(tag: &StructureTag) -> &[u8] {
+        match &tag.payload {
+            PL::P(bytes) => bytes,
+            PL::C(_) => &[],
+        }
+    }
+
+    fn universal(id: u64, children: Vec<StructureTag>) -> StructureTag {
+        StructureTag {
+            class: TagClass::Universal,
+            id,
+            payload: PL::C(children),
+        }
+    }
+
+    fn application(id: u64, children: Vec<StructureTag>) -> StructureTag {
+        StructureTag {
+            class: TagClass::Application,
+            id,
+            payload: PL::C(children),
+        }
+    }
+
+    fn octets(bytes: &[u8]) -> StructureTag {
+        StructureTag {
+            class: TagClass::Universal,
+            id: 4,
+            payload: PL::P(bytes.to_vec()),
+        }
+    }
+
+    // LDAPResult: [resultCode, matchedDN, diagnosticMessage]
+    fn result_fields(rc: u32) -> Vec<StructureTag> {
+        vec![
+            StructureTag {
+                class: TagClass::Universal,
+                id: 10,
+                payload: PL::P(vec![rc as u8]),
+            },
+            octets(b""),
+            octets(b""),
+        ]
+    }
+
+    fn attribute(name: &str, value: &str) -> StructureTag {
+        universal(
+            16,
+            vec![
+                octets(name.as_bytes()),
+                universal(17, vec![octets(value.as_bytes())]),
+            ],
+        )
+    }
+
+    fn config(url: String) -> LdapConfig {
+        LdapConfig {
+            url,
+            base_dn: BASE_DN.to_string(),
+            timeout: 5,
+            ..LdapConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn bind_with_correct_password_returns_entry() {
+        let (url, _) = start_directory().await;
+
+        let result = bind_as_user(&config(url), "alice", USER_PASSWORD)
+            .await
+            .unwrap();
+        let BindResult::Verified(entry) = result else {
+            panic!("expected the bind to succeed");
+        };
+        assert_eq!(entry.dn, USER_DN);
+        assert_eq!(entry.first_value("UID"), Some("alice"));
+        assert_eq!(entry.first_value("mail"), Some("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn bind_with_wrong_password_is_rejected() {
+        let (url, _) = start_directory().await;
+
+        let result = bind_as_user(&config(url), "alice", "wrong password")
+            .await
+            .unwrap();
+        assert!(matches!(result, BindResult::Rejected));
+    }
+
+    #[tokio::test]
+    async fn username_cannot_inject_filter() {
+        let (url, assertions) = start_directory().await;
+
+        // 未转义时过滤器会变为 (uid=*)(uid=*)，匹配目录中任意用户
+        let result = bind_as_user(&config(url), "*)(uid=*", USER_PASSWORD)
+            .await
+            .unwrap();
+        assert!(matches!(result, BindResult::UnknownUser));
+        assert_eq!(*assertions.lock().unwrap(), vec!["*)(uid=*".to_string()]);
+    }
+}
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cache::ObjectCache;
+use crate::cache::traits::{CacheResult, TypedObjectCache};
+use crate::errors::{HWSystemError, Result};
+use crate::system::app_config::AuthConfig;
+
+/// 登录失败计数的对象，按账号或按 IP 统计
+pub enum LoginSubject<'a> {
+    User(i64),
+    Ip(&'a str),
+}
+
+impl LoginSubject<'_> {
+    // 统计窗口内的失败次数，由缓存原子递增
+    fn failures_key(&self) -> String {
+        match self {
+            LoginSubject::User(user_id) => format!("login_failures:user:{user_id}"),
+            LoginSubject::Ip(ip) => format!("login_failures:ip:{ip}"),
+        }
+    }
+
+    // 限制截止时间，过期判断基于时间戳而非缓存 TTL，moka 缓存会忽略单条 TTL
+    fn block_key(&self) -> String {
+        match self {
+            LoginSubject::User(user_id) => format!("login_block:user:{user_id}"),
+            LoginSubject::Ip(ip) => format!("login_block:ip:{ip}"),
+        }
+    }
+
+    fn max_attempts(&self, config: &AuthConfig) -> u32 {
+        match self {
+            LoginSubject::User(_) => config.max_failed_attempts,
+            LoginSubject::Ip(_) => config.max_failed_attempts_per_ip,
+        }
+    }
+}
+
+// 缓存中保存的限制状态，locked 为 false 时表示失败后的递增延迟
+#[derive(Serialize, Deserialize)]
+struct BlockState {
+    until_ms: i64,
+    locked: bool,
+}
+
+/// 当前禁止登录的原因与剩余时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoginBlock {
+    /// 剩余秒数，向上取整
+    pub retry_after: i64,
+    /// true 为失败次数达到上限后的锁定，false 为失败后的递增延迟
+    pub locked: bool,
+}
+
+/// 返回当前的登录限制，没有限制时返回 None；缓存不可用时返回错误
+pub async fn current_block(
+    cache: &dyn ObjectCache,
+    subject: &LoginSubject<'_>,
+) -> Result<Option<LoginBlock>> {
+    let state = match cache.get::<BlockState>(&subject.block_key()).await {
+        CacheResult::Found(state) => state,
+        CacheResult::NotFound => return Ok(None),
+        CacheResult::ExistsButNoValue => {
+            return Err(HWSystemError::cache_connection(
+                "Failed to read login block state",
+            ));
+        }
+    };
+    let remaining_ms = state.until_ms - chrono::Utc::now().timestamp_millis();
+    Ok((remaining_ms > 0).then(|| LoginBlock {
+        retry_after: (remaining_ms + 999) / 1000,
+        locked: state.locked,
+    }))
+}
+
+/// 记录一次登录失败，返回统计窗口内的失败次数
+///
+/// 达到失败上限时锁定，否则在递增延迟结束前拒绝下一次登录，
+/// 请求本身不再等待。计数器不可用时返回错误，调用方应拒绝本次登录。
+pub async fn record_failure(
+    cache: &dyn ObjectCache,
+    config: &AuthConfig,
+    subject: &LoginSubject<'_>,
+) -> Result<u32> {
+    let count = cache
+        .increment(&subject.failures_key(), config.failure_window.max(1) as u64)
+        .await?;
+    let count = u32::try_from(count).unwrap_or(u32::MAX);
+
+    let max_attempts = subject.max_attempts(config);
+    let (duration_ms, locked) = if max_attempts > 0 && count >= max_attempts {
+        (config.lockout_duration.max(0).saturating_mul(1000), true)
+    } else {
+        let delay = failure_delay(config, count).as_millis();
+        (i64::try_from(delay).unwrap_or(i64::MAX), false)
+    };
+
+    if duration_ms > 0 {
+        let state = BlockState {
+            until_ms: chrono::Utc::now()
+                .timestamp_millis()
+                .saturating_add(duration_ms),
+            locked,
+        };
+        let ttl = (duration_ms as u64).div_ceil(1000);
+        cache.insert(subject.block_key(), state, ttl).await;
+    }
+
+    Ok(count)
+}
+
+/// 清除登录失败记录（登录成功或管理员解锁）
+pub async fn clear_failures(cache: &dyn ObjectCache, subject: &LoginSubject<'_>) {
+    cache.remove(&subject.failures_key()).await;
+    cache.remove(&subject.block_key()).await;
+}
+
+/// 登录失败后的递增延迟，失败次数每增加一次延迟翻倍
+pub fn failure_delay(config: &AuthConfig, failures: u32) -> std::time::Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    let delay = config
+        .failure_delay_base_ms
+        .saturating_mul(1 << exponent)
+        .min(config.failure_delay_max_ms);
+
+    std::time::Duration::from_millis(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::cache::object_cache::moka::MokaCacheWrapper;
+    use crate::system::app_config::AppConfig;
+
+    fn auth_config() -> AuthConfig {
+        AuthConfig {
+            max_failed_attempts: 3,
+            max_failed_attempts_per_ip: 5,
+            ..AppConfig::init_for_test().auth.clone()
+        }
+    }
+
+    // 模拟 Redis 断开：读取得不到值，递增返回错误
+    struct UnavailableCache;
+
+    #[async_trait]
+    impl ObjectCache for UnavailableCache {
+        async fn get_raw(&self, _key: &str) -> CacheResult<String> {
+            CacheResult::ExistsButNoValue
+        }
+
+        async fn insert_raw(&self, _key: String, _value: String, _ttl: u64) {}
+
+        async fn remove(&self, _key: &str) {}
+
+        async fn invalidate_all(&self) {}
+
+        async fn increment(&self, _key: &str, _ttl: u64) -> Result<u64> {
+            Err(HWSystemError::cache_connection("connection refused"))
+        }
+    }
+
+    #[actix_web::test]
+    async fn locks_after_reaching_threshold() {
+        let config = auth_config();
+        let cache = MokaCacheWrapper::new().unwrap();
+        let subject = LoginSubject::User(1);
+
+        for expected in 1..config.max_failed_attempts {
+            assert_eq!(
+                record_failure(&cache, &config, &subject).await.unwrap(),
+                expected
+            );
+            assert_eq!(current_block(&cache, &subject).await.unwrap(), None);
+        }
+        assert_eq!(record_failure(&cache, &config, &subject).await.unwrap(), 3);
+        let block = current_block(&cache, &subject).await.unwrap().unwrap();
+        assert!(block.locked);
+        assert!(block.retry_after > 0 && block.retry_after <= config.lockout_duration);
+
+        // 不同对象的计数互不影响
+        assert_eq!(
+            current_block(&cache, &LoginSubject::Ip("203.0.113.9"))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[actix_web::test]
+    async fn failure_delay_is_kept_in_block_state() {
+        let config = AuthConfig {
+            failure_delay_base_ms: 1500,
+            failure_delay_max_ms: 5000,
+            ..auth_config()
+        };
+        let cache = MokaCacheWrapper::new().unwrap();
+        let subject = LoginSubject::User(2);
+
+        // 第一次失败后 1.5 秒内拒绝登录，但不算锁定
+        record_failure(&cache, &config, &subject).await.unwrap();
+        let block = current_block(&cache, &subject).await.unwrap().unwrap();
+        assert_eq!(
+            block,
+            LoginBlock {
+                retry_after: 2,
+                locked: false
+            }
+        );
+
+        // 第二次失败延迟翻倍
+        record_failure(&cache, &config, &subject).await.unwrap();
+        let block = current_block(&cache, &subject).await.unwrap().unwrap();
+        assert_eq!(
+            block,
+            LoginBlock {
+                retry_after: 3,
+                locked: false
+            }
+        );
+
+        clear_failures(&cache, &subject).await;
+        assert_eq!(current_block(&cache, &subject).await.unwrap(), None);
+    }
+
+    #[actix_web::test]
+    async fn unavailable_cache_is_an_error() {
+        let config = auth_config();
+        let subject = LoginSubject::User(3);
+
+        assert!(
+            record_failure(&UnavailableCache, &config, &subject)
+                .await
+                .is_err()
+        );
+        assert!(current_block(&UnavailableCache, &subject).await.is_err());
+    }
+
+    #[actix_web::test]
+    async fn clear_failures_resets_count_and_lock() {
+        let config = auth_config();
+        let cache = MokaCacheWrapper::new().unwrap();
+        let subject = LoginSubject::Ip("203.0.113.9");
+
+        for _ in 0..config.max_failed_attempts_per_ip {
+            record_failure(&cache, &config, &subject).await.unwrap();
+        }
+        assert!(current_block(&cache, &subject).await.unwrap().is_some());
+
+        clear_failures(&cache, &subject).await;
+        assert_eq!(current_block(&cache, &subject).await.unwrap(), None);
+        assert_eq!(record_failure(&cache, &config, &subject).await.unwrap(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_failures_are_all_counted() {
+        let config = Arc::new(auth_config());
+        let cache = Arc::new(MokaCacheWrapper::new().unwrap());
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let (cache, config) = (cache.clone(), config.clone());
+                tokio::spawn(async move {
+                    record_failure(cache.as_ref(), &config, &LoginSubject::User(7)).await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        assert_eq!(
+            record_failure(cache.as_ref(), &config, &LoginSubject::User(7))
+                .await
+                .unwrap(),
+            51
+        );
+    }
+}
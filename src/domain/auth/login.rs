@@ -1,13 +1,22 @@
-use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use actix_web::cookie::Cookie;
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, http::header};
+use tracing::warn;
 
+use crate::cache::ObjectCache;
 use crate::models::{
     ApiResponse, ErrorCode,
     auth::{LoginRequest, LoginResponse},
+    users::entities::{User, UserStatus},
 };
+use crate::system::app_config::AuthConfig;
+use crate::utils::client_ip::client_ip;
 use crate::utils::jwt;
-use crate::utils::password::verify_password;
 
 use super::AuthService;
+use super::authenticator::{AuthContext, AuthOutcome, authenticators};
+use super::lockout::{self, LoginSubject};
+use super::mfa;
+use super::sessions::create_login_session;
 
 pub async fn handle_login(
     service: &AuthService,
@@ -15,71 +24,286 @@ pub async fn handle_login(
     request: &HttpRequest,
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
     let config = service.get_config();
 
-    // 1. 根据用户名或邮箱获取用户信息
-    match storage
+    let ip_address = client_ip(request).map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+    let ip_subject = LoginSubject::Ip(&ip_address);
+
+    // 0. 同一 IP 失败次数过多时直接拒绝
+    if let Some(response) = check_login_block(cache.as_ref(), &ip_subject).await {
+        return Ok(response);
+    }
+
+    // 1. 根据用户名或邮箱获取本地用户，外部认证方式首次登录时本地用户可能不存在
+    let local_user = match storage
         .get_user_by_username_or_email(&login_request.username)
         .await
     {
-        Ok(Some(user)) => {
-            // 2. 验证密码
-            if verify_password(&login_request.password, &user.password_hash) {
-                // 3. 更新最后登录时间
-                let _ = storage.update_last_login(user.id).await;
-
-                // 4. 生成令牌对
-                match user
-                    .generate_token_pair(login_request.remember_me.then(|| {
-                        chrono::Duration::days(config.jwt.refresh_token_remember_me_expiry)
-                    }))
-                    .await
-                {
-                    Ok(token_pair) => {
-                        // 生成 Access Token 和 Refresh Token 成功
-                        tracing::info!("User {} logged in successfully", user.username);
-
-                        let response = LoginResponse {
-                            access_token: token_pair.access_token,
-                            expires_in: config.jwt.access_token_expiry * 60, // 转换为秒
-                            user,
-                            created_at: chrono::Utc::now(),
-                        };
-
-                        // 6. 创建 refresh token cookie
-                        let refresh_cookie =
-                            jwt::JwtUtils::create_refresh_token_cookie(&token_pair.refresh_token);
-
-                        Ok(HttpResponse::Ok()
-                            .cookie(refresh_cookie)
-                            .json(ApiResponse::success(response, "Login successful")))
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to generate JWT token: {}", e);
-                        Ok(
-                            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
-                                ErrorCode::InternalServerError,
-                                "Login failed, unable to generate token",
-                            )),
-                        )
-                    }
-                }
-            } else {
-                Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
-                    ErrorCode::AuthFailed,
-                    "Username or password is incorrect",
-                )))
+        Ok(user) => user,
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Login failed: {e}"),
+                )),
+            );
+        }
+    };
+    let user_subject = local_user.as_ref().map(|user| LoginSubject::User(user.id));
+
+    // 2. 账号处于锁定期或失败延迟内时不再校验密码
+    if let Some(user_subject) = &user_subject
+        && let Some(response) = check_login_block(cache.as_ref(), user_subject).await
+    {
+        return Ok(response);
+    }
+
+    // 3. 按配置顺序尝试各认证方式，第一个通过的生效
+    let ctx = AuthContext {
+        storage: &storage,
+        cache: cache.as_ref(),
+        config,
+    };
+    let mut rejected = false;
+    let mut unavailable = false;
+    let mut authenticated = None;
+    for authenticator in authenticators() {
+        match authenticator
+            .authenticate(
+                &ctx,
+                &login_request.username,
+                &login_request.password,
+                local_user.as_ref(),
+            )
+            .await
+        {
+            AuthOutcome::Authenticated(user) => {
+                authenticated = Some(user);
+                break;
+            }
+            AuthOutcome::Rejected => rejected = true,
+            AuthOutcome::UnknownUser => {}
+            AuthOutcome::NotProvisioned(message) => {
+                return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                    ErrorCode::ExternalAccountNotLinked,
+                    message,
+                )));
+            }
+            AuthOutcome::Unavailable(e) => {
+                warn!(
+                    "Authenticator {} is unavailable: {}",
+                    authenticator.name(),
+                    e
+                );
+                unavailable = true;
             }
         }
-        Ok(None) => Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+    }
+
+    let Some(user) = authenticated else {
+        // 没有任何认证方式给出明确的拒绝时，不把认证服务故障计入失败次数
+        if unavailable && !rejected {
+            return Ok(
+                HttpResponse::ServiceUnavailable().json(ApiResponse::error_empty(
+                    ErrorCode::AuthServiceUnavailable,
+                    "Authentication service is temporarily unavailable, please try again later",
+                )),
+            );
+        }
+        return Ok(login_failed(
+            cache.as_ref(),
+            &config.auth,
+            &ip_subject,
+            user_subject.as_ref(),
             ErrorCode::AuthFailed,
             "Username or password is incorrect",
-        ))),
-        Err(e) => Ok(
+        )
+        .await);
+    };
+    let user_subject = LoginSubject::User(user.id);
+
+    // 4. 未激活、已暂停或待审核的账号不能登录
+    match user.status {
+        UserStatus::Active => {}
+        UserStatus::Inactive => {
+            return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                ErrorCode::AccountNotActive,
+                "Account is not active",
+            )));
+        }
+        UserStatus::Pending => {
+            return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                ErrorCode::AccountNotActive,
+                "Account is pending approval",
+            )));
+        }
+        UserStatus::Suspended => {
+            return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                ErrorCode::AccountNotActive,
+                "Account is suspended",
+            )));
+        }
+    }
+
+    // 5. 启用或必须启用两步验证时，先返回第二步令牌
+    // 失败计数在两步验证通过后才清除，避免反复重新登录来绕过验证码的失败次数限制
+    if let Some(response) =
+        mfa::login_challenge(&storage, config, &user, login_request.remember_me).await
+    {
+        return Ok(response);
+    }
+    lockout::clear_failures(cache.as_ref(), &user_subject).await;
+
+    // 6. 签发令牌
+    Ok(issue_login_tokens(service, request, user, login_request.remember_me, None).await)
+}
+
+// 登录失败计数不可用时拒绝登录，不能在无法限制尝试次数的情况下继续校验密码
+pub(super) fn lockout_unavailable(e: crate::errors::HWSystemError) -> HttpResponse {
+    warn!("Login lockout state is unavailable: {}", e);
+    HttpResponse::ServiceUnavailable().json(ApiResponse::error_empty(
+        ErrorCode::AuthServiceUnavailable,
+        "Authentication service is temporarily unavailable, please try again later",
+    ))
+}
+
+// 处于锁定期或失败延迟内时返回拒绝响应：账号锁定返回 423，其余返回 429
+pub(super) async fn check_login_block(
+    cache: &dyn ObjectCache,
+    subject: &LoginSubject<'_>,
+) -> Option<HttpResponse> {
+    let block = match lockout::current_block(cache, subject).await {
+        Ok(Some(block)) => block,
+        Ok(None) => return None,
+        Err(e) => return Some(lockout_unavailable(e)),
+    };
+
+    let mut response = if block.locked && matches!(subject, LoginSubject::User(_)) {
+        HttpResponse::Locked()
+    } else {
+        HttpResponse::TooManyRequests()
+    };
+    response.insert_header((header::RETRY_AFTER, block.retry_after.to_string()));
+    Some(if block.locked {
+        match subject {
+            LoginSubject::User(_) => response.json(ApiResponse::error_empty(
+                ErrorCode::AccountLocked,
+                "Account is temporarily locked due to too many failed login attempts",
+            )),
+            LoginSubject::Ip(_) => response.json(ApiResponse::error_empty(
+                ErrorCode::TooManyLoginAttempts,
+                "Too many failed login attempts, please try again later",
+            )),
+        }
+    } else {
+        response.json(ApiResponse::error_empty(
+            ErrorCode::TooManyLoginAttempts,
+            "Login attempted too soon after a failure, please try again later",
+        ))
+    })
+}
+
+// 记录失败次数，下一次登录需等待递增延迟或锁定结束
+pub(super) async fn login_failed(
+    cache: &dyn ObjectCache,
+    config: &AuthConfig,
+    ip_subject: &LoginSubject<'_>,
+    user_subject: Option<&LoginSubject<'_>>,
+    code: ErrorCode,
+    message: &str,
+) -> HttpResponse {
+    if let Err(e) = lockout::record_failure(cache, config, ip_subject).await {
+        return lockout_unavailable(e);
+    }
+    if let Some(user_subject) = user_subject
+        && let Err(e) = lockout::record_failure(cache, config, user_subject).await
+    {
+        return lockout_unavailable(e);
+    }
+
+    HttpResponse::Unauthorized().json(ApiResponse::error_empty(code, message))
+}
+
+// 更新最后登录时间，签发令牌对并创建登录会话
+pub(super) async fn issue_login_tokens(
+    service: &AuthService,
+    request: &HttpRequest,
+    user: User,
+    remember_me: bool,
+    recovery_codes: Option<Vec<String>>,
+) -> HttpResponse {
+    match create_login_tokens(service, request, user, remember_me, recovery_codes).await {
+        Ok((response, refresh_cookie)) => HttpResponse::Ok()
+            .cookie(refresh_cookie)
+            .json(ApiResponse::success(response, "Login successful")),
+        Err(response) => response,
+    }
+}
+
+// 签发令牌并创建登录会话，返回登录响应与 refresh token cookie
+pub(super) async fn create_login_tokens(
+    service: &AuthService,
+    request: &HttpRequest,
+    user: User,
+    remember_me: bool,
+    recovery_codes: Option<Vec<String>>,
+) -> Result<(LoginResponse, Cookie<'static>), HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let _ = storage.update_last_login(user.id).await;
+
+    let token_pair = match user
+        .generate_token_pair(
+            remember_me
+                .then(|| chrono::Duration::days(config.jwt.refresh_token_remember_me_expiry)),
+        )
+        .await
+    {
+        Ok(token_pair) => token_pair,
+        Err(e) => {
+            tracing::error!("Failed to generate JWT token: {}", e);
+            return Err(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Login failed, unable to generate token",
+                )),
+            );
+        }
+    };
+
+    // 创建登录会话并记录 Refresh Token
+    if let Err(e) = create_login_session(
+        &storage,
+        request,
+        user.id,
+        remember_me,
+        &token_pair.refresh_claims,
+    )
+    .await
+    {
+        tracing::error!("Failed to create login session: {}", e);
+        return Err(
             HttpResponse::InternalServerError().json(ApiResponse::error_empty(
                 ErrorCode::InternalServerError,
-                format!("Login failed: {e}"),
+                "Login failed, unable to create session",
             )),
-        ),
+        );
     }
+
+    tracing::info!("User {} logged in successfully", user.username);
+
+    let response = LoginResponse {
+        access_token: token_pair.access_token,
+        expires_in: config.jwt.access_token_expiry * 60, // 转换为秒
+        user,
+        created_at: chrono::Utc::now(),
+        recovery_codes,
+    };
+
+    // 创建 refresh token cookie
+    let refresh_cookie = jwt::JwtUtils::create_refresh_token_cookie(&token_pair.refresh_token);
+
+    Ok((response, refresh_cookie))
 }
@@ -0,0 +1,105 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use crate::middlewares::require_jwt::RequireJWT;
+use crate::models::{ApiResponse, ErrorCode};
+use crate::utils::jwt;
+
+use super::AuthService;
+
+pub async fn handle_logout(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let user_id = match RequireJWT::extract_user_id(request) {
+        Some(user_id) => user_id,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized access, please login",
+            )));
+        }
+    };
+
+    // 终止当前会话，仅处理属于当前用户的 refresh token
+    if let Some(claims) = jwt::JwtUtils::extract_refresh_token_from_cookie(request)
+        .and_then(|token| jwt::JwtUtils::verify_refresh_token(&token).ok())
+        .filter(|claims| claims.sub == user_id.to_string())
+    {
+        let result = match &claims.fid {
+            Some(session_id) => storage.revoke_session(session_id).await,
+            None => storage.revoke_refresh_token(&claims.jti).await,
+        };
+        if let Err(e) = result {
+            error!("Failed to revoke refresh token on logout: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Logout failed, please try again later",
+                )),
+            );
+        }
+    }
+
+    // 当前会话签发的 access token 在剩余有效期内同样失效
+    if let Some(claims) = RequireJWT::extract_access_claims(request) {
+        jwt::JwtUtils::revoke_access_token(cache.as_ref(), &claims).await;
+        if let Some(session_id) = &claims.fid {
+            jwt::JwtUtils::revoke_session_access_tokens(cache.as_ref(), session_id).await;
+        }
+    }
+
+    info!("User {} logged out", user_id);
+
+    Ok(HttpResponse::Ok()
+        .cookie(jwt::JwtUtils::create_empty_refresh_token_cookie())
+        .json(ApiResponse::success_empty("Logout successful")))
+}
+
+pub async fn handle_logout_all(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let user_id = match RequireJWT::extract_user_id(request) {
+        Some(user_id) => user_id,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized access, please login",
+            )));
+        }
+    };
+
+    match storage.revoke_user_sessions(user_id).await {
+        Ok(revoked) => {
+            // 此前签发的 access token 全部失效
+            jwt::JwtUtils::revoke_user_access_tokens(cache.as_ref(), user_id).await;
+
+            info!(
+                "User {} logged out from all devices, {} sessions terminated",
+                user_id, revoked
+            );
+
+            Ok(HttpResponse::Ok()
+                .cookie(jwt::JwtUtils::create_empty_refresh_token_cookie())
+                .json(ApiResponse::success_empty(
+                    "Logged out from all devices successfully",
+                )))
+        }
+        Err(e) => {
+            error!("Failed to revoke refresh tokens on logout: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Logout failed, please try again later",
+                )),
+            )
+        }
+    }
+}
@@ -0,0 +1,644 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use rand::Rng;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::errors::{HWSystemError, Result};
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::{
+        entities::{MfaStage, UserMfa},
+        requests::{DisableMfaRequest, MfaCodeRequest, MfaLoginRequest, MfaLoginSetupRequest},
+        responses::{
+            MfaChallengeResponse, MfaRecoveryCodesResponse, MfaSetupResponse, MfaStatusResponse,
+        },
+    },
+    users::entities::{User, UserStatus},
+};
+use crate::repository::Storage;
+use crate::system::app_config::AppConfig;
+use crate::utils::client_ip::client_ip;
+use crate::utils::mfa_token::MfaTokenUtils;
+use crate::utils::password::{hash_password, verify_password};
+use crate::utils::totp;
+
+use super::AuthService;
+use super::lockout::{self, LoginSubject};
+use super::login::{check_login_block, issue_login_tokens, login_failed};
+
+// 恢复码字符集，去掉了容易混淆的 0/o/1/l/i
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+const RECOVERY_CODE_LENGTH: usize = 10;
+
+fn mfa_required(config: &AppConfig, user: &User) -> bool {
+    config.mfa.required_roles.contains(&user.role)
+}
+
+fn internal_error(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        message.into(),
+    ))
+}
+
+fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+        ErrorCode::Unauthorized,
+        "Unauthorized access, please login",
+    ))
+}
+
+fn mfa_token_invalid() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+        ErrorCode::MfaTokenInvalid,
+        "Two-factor login token is invalid or has expired, please login again",
+    ))
+}
+
+// 生成恢复码，返回明文（展示给用户）与哈希（写入存储）
+async fn generate_recovery_codes(count: usize) -> Result<(Vec<String>, Vec<String>)> {
+    let codes: Vec<String> = {
+        let mut rng = rand::rng();
+        (0..count)
+            .map(|_| {
+                let code: String = (0..RECOVERY_CODE_LENGTH)
+                    .map(|_| {
+                        RECOVERY_CODE_ALPHABET[rng.random_range(0..RECOVERY_CODE_ALPHABET.len())]
+                            as char
+                    })
+                    .collect();
+                format!("{}-{}", &code[..5], &code[5..])
+            })
+            .collect()
+    };
+
+    // 恢复码与密码一样使用 Argon2 哈希，计算较慢，放到阻塞线程池中执行
+    let plain = codes.clone();
+    let hashes = web::block(move || {
+        plain
+            .iter()
+            .map(|code| hash_password(&normalize_recovery_code(code)))
+            .collect::<Result<Vec<String>>>()
+    })
+    .await
+    .map_err(|e| HWSystemError::validation(format!("Recovery code hashing failed: {e}")))??;
+
+    Ok((codes, hashes))
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == totp::TOTP_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit())
+}
+
+// 校验 TOTP 验证码，同一时间步的验证码只能使用一次
+async fn verify_totp(
+    storage: &Arc<dyn Storage>,
+    config: &AppConfig,
+    mfa: &UserMfa,
+    code: &str,
+) -> Result<bool> {
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let Some(step) = totp::verify(&mfa.secret, code, now, config.mfa.allowed_skew) else {
+        return Ok(false);
+    };
+    storage
+        .update_mfa_last_used_step(mfa.user_id, step as i64)
+        .await
+}
+
+// 校验恢复码，校验通过后立即作废
+async fn verify_recovery_code(
+    storage: &Arc<dyn Storage>,
+    user_id: i64,
+    code: &str,
+) -> Result<bool> {
+    let normalized = normalize_recovery_code(code);
+    if normalized.len() != RECOVERY_CODE_LENGTH {
+        return Ok(false);
+    }
+
+    let codes = storage.list_unused_recovery_codes(user_id).await?;
+    let matched = web::block(move || {
+        codes
+            .into_iter()
+            .find(|recovery_code| verify_password(&normalized, &recovery_code.code_hash))
+    })
+    .await
+    .map_err(|e| HWSystemError::validation(format!("Recovery code verification failed: {e}")))?;
+
+    match matched {
+        Some(recovery_code) => storage.use_recovery_code(recovery_code.id).await,
+        None => Ok(false),
+    }
+}
+
+// 校验第二因素，6 位数字按 TOTP 验证码处理，其余按恢复码处理
+async fn verify_second_factor(
+    storage: &Arc<dyn Storage>,
+    config: &AppConfig,
+    mfa: &UserMfa,
+    code: &str,
+) -> Result<bool> {
+    if is_totp_code(code) {
+        verify_totp(storage, config, mfa, code).await
+    } else {
+        verify_recovery_code(storage, mfa.user_id, code).await
+    }
+}
+
+// 生成新密钥并返回绑定信息，覆盖尚未启用的旧密钥
+async fn start_setup(storage: &Arc<dyn Storage>, config: &AppConfig, user: &User) -> HttpResponse {
+    let secret = totp::generate_secret();
+    if let Err(e) = storage.save_user_mfa_secret(user.id, &secret).await {
+        return internal_error(format!("Failed to start two-factor setup: {e}"));
+    }
+
+    let otpauth_uri = totp::otpauth_uri(&config.mfa.issuer, &user.username, &secret);
+    HttpResponse::Ok().json(ApiResponse::success(
+        MfaSetupResponse {
+            secret,
+            otpauth_uri,
+        },
+        "Scan the QR code with an authenticator app, then confirm with a verification code",
+    ))
+}
+
+// 校验验证码后启用两步验证，返回恢复码明文
+async fn confirm_setup(
+    storage: &Arc<dyn Storage>,
+    config: &AppConfig,
+    mfa: &UserMfa,
+    code: &str,
+) -> std::result::Result<Vec<String>, HttpResponse> {
+    match verify_totp(storage, config, mfa, code).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaCodeInvalid,
+                "Invalid verification code",
+            )));
+        }
+        Err(e) => return Err(internal_error(format!("Failed to verify code: {e}"))),
+    }
+
+    let (codes, hashes) = generate_recovery_codes(config.mfa.recovery_code_count)
+        .await
+        .map_err(|e| internal_error(format!("Failed to generate recovery codes: {e}")))?;
+
+    match storage.enable_user_mfa(mfa.user_id, &hashes).await {
+        Ok(true) => Ok(codes),
+        Ok(false) => Err(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::MfaAlreadyEnabled,
+            "Two-factor authentication is already enabled",
+        ))),
+        Err(e) => Err(internal_error(format!(
+            "Failed to enable two-factor authentication: {e}"
+        ))),
+    }
+}
+
+/// 密码校验通过后判断是否需要两步验证，需要时返回携带第二步令牌的响应
+pub(super) async fn login_challenge(
+    storage: &Arc<dyn Storage>,
+    config: &AppConfig,
+    user: &User,
+    remember_me: bool,
+) -> Option<HttpResponse> {
+    match pending_challenge(storage, config, user, remember_me).await {
+        Ok(Some(challenge)) => Some(HttpResponse::Ok().json(ApiResponse::success(
+            challenge,
+            "Two-factor authentication required",
+        ))),
+        Ok(None) => None,
+        Err(response) => Some(response),
+    }
+}
+
+/// 判断是否需要两步验证，需要时签发第二步令牌
+pub(super) async fn pending_challenge(
+    storage: &Arc<dyn Storage>,
+    config: &AppConfig,
+    user: &User,
+    remember_me: bool,
+) -> std::result::Result<Option<MfaChallengeResponse>, HttpResponse> {
+    let mfa = match storage.get_user_mfa(user.id).await {
+        Ok(mfa) => mfa,
+        Err(e) => return Err(internal_error(format!("Login failed: {e}"))),
+    };
+
+    let stage = if mfa.is_some_and(|mfa| mfa.enabled) {
+        MfaStage::Verify
+    } else if mfa_required(config, user) {
+        MfaStage::Enroll
+    } else {
+        return Ok(None);
+    };
+
+    let expiry = chrono::Duration::minutes(config.mfa.pending_token_expiry);
+    match MfaTokenUtils::generate(user, stage, remember_me, expiry) {
+        Ok(mfa_token) => Ok(Some(MfaChallengeResponse {
+            mfa_required: true,
+            mfa_token,
+            stage,
+            expires_in: expiry.num_seconds(),
+        })),
+        Err(e) => {
+            error!("Failed to generate MFA token: {}", e);
+            Err(internal_error("Login failed, unable to generate token"))
+        }
+    }
+}
+
+// 解析第二步令牌并加载对应用户，令牌无效、用户不存在或已停用时返回错误响应
+async fn load_pending_user(
+    storage: &Arc<dyn Storage>,
+    mfa_token: &str,
+) -> std::result::Result<(User, bool, MfaStage), HttpResponse> {
+    let Ok(claims) = MfaTokenUtils::decode(mfa_token) else {
+        return Err(mfa_token_invalid());
+    };
+    let Ok(user_id) = claims.sub.parse::<i64>() else {
+        return Err(mfa_token_invalid());
+    };
+
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) if MfaTokenUtils::matches_user(&claims, &user) => user,
+        Ok(_) => return Err(mfa_token_invalid()),
+        Err(e) => return Err(internal_error(format!("Login failed: {e}"))),
+    };
+
+    if user.status != UserStatus::Active {
+        return Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::AccountNotActive,
+            "Account is not active",
+        )));
+    }
+
+    Ok((user, claims.data.remember_me, claims.data.stage))
+}
+
+// 登录第二步：校验验证码或恢复码，角色要求两步验证时同时完成绑定
+pub async fn handle_mfa_login(
+    service: &AuthService,
+    login_request: MfaLoginRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+    let config = service.get_config();
+
+    let (user, remember_me, stage) =
+        match load_pending_user(&storage, &login_request.mfa_token).await {
+            Ok(pending) => pending,
+            Err(response) => return Ok(response),
+        };
+
+    let ip_address = client_ip(request).map_or_else(|| "unknown".to_string(), |ip| ip.to_string());
+    let ip_subject = LoginSubject::Ip(&ip_address);
+    let user_subject = LoginSubject::User(user.id);
+
+    // 验证码错误与密码错误共用失败计数和锁定
+    if let Some(response) = check_login_block(cache.as_ref(), &user_subject).await {
+        return Ok(response);
+    }
+
+    let mfa = match storage.get_user_mfa(user.id).await {
+        Ok(mfa) => mfa,
+        Err(e) => return Ok(internal_error(format!("Login failed: {e}"))),
+    };
+
+    let recovery_codes = match mfa {
+        Some(mfa) if mfa.enabled => {
+            match verify_second_factor(&storage, config, &mfa, &login_request.code).await {
+                Ok(true) => None,
+                Ok(false) => {
+                    return Ok(login_failed(
+                        cache.as_ref(),
+                        &config.auth,
+                        &ip_subject,
+                        Some(&user_subject),
+                        ErrorCode::MfaCodeInvalid,
+                        "Invalid verification code",
+                    )
+                    .await);
+                }
+                Err(e) => return Ok(internal_error(format!("Failed to verify code: {e}"))),
+            }
+        }
+        Some(mfa) if stage == MfaStage::Enroll => {
+            if !is_totp_code(&login_request.code) {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                    ErrorCode::MfaCodeInvalid,
+                    "Invalid verification code",
+                )));
+            }
+            match confirm_setup(&storage, config, &mfa, &login_request.code).await {
+                Ok(codes) => {
+                    info!("User {} enabled two-factor authentication", user.username);
+                    Some(codes)
+                }
+                Err(response) => return Ok(response),
+            }
+        }
+        // 绑定阶段尚未获取密钥
+        None if stage == MfaStage::Enroll => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaNotEnabled,
+                "Two-factor setup has not been started",
+            )));
+        }
+        // 签发令牌后两步验证已被关闭或重置，需重新登录
+        _ => return Ok(mfa_token_invalid()),
+    };
+
+    lockout::clear_failures(cache.as_ref(), &user_subject).await;
+
+    Ok(issue_login_tokens(service, request, user, remember_me, recovery_codes).await)
+}
+
+// 登录第二步的绑定：角色要求两步验证但尚未启用时，获取验证器绑定信息
+pub async fn handle_mfa_login_setup(
+    service: &AuthService,
+    setup_request: MfaLoginSetupRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let user = match load_pending_user(&storage, &setup_request.mfa_token).await {
+        Ok((user, _, MfaStage::Enroll)) => user,
+        Ok(_) => return Ok(mfa_token_invalid()),
+        Err(response) => return Ok(response),
+    };
+
+    match storage.get_user_mfa(user.id).await {
+        Ok(Some(mfa)) if mfa.enabled => {
+            Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+                ErrorCode::MfaAlreadyEnabled,
+                "Two-factor authentication is already enabled",
+            )))
+        }
+        Ok(_) => Ok(start_setup(&storage, config, &user).await),
+        Err(e) => Ok(internal_error(format!(
+            "Failed to start two-factor setup: {e}"
+        ))),
+    }
+}
+
+// 查看当前用户的两步验证状态
+pub async fn handle_mfa_status(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let Some(user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(unauthorized());
+    };
+
+    let enabled = match storage.get_user_mfa(user.id).await {
+        Ok(mfa) => mfa.is_some_and(|mfa| mfa.enabled),
+        Err(e) => return Ok(internal_error(format!("Failed to query MFA status: {e}"))),
+    };
+    let recovery_codes_remaining = if enabled {
+        match storage.list_unused_recovery_codes(user.id).await {
+            Ok(codes) => codes.len(),
+            Err(e) => return Ok(internal_error(format!("Failed to query MFA status: {e}"))),
+        }
+    } else {
+        0
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        MfaStatusResponse {
+            enabled,
+            required: mfa_required(config, &user),
+            recovery_codes_remaining,
+        },
+        "Two-factor status retrieved successfully",
+    )))
+}
+
+// 开始绑定验证器
+pub async fn handle_mfa_setup(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let Some(user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(unauthorized());
+    };
+
+    match storage.get_user_mfa(user.id).await {
+        Ok(Some(mfa)) if mfa.enabled => {
+            Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+                ErrorCode::MfaAlreadyEnabled,
+                "Two-factor authentication is already enabled",
+            )))
+        }
+        Ok(_) => Ok(start_setup(&storage, config, &user).await),
+        Err(e) => Ok(internal_error(format!(
+            "Failed to start two-factor setup: {e}"
+        ))),
+    }
+}
+
+// 使用验证码确认绑定并启用两步验证
+pub async fn handle_mfa_enable(
+    service: &AuthService,
+    code_request: MfaCodeRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let Some(user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(unauthorized());
+    };
+
+    let mfa = match storage.get_user_mfa(user.id).await {
+        Ok(Some(mfa)) if mfa.enabled => {
+            return Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+                ErrorCode::MfaAlreadyEnabled,
+                "Two-factor authentication is already enabled",
+            )));
+        }
+        Ok(Some(mfa)) => mfa,
+        Ok(None) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaNotEnabled,
+                "Two-factor setup has not been started",
+            )));
+        }
+        Err(e) => {
+            return Ok(internal_error(format!(
+                "Failed to enable two-factor authentication: {e}"
+            )));
+        }
+    };
+
+    match confirm_setup(&storage, config, &mfa, &code_request.code).await {
+        Ok(recovery_codes) => {
+            info!("User {} enabled two-factor authentication", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                MfaRecoveryCodesResponse { recovery_codes },
+                "Two-factor authentication enabled, store the recovery codes in a safe place",
+            )))
+        }
+        Err(response) => Ok(response),
+    }
+}
+
+// 关闭两步验证，需要同时提供密码与验证码（或恢复码）
+pub async fn handle_mfa_disable(
+    service: &AuthService,
+    disable_request: DisableMfaRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+
+    // 缓存中的用户信息不含密码哈希，需从存储中读取
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::UserNotFound,
+                "User not found",
+            )));
+        }
+        Err(e) => {
+            return Ok(internal_error(format!(
+                "Failed to disable two-factor authentication: {e}"
+            )));
+        }
+    };
+
+    if mfa_required(config, &user) {
+        return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::MfaRequired,
+            "Two-factor authentication is required for your role",
+        )));
+    }
+
+    if !verify_password(&disable_request.password, &user.password_hash) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::AuthFailed,
+            "Password is incorrect",
+        )));
+    }
+
+    let mfa = match storage.get_user_mfa(user.id).await {
+        Ok(Some(mfa)) if mfa.enabled => mfa,
+        Ok(_) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaNotEnabled,
+                "Two-factor authentication is not enabled",
+            )));
+        }
+        Err(e) => {
+            return Ok(internal_error(format!(
+                "Failed to disable two-factor authentication: {e}"
+            )));
+        }
+    };
+
+    match verify_second_factor(&storage, config, &mfa, &disable_request.code).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaCodeInvalid,
+                "Invalid verification code",
+            )));
+        }
+        Err(e) => return Ok(internal_error(format!("Failed to verify code: {e}"))),
+    }
+
+    if let Err(e) = storage.delete_user_mfa(user.id).await {
+        return Ok(internal_error(format!(
+            "Failed to disable two-factor authentication: {e}"
+        )));
+    }
+
+    info!("User {} disabled two-factor authentication", user.username);
+    Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+        "Two-factor authentication disabled",
+    )))
+}
+
+// 重新生成恢复码，旧恢复码全部作废，需使用验证器中的验证码确认
+pub async fn handle_regenerate_recovery_codes(
+    service: &AuthService,
+    code_request: MfaCodeRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = service.get_config();
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+
+    let mfa = match storage.get_user_mfa(user_id).await {
+        Ok(Some(mfa)) if mfa.enabled => mfa,
+        Ok(_) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaNotEnabled,
+                "Two-factor authentication is not enabled",
+            )));
+        }
+        Err(e) => {
+            return Ok(internal_error(format!(
+                "Failed to regenerate recovery codes: {e}"
+            )));
+        }
+    };
+
+    match verify_totp(&storage, config, &mfa, &code_request.code).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MfaCodeInvalid,
+                "Invalid verification code",
+            )));
+        }
+        Err(e) => return Ok(internal_error(format!("Failed to verify code: {e}"))),
+    }
+
+    let (recovery_codes, hashes) =
+        match generate_recovery_codes(config.mfa.recovery_code_count).await {
+            Ok(codes) => codes,
+            Err(e) => {
+                return Ok(internal_error(format!(
+                    "Failed to generate recovery codes: {e}"
+                )));
+            }
+        };
+
+    if let Err(e) = storage.replace_recovery_codes(user_id, &hashes).await {
+        return Ok(internal_error(format!(
+            "Failed to regenerate recovery codes: {e}"
+        )));
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        MfaRecoveryCodesResponse { recovery_codes },
+        "Recovery codes regenerated, previous codes are no longer valid",
+    )))
+}
@@ -1,10 +1,24 @@
+pub mod api_tokens;
+pub mod authenticator;
+pub mod avatar;
+pub mod email_verification;
+pub mod ldap;
+pub mod lockout;
 pub mod login;
+pub mod logout;
+pub mod mfa;
+pub mod oidc;
+pub mod password_reset;
+pub mod profile;
 pub mod register;
+pub mod sessions;
 pub mod token;
 
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 use std::sync::Arc;
 
+use crate::cache::ObjectCache;
+use crate::mailer::Mailer;
 use crate::repository::Storage;
 use crate::system::app_config::AppConfig;
 
@@ -29,6 +43,22 @@ impl AuthService {
         }
     }
 
+    pub(crate) fn get_cache(&self, request: &HttpRequest) -> Arc<dyn ObjectCache> {
+        request
+            .app_data::<actix_web::web::Data<Arc<dyn ObjectCache>>>()
+            .expect("Cache not found in app data")
+            .get_ref()
+            .clone()
+    }
+
+    pub(crate) fn get_mailer(&self, request: &HttpRequest) -> Arc<dyn Mailer> {
+        request
+            .app_data::<actix_web::web::Data<Arc<dyn Mailer>>>()
+            .expect("Mailer not found in app data")
+            .get_ref()
+            .clone()
+    }
+
     pub(crate) fn get_config(&self) -> &AppConfig {
         AppConfig::get()
     }
@@ -45,10 +75,10 @@ impl AuthService {
     // 用户注册
     pub async fn register(
         &self,
-        create_request: crate::models::users::requests::CreateUserRequest,
+        register_request: crate::models::auth::RegisterRequest,
         request: &HttpRequest,
     ) -> ActixResult<HttpResponse> {
-        register::handle_register(self, create_request, request).await
+        register::handle_register(self, register_request, request).await
     }
 
     // 刷新令牌
@@ -56,6 +86,195 @@ impl AuthService {
         token::handle_refresh_token(self, request).await
     }
 
+    // 登出当前会话
+    pub async fn logout(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        logout::handle_logout(self, request).await
+    }
+
+    // 登出所有设备
+    pub async fn logout_all(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        logout::handle_logout_all(self, request).await
+    }
+
+    // 列出当前用户的登录会话
+    pub async fn list_sessions(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        sessions::handle_list_sessions(self, request).await
+    }
+
+    // 终止当前用户的指定会话
+    pub async fn revoke_session(
+        &self,
+        request: &HttpRequest,
+        session_id: &str,
+    ) -> ActixResult<HttpResponse> {
+        sessions::handle_revoke_session(self, request, session_id).await
+    }
+
+    // 验证邮箱
+    pub async fn verify_email(
+        &self,
+        verify_request: crate::models::auth::VerifyEmailRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        email_verification::handle_verify_email(self, verify_request, request).await
+    }
+
+    // 重新发送邮箱验证邮件
+    pub async fn resend_verification_email(
+        &self,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        email_verification::handle_resend_verification(self, request).await
+    }
+
+    // 忘记密码，发送密码重置邮件
+    pub async fn forgot_password(
+        &self,
+        forgot_request: crate::models::auth::ForgotPasswordRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        password_reset::handle_forgot_password(self, forgot_request, request).await
+    }
+
+    // 通过邮件链接重置密码
+    pub async fn reset_password(
+        &self,
+        reset_request: crate::models::auth::ResetPasswordRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        password_reset::handle_reset_password(self, reset_request, request).await
+    }
+
+    // 更新当前用户的个人资料
+    pub async fn update_profile(
+        &self,
+        update_request: crate::models::auth::requests::UpdateProfileRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        profile::handle_update_profile(self, update_request, request).await
+    }
+
+    // 修改当前用户的密码
+    pub async fn change_password(
+        &self,
+        change_request: crate::models::auth::requests::ChangePasswordRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        profile::handle_change_password(self, change_request, request).await
+    }
+
+    // 登录第二步：校验两步验证码
+    pub async fn mfa_login(
+        &self,
+        login_request: crate::models::auth::requests::MfaLoginRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_login(self, login_request, request).await
+    }
+
+    // 登录第二步：绑定验证器
+    pub async fn mfa_login_setup(
+        &self,
+        setup_request: crate::models::auth::requests::MfaLoginSetupRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_login_setup(self, setup_request, request).await
+    }
+
+    // 查看两步验证状态
+    pub async fn mfa_status(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_status(self, request).await
+    }
+
+    // 开始绑定验证器
+    pub async fn mfa_setup(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_setup(self, request).await
+    }
+
+    // 启用两步验证
+    pub async fn mfa_enable(
+        &self,
+        code_request: crate::models::auth::requests::MfaCodeRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_enable(self, code_request, request).await
+    }
+
+    // 关闭两步验证
+    pub async fn mfa_disable(
+        &self,
+        disable_request: crate::models::auth::requests::DisableMfaRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::handle_mfa_disable(self, disable_request, request).await
+    }
+
+    // 重新生成恢复码
+    pub async fn regenerate_recovery_codes(
+        &self,
+        code_request: crate::models::auth::requests::MfaCodeRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::handle_regenerate_recovery_codes(self, code_request, request).await
+    }
+
+    // 列出当前用户的个人访问令牌
+    pub async fn list_api_tokens(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        api_tokens::handle_list_api_tokens(self, request).await
+    }
+
+    // 创建个人访问令牌
+    pub async fn create_api_token(
+        &self,
+        create_request: crate::models::auth::requests::CreateApiTokenRequest,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        api_tokens::handle_create_api_token(self, create_request, request).await
+    }
+
+    // 吊销个人访问令牌
+    pub async fn revoke_api_token(
+        &self,
+        request: &HttpRequest,
+        token_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        api_tokens::handle_revoke_api_token(self, request, token_id).await
+    }
+
+    // 列出可用于单点登录的身份提供方
+    pub async fn list_oidc_providers(&self) -> ActixResult<HttpResponse> {
+        oidc::handle_list_providers(self).await
+    }
+
+    // 发起单点登录，跳转到身份提供方
+    pub async fn oidc_login(
+        &self,
+        request: &HttpRequest,
+        provider: &str,
+        query: crate::models::auth::requests::OidcLoginQuery,
+    ) -> ActixResult<HttpResponse> {
+        oidc::handle_oidc_login(self, request, provider, query).await
+    }
+
+    // 身份提供方回调，完成单点登录
+    pub async fn oidc_callback(
+        &self,
+        request: &HttpRequest,
+        provider: &str,
+        query: crate::models::auth::requests::OidcCallbackQuery,
+    ) -> ActixResult<HttpResponse> {
+        oidc::handle_oidc_callback(self, request, provider, query).await
+    }
+
+    // 上传当前用户的头像
+    pub async fn upload_avatar(
+        &self,
+        request: &HttpRequest,
+        payload: actix_multipart::Multipart,
+    ) -> ActixResult<HttpResponse> {
+        avatar::handle_upload_avatar(self, request, payload).await
+    }
+
     // 验证令牌
     pub async fn verify_token(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
         token::handle_verify_token(self, request).await
@@ -0,0 +1,538 @@
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, http::header};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::cache::traits::{CacheResult, TypedObjectCache};
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::{
+        entities::MfaStage,
+        requests::{OidcCallbackQuery, OidcLoginQuery},
+        responses::{OidcProviderListResponse, OidcProviderResponse},
+    },
+    users::{
+        entities::{User, UserProfile, UserStatus},
+        requests::CreateUserRequest,
+    },
+};
+use crate::repository::Storage;
+use crate::system::app_config::{AppConfig, OidcProviderConfig};
+use crate::utils::oidc::{self, IdTokenClaims};
+use crate::utils::password::hash_password;
+use crate::utils::random_code::generate_random_code;
+use crate::utils::validate::{validate_email, validate_username};
+
+use super::AuthService;
+use super::login::create_login_tokens;
+use super::mfa;
+
+// 把登录请求绑定到发起登录的浏览器，防止攻击者让受害者登录到攻击者的账号
+const STATE_COOKIE: &str = "oidc_state";
+const STATE_COOKIE_PATH: &str = "/api/v1/auth/oidc";
+// 自动生成用户名时尝试的次数
+const USERNAME_ATTEMPTS: usize = 5;
+// 自动创建的用户的随机密码长度，用户需通过找回密码设置本地密码
+pub(super) const RANDOM_PASSWORD_LENGTH: usize = 32;
+
+// 发起登录时保存的状态，过期判断基于时间戳而非缓存 TTL，moka 缓存会忽略单条 TTL
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    provider: String,
+    nonce: String,
+    code_verifier: String,
+    remember_me: bool,
+    created_at: i64,
+}
+
+fn state_cache_key(state: &str) -> String {
+    format!("oidc_state:{state}")
+}
+
+fn redirect_uri(config: &AppConfig, provider_name: &str) -> String {
+    format!(
+        "{}/api/v1/auth/oidc/{provider_name}/callback",
+        config.oidc.redirect_base_url.trim_end_matches('/')
+    )
+}
+
+fn state_cookie(value: &str, max_age: i64) -> Cookie<'static> {
+    // 身份提供方跳转回来属于跨站的顶级导航，SameSite=Strict 的 cookie 不会被发送
+    Cookie::build(STATE_COOKIE, value.to_string())
+        .path(STATE_COOKIE_PATH)
+        .max_age(actix_web::cookie::time::Duration::seconds(max_age))
+        .same_site(SameSite::Lax)
+        .http_only(true)
+        .secure(AppConfig::get().is_production())
+        .finish()
+}
+
+fn find_provider<'a>(
+    config: &'a AppConfig,
+    provider_name: &str,
+) -> Result<&'a OidcProviderConfig, HttpResponse> {
+    config.oidc.providers.get(provider_name).ok_or_else(|| {
+        HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::OidcProviderNotFound,
+            "Identity provider not found",
+        ))
+    })
+}
+
+fn state_invalid() -> HttpResponse {
+    HttpResponse::BadRequest()
+        .cookie(state_cookie("", 0))
+        .json(ApiResponse::error_empty(
+            ErrorCode::OidcStateInvalid,
+            "Login request is invalid or has expired, please try again",
+        ))
+}
+
+fn login_failed(status: actix_web::http::StatusCode, message: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status)
+        .cookie(state_cookie("", 0))
+        .json(ApiResponse::error_empty(
+            ErrorCode::OidcLoginFailed,
+            message.into(),
+        ))
+}
+
+fn internal_error(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        message.into(),
+    ))
+}
+
+pub async fn handle_list_providers(service: &AuthService) -> ActixResult<HttpResponse> {
+    let config = service.get_config();
+
+    let mut items: Vec<OidcProviderResponse> = config
+        .oidc
+        .providers
+        .iter()
+        .map(|(name, provider)| OidcProviderResponse {
+            name: name.clone(),
+            display_name: if provider.display_name.is_empty() {
+                name.clone()
+            } else {
+                provider.display_name.clone()
+            },
+            login_url: format!("{STATE_COOKIE_PATH}/{name}/login"),
+        })
+        .collect();
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        OidcProviderListResponse { items },
+        "Identity providers retrieved successfully",
+    )))
+}
+
+// 生成 state、nonce 与 PKCE 参数并跳转到身份提供方的授权页面
+pub async fn handle_oidc_login(
+    service: &AuthService,
+    request: &HttpRequest,
+    provider_name: &str,
+    query: OidcLoginQuery,
+) -> ActixResult<HttpResponse> {
+    let cache = service.get_cache(request);
+    let config = service.get_config();
+
+    let provider = match find_provider(config, provider_name) {
+        Ok(provider) => provider,
+        Err(response) => return Ok(response),
+    };
+
+    let metadata = match oidc::metadata(provider).await {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            error!("Failed to load OIDC metadata for {}: {}", provider_name, e);
+            return Ok(login_failed(
+                actix_web::http::StatusCode::BAD_GATEWAY,
+                "Identity provider is unavailable",
+            ));
+        }
+    };
+
+    let state = oidc::random_token();
+    let nonce = oidc::random_token();
+    let (code_verifier, code_challenge) = oidc::generate_pkce();
+
+    let location = match oidc::authorization_url(
+        &metadata,
+        provider,
+        &redirect_uri(config, provider_name),
+        &state,
+        &nonce,
+        &code_challenge,
+    ) {
+        Ok(location) => location,
+        Err(e) => {
+            error!("Invalid OIDC configuration for {}: {}", provider_name, e);
+            return Ok(internal_error("Identity provider is misconfigured"));
+        }
+    };
+
+    let pending = PendingLogin {
+        provider: provider_name.to_string(),
+        nonce,
+        code_verifier,
+        remember_me: query.remember_me,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    cache
+        .insert(
+            state_cache_key(&state),
+            pending,
+            config.oidc.state_expiry as u64,
+        )
+        .await;
+
+    Ok(HttpResponse::Found()
+        .insert_header((header::LOCATION, location))
+        .cookie(state_cookie(&state, config.oidc.state_expiry))
+        .finish())
+}
+
+// 校验 state 后使用授权码换取并验证 id_token，关联或创建本地用户后签发登录令牌
+pub async fn handle_oidc_callback(
+    service: &AuthService,
+    request: &HttpRequest,
+    provider_name: &str,
+    query: OidcCallbackQuery,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+    let config = service.get_config();
+
+    let provider = match find_provider(config, provider_name) {
+        Ok(provider) => provider,
+        Err(response) => return Ok(response),
+    };
+
+    // 0. state 只能使用一次，无论后续是否成功都先删除
+    let Some(state) = query.state.as_deref() else {
+        return Ok(state_invalid());
+    };
+    let pending = match cache.get::<PendingLogin>(&state_cache_key(state)).await {
+        CacheResult::Found(pending) => pending,
+        _ => return Ok(state_invalid()),
+    };
+    cache.remove(&state_cache_key(state)).await;
+
+    let state_cookie_matches = request
+        .cookie(STATE_COOKIE)
+        .is_some_and(|cookie| cookie.value() == state);
+    if !state_cookie_matches
+        || pending.provider != provider_name
+        || chrono::Utc::now().timestamp() - pending.created_at > config.oidc.state_expiry
+    {
+        return Ok(state_invalid());
+    }
+
+    // 1. 用户在身份提供方拒绝授权或授权失败
+    if let Some(error) = &query.error {
+        info!("OIDC login via {} was rejected: {}", provider_name, error);
+        return Ok(login_failed(
+            actix_web::http::StatusCode::UNAUTHORIZED,
+            format!(
+                "Identity provider returned an error: {}",
+                query.error_description.as_deref().unwrap_or(error)
+            ),
+        ));
+    }
+    let Some(code) = query.code.as_deref() else {
+        return Ok(state_invalid());
+    };
+
+    // 2. 交换授权码并验证 id_token
+    let claims = match exchange_and_verify(provider_name, provider, code, &pending, config).await {
+        Ok(claims) => claims,
+        Err(response) => return Ok(response),
+    };
+
+    // 3. 查找已关联的用户，或按邮箱关联、自动创建用户
+    let user = match resolve_user(&storage, provider_name, provider, &claims).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    if user.status != UserStatus::Active {
+        return Ok(HttpResponse::Forbidden().cookie(state_cookie("", 0)).json(
+            ApiResponse::error_empty(
+                ErrorCode::AccountNotActive,
+                "Account is not active or is pending approval",
+            ),
+        ));
+    }
+
+    // 4. 本地启用或必须启用两步验证时，与密码登录一样先完成第二步
+    let frontend_url = config.oidc.frontend_redirect_url.as_str();
+    match mfa::pending_challenge(&storage, config, &user, pending.remember_me).await {
+        Ok(Some(challenge)) if frontend_url.is_empty() => {
+            return Ok(HttpResponse::Ok()
+                .cookie(state_cookie("", 0))
+                .json(ApiResponse::success(
+                    challenge,
+                    "Two-factor authentication required",
+                )));
+        }
+        Ok(Some(challenge)) => {
+            // 放在 fragment 中，不会发送到前端服务器或出现在 Referer 中
+            let fragment = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("mfa_token", &challenge.mfa_token)
+                .append_pair(
+                    "stage",
+                    match challenge.stage {
+                        MfaStage::Verify => "verify",
+                        MfaStage::Enroll => "enroll",
+                    },
+                )
+                .append_pair("expires_in", &challenge.expires_in.to_string())
+                .finish();
+            return Ok(HttpResponse::Found()
+                .insert_header((header::LOCATION, format!("{frontend_url}#{fragment}")))
+                .cookie(state_cookie("", 0))
+                .finish());
+        }
+        Ok(None) => {}
+        Err(response) => return Ok(response),
+    }
+
+    // 5. 签发令牌，配置了前端地址时只设置 refresh token cookie 并跳转，前端再调用刷新接口获取访问令牌
+    let (response, refresh_cookie) =
+        match create_login_tokens(service, request, user, pending.remember_me, None).await {
+            Ok(tokens) => tokens,
+            Err(response) => return Ok(response),
+        };
+    info!(
+        "User {} logged in via identity provider {}",
+        response.user.username, provider_name
+    );
+
+    if frontend_url.is_empty() {
+        Ok(HttpResponse::Ok()
+            .cookie(refresh_cookie)
+            .cookie(state_cookie("", 0))
+            .json(ApiResponse::success(response, "Login successful")))
+    } else {
+        Ok(HttpResponse::Found()
+            .insert_header((header::LOCATION, frontend_url))
+            .cookie(refresh_cookie)
+            .cookie(state_cookie("", 0))
+            .finish())
+    }
+}
+
+async fn exchange_and_verify(
+    provider_name: &str,
+    provider: &OidcProviderConfig,
+    code: &str,
+    pending: &PendingLogin,
+    config: &AppConfig,
+) -> Result<IdTokenClaims, HttpResponse> {
+    let bad_gateway =
+        |message: &str| login_failed(actix_web::http::StatusCode::BAD_GATEWAY, message);
+
+    let metadata = oidc::metadata(provider).await.map_err(|e| {
+        error!("Failed to load OIDC metadata for {}: {}", provider_name, e);
+        bad_gateway("Identity provider is unavailable")
+    })?;
+
+    let tokens = oidc::exchange_code(
+        &metadata,
+        provider,
+        code,
+        &redirect_uri(config, provider_name),
+        &pending.code_verifier,
+    )
+    .await
+    .map_err(|e| {
+        error!("OIDC code exchange with {} failed: {}", provider_name, e);
+        bad_gateway("Failed to exchange authorization code")
+    })?;
+
+    let Some(id_token) = tokens.id_token else {
+        error!("OIDC token response from {} has no id_token", provider_name);
+        return Err(bad_gateway("Identity provider did not return an id_token"));
+    };
+
+    oidc::verify_id_token(&metadata, provider, &id_token, &pending.nonce)
+        .await
+        .map_err(|e| {
+            warn!("OIDC id_token from {} rejected: {}", provider_name, e);
+            login_failed(
+                actix_web::http::StatusCode::UNAUTHORIZED,
+                "Identity token verification failed",
+            )
+        })
+}
+
+// 按外部身份、已验证邮箱的顺序查找用户，均未找到时按配置自动创建
+async fn resolve_user(
+    storage: &Arc<dyn Storage>,
+    provider_name: &str,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+) -> Result<User, HttpResponse> {
+    let database_error = |e: crate::errors::HWSystemError| {
+        error!("OIDC login failed: {}", e);
+        internal_error(format!("Login failed: {e}"))
+    };
+
+    let email = claims
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| !email.is_empty());
+    let verified_email = email.filter(|_| claims.email_verified());
+
+    // 1. 已关联的外部身份
+    if let Some(identity) = storage
+        .get_user_identity(provider_name, &claims.sub)
+        .await
+        .map_err(database_error)?
+    {
+        let _ = storage.touch_user_identity(identity.id, email).await;
+        return storage
+            .get_user_by_id(identity.user_id)
+            .await
+            .map_err(database_error)?
+            .ok_or_else(|| {
+                HttpResponse::NotFound().json(ApiResponse::error_empty(
+                    ErrorCode::UserNotFound,
+                    "User not found",
+                ))
+            });
+    }
+
+    // 2. 身份提供方确认过的邮箱与已有用户一致时关联，未验证的邮箱可能被他人冒用
+    if provider.link_by_email
+        && let Some(email) = verified_email
+        && let Some(user) = storage
+            .get_user_by_email(email)
+            .await
+            .map_err(database_error)?
+    {
+        storage
+            .create_user_identity(user.id, provider_name, &claims.sub, Some(email))
+            .await
+            .map_err(database_error)?;
+        info!(
+            "Linked {} identity {} to user {} by email",
+            provider_name, claims.sub, user.username
+        );
+        return Ok(user);
+    }
+
+    // 3. 自动创建用户
+    let not_linked = |message: &str| {
+        HttpResponse::Forbidden()
+            .cookie(state_cookie("", 0))
+            .json(ApiResponse::error_empty(
+                ErrorCode::OidcAccountNotLinked,
+                message.to_string(),
+            ))
+    };
+    if !provider.auto_provision {
+        return Err(not_linked(
+            "This account is not linked to any user, please contact the administrator",
+        ));
+    }
+    let Some(email) = email.filter(|email| validate_email(email).is_ok()) else {
+        return Err(not_linked(
+            "Identity provider did not return a valid email, unable to create user",
+        ));
+    };
+    if storage
+        .get_user_by_email(email)
+        .await
+        .map_err(database_error)?
+        .is_some()
+    {
+        return Err(HttpResponse::Conflict().cookie(state_cookie("", 0)).json(
+            ApiResponse::error_empty(
+                ErrorCode::UserEmailAlreadyExists,
+                "Email already belongs to another user, please login and link the account",
+            ),
+        ));
+    }
+
+    let username_source = claims
+        .string_claim(&provider.username_claim)
+        .unwrap_or_else(|| email.split('@').next().unwrap_or_default());
+    let username = available_username(storage, username_source)
+        .await
+        .map_err(database_error)?;
+    let password =
+        hash_password(&generate_random_code(RANDOM_PASSWORD_LENGTH)).map_err(database_error)?;
+
+    let user = storage
+        .create_user(CreateUserRequest {
+            username: username.clone(),
+            email: email.to_string(),
+            password,
+            role: provider.default_role.clone(),
+            status: Some(UserStatus::Active),
+            profile: UserProfile {
+                profile_name: claims.name.clone().unwrap_or_else(|| username.clone()),
+                avatar_url: None,
+            },
+        })
+        .await
+        .map_err(database_error)?;
+
+    if verified_email.is_some() {
+        let _ = storage.mark_email_verified(user.id, email).await;
+    }
+    storage
+        .create_user_identity(user.id, provider_name, &claims.sub, Some(email))
+        .await
+        .map_err(database_error)?;
+
+    info!(
+        "Provisioned user {} for {} identity {}",
+        user.username, provider_name, claims.sub
+    );
+
+    // 重新读取以获取 email_verified 的最新值
+    Ok(storage
+        .get_user_by_id(user.id)
+        .await
+        .map_err(database_error)?
+        .unwrap_or(user))
+}
+
+// 优先使用外部账号的用户名，不合法或已被占用时追加随机后缀
+pub(super) async fn available_username(
+    storage: &Arc<dyn Storage>,
+    source: &str,
+) -> crate::errors::Result<String> {
+    let base: String = source
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .take(16)
+        .collect();
+
+    if validate_username(&base).is_ok() && storage.get_user_by_username(&base).await?.is_none() {
+        return Ok(base);
+    }
+
+    let prefix: String = if base.is_empty() {
+        "user".to_string()
+    } else {
+        base.chars().take(10).collect()
+    };
+    for _ in 0..USERNAME_ATTEMPTS {
+        let candidate = format!("{prefix}_{}", generate_random_code(5).to_lowercase());
+        if storage.get_user_by_username(&candidate).await?.is_none() {
+            return Ok(candidate);
+        }
+    }
+
+    Err(crate::errors::HWSystemError::validation(
+        "Unable to generate an available username",
+    ))
+}
@@ -0,0 +1,178 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::errors::{HWSystemError, Result};
+use crate::mailer::{MailMessage, Mailer};
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::{ForgotPasswordRequest, ResetPasswordRequest},
+    users::{
+        entities::{User, UserStatus},
+        requests::UpdateUserRequest,
+    },
+};
+use crate::system::app_config::AppConfig;
+use crate::utils::email_token::{EmailTokenPurpose, EmailTokenUtils};
+use crate::utils::jwt::JwtUtils;
+use crate::utils::password::hash_password;
+use crate::utils::validate::validate_password;
+
+use super::AuthService;
+use super::lockout::{self, LoginSubject};
+
+async fn send_password_reset_email(mailer: &dyn Mailer, user: &User) -> Result<()> {
+    let config = AppConfig::get();
+    let token = EmailTokenUtils::generate(
+        user,
+        EmailTokenPurpose::ResetPassword,
+        chrono::Duration::minutes(config.auth.password_reset_expiry),
+    )
+    .map_err(|e| HWSystemError::mail(format!("Failed to generate password reset token: {e}")))?;
+
+    let link = format!(
+        "{}/reset-password?token={}",
+        config.mail.link_base_url.trim_end_matches('/'),
+        token
+    );
+
+    mailer
+        .send(MailMessage {
+            to: user.email.clone(),
+            subject: format!("[{}] 重置你的密码", config.app.system_name),
+            body: format!(
+                "{}，你好：\n\n我们收到了重置你账号密码的请求，请打开以下链接设置新密码，链接在 {} 分钟内有效且只能使用一次：\n\n{}\n\n如果这不是你本人的操作，请忽略此邮件，你的密码不会被修改。",
+                user.profile.profile_name, config.auth.password_reset_expiry, link
+            ),
+        })
+        .await
+}
+
+pub async fn handle_forgot_password(
+    service: &AuthService,
+    forgot_request: ForgotPasswordRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let mailer = service.get_mailer(request);
+
+    match storage.get_user_by_email(&forgot_request.email).await {
+        Ok(Some(user)) if user.status == UserStatus::Active => {
+            // 后台发送邮件，避免通过响应时间判断邮箱是否已注册
+            actix_web::rt::spawn(send_reset_email_in_background(mailer, user));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Failed to look up user for password reset: {}", e);
+        }
+    }
+
+    // 无论邮箱是否存在都返回相同的响应，防止枚举已注册邮箱
+    Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+        "If the email is registered, a password reset link has been sent",
+    )))
+}
+
+async fn send_reset_email_in_background(mailer: Arc<dyn Mailer>, user: User) {
+    match send_password_reset_email(mailer.as_ref(), &user).await {
+        Ok(()) => info!("Password reset email sent to user {}", user.username),
+        Err(e) => error!("Failed to send password reset email: {}", e),
+    }
+}
+
+pub async fn handle_reset_password(
+    service: &AuthService,
+    reset_request: ResetPasswordRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    if let Err(msg) = validate_password(&reset_request.new_password) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UserPasswordInvalid,
+            msg,
+        )));
+    }
+
+    let invalid_token = || {
+        HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::EmailTokenInvalid,
+            "Password reset link is invalid or has expired",
+        ))
+    };
+
+    let Ok(claims) =
+        EmailTokenUtils::decode(&reset_request.token, EmailTokenPurpose::ResetPassword)
+    else {
+        return Ok(invalid_token());
+    };
+    let Ok(user_id) = claims.sub.parse::<i64>() else {
+        return Ok(invalid_token());
+    };
+
+    // 令牌绑定签发时的密码哈希，密码修改后令牌即失效
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user))
+            if EmailTokenUtils::matches_user(&claims, &user, EmailTokenPurpose::ResetPassword) =>
+        {
+            user
+        }
+        Ok(_) => return Ok(invalid_token()),
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to reset password: {e}"),
+                )),
+            );
+        }
+    };
+
+    let password_hash = match hash_password(&reset_request.new_password) {
+        Ok(hash) => hash,
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Password hashing failed: {e}"),
+                )),
+            );
+        }
+    };
+
+    let update = UpdateUserRequest {
+        email: None,
+        password: Some(password_hash),
+        role: None,
+        status: None,
+        profile: None,
+    };
+    if let Err(e) = storage.update_user(user.id, update).await {
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::UserUpdateFailed,
+                format!("Failed to reset password: {e}"),
+            )),
+        );
+    }
+
+    // 密码已重置，终止所有已登录的会话
+    if let Err(e) = storage.revoke_user_sessions(user.id).await {
+        error!("Failed to revoke sessions after password reset: {}", e);
+    }
+    JwtUtils::revoke_user_access_tokens(cache.as_ref(), user.id).await;
+    RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+    lockout::clear_failures(cache.as_ref(), &LoginSubject::User(user.id)).await;
+
+    // 能收到重置邮件即证明拥有该邮箱
+    if !user.email_verified {
+        let _ = storage.mark_email_verified(user.id, &user.email).await;
+    }
+
+    info!("Password reset for user {}", user.username);
+    Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+        "Password reset successfully, please login again",
+    )))
+}
@@ -0,0 +1,235 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use tracing::{error, info};
+
+use crate::errors::HWSystemError;
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::{
+        requests::{ChangePasswordRequest, UpdateProfileRequest},
+        responses::UserInfoResponse,
+    },
+    users::{entities::UserProfile, requests::UpdateUserRequest},
+};
+use crate::utils::jwt::JwtUtils;
+use crate::utils::password::{hash_password, verify_password};
+use crate::utils::validate::validate_password;
+
+use super::AuthService;
+use super::lockout::{self, LoginSubject};
+use super::login::{check_login_block, lockout_unavailable};
+
+const MAX_PROFILE_NAME_LENGTH: usize = 64;
+
+// 更新当前用户的个人资料，只允许修改 profile 字段，角色与状态不可自行修改
+pub async fn handle_update_profile(
+    service: &AuthService,
+    update_request: UpdateProfileRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let Some(current_user) = RequireJWT::extract_user_claims(request) else {
+        return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Unauthorized access, please login",
+        )));
+    };
+
+    let Some(profile_name) = update_request.profile_name else {
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(
+            UserInfoResponse { user: current_user },
+            "Profile updated successfully",
+        )));
+    };
+    let profile_name = profile_name.trim().to_string();
+    if profile_name.is_empty() || profile_name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::BadRequest,
+            "Profile name must be between 1 and 64 characters",
+        )));
+    }
+
+    // 缓存中的用户信息可能已过期，头像以存储中的为准，避免覆盖刚上传的头像
+    let avatar_url = match storage.get_user_by_id(current_user.id).await {
+        Ok(Some(user)) => user.profile.avatar_url,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::UserNotFound,
+                "User not found",
+            )));
+        }
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to update profile: {e}"),
+                )),
+            );
+        }
+    };
+
+    let update = UpdateUserRequest {
+        email: None,
+        password: None,
+        role: None,
+        status: None,
+        profile: Some(UserProfile {
+            profile_name,
+            avatar_url,
+        }),
+    };
+
+    match storage.update_user(current_user.id, update).await {
+        Ok(Some(user)) => {
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                UserInfoResponse { user },
+                "Profile updated successfully",
+            )))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::UserUpdateFailed,
+                format!("Failed to update profile: {e}"),
+            )),
+        ),
+    }
+}
+
+// 修改当前用户的密码，成功后终止除当前会话外的所有会话
+pub async fn handle_change_password(
+    service: &AuthService,
+    change_request: ChangePasswordRequest,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let (Some(user_id), Some(claims)) = (
+        RequireJWT::extract_user_id(request),
+        RequireJWT::extract_access_claims(request),
+    ) else {
+        return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Unauthorized access, please login",
+        )));
+    };
+
+    if let Err(msg) = validate_password(&change_request.new_password) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UserPasswordInvalid,
+            msg,
+        )));
+    }
+
+    // 缓存中的用户信息不含密码哈希，需从存储中读取
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::UserNotFound,
+                "User not found",
+            )));
+        }
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to change password: {e}"),
+                )),
+            );
+        }
+    };
+
+    // 当前密码错误与登录失败共用失败计数和锁定，避免借已登录的会话暴力破解密码
+    let config = service.get_config();
+    let user_subject = LoginSubject::User(user.id);
+    if let Some(response) = check_login_block(cache.as_ref(), &user_subject).await {
+        return Ok(response);
+    }
+
+    // Argon2 计算较慢，放到阻塞线程池中执行
+    let current_password = change_request.current_password;
+    let password_hash = user.password_hash.clone();
+    let verified =
+        match web::block(move || verify_password(&current_password, &password_hash)).await {
+            Ok(verified) => verified,
+            Err(e) => {
+                return Ok(
+                    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                        ErrorCode::InternalServerError,
+                        format!("Password verification failed: {e}"),
+                    )),
+                );
+            }
+        };
+    if !verified {
+        if let Err(e) = lockout::record_failure(cache.as_ref(), &config.auth, &user_subject).await {
+            return Ok(lockout_unavailable(e));
+        }
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::AuthFailed,
+            "Current password is incorrect",
+        )));
+    }
+
+    let new_password = change_request.new_password;
+    let hashed = web::block(move || hash_password(&new_password))
+        .await
+        .map_err(|e| HWSystemError::validation(format!("{e}")))
+        .flatten();
+    let password_hash = match hashed {
+        Ok(hash) => hash,
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Password hashing failed: {e}"),
+                )),
+            );
+        }
+    };
+
+    let update = UpdateUserRequest {
+        email: None,
+        password: Some(password_hash),
+        role: None,
+        status: None,
+        profile: None,
+    };
+    if let Err(e) = storage.update_user(user.id, update).await {
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::UserUpdateFailed,
+                format!("Failed to change password: {e}"),
+            )),
+        );
+    }
+    RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+
+    // 终止其他设备上的会话，保留当前会话
+    match storage.list_user_sessions(user.id).await {
+        Ok(sessions) => {
+            for session in sessions
+                .into_iter()
+                .filter(|session| claims.fid.as_deref() != Some(session.id.as_str()))
+            {
+                if let Err(e) = storage.revoke_session(&session.id).await {
+                    error!("Failed to revoke session {}: {}", session.id, e);
+                    continue;
+                }
+                JwtUtils::revoke_session_access_tokens(cache.as_ref(), &session.id).await;
+            }
+        }
+        Err(e) => error!("Failed to list sessions after password change: {}", e),
+    }
+
+    info!("User {} changed password", user.username);
+    Ok(HttpResponse::Ok().json(ApiResponse::success_empty("Password changed successfully")))
+}
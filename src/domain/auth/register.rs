@@ -2,17 +2,47 @@ use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 use argon2::Argon2;
 use argon2::password_hash::{PasswordHasher, SaltString, rand_core::OsRng};
 
-use crate::models::{ApiResponse, ErrorCode, users::requests::CreateUserRequest};
+use crate::models::{
+    ApiResponse, ErrorCode,
+    auth::RegisterRequest,
+    users::{
+        entities::{UserRole, UserStatus},
+        requests::CreateUserRequest,
+    },
+};
+use crate::system::app_config::{RegistrationConfig, RegistrationMode};
 use crate::utils::validate::{validate_email, validate_username};
 
 use super::AuthService;
+use super::email_verification::send_verification_email;
 
 pub async fn handle_register(
     service: &AuthService,
-    mut create_request: CreateUserRequest,
+    register_request: RegisterRequest,
     request: &HttpRequest,
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
+    let config = &service.get_config().registration;
+
+    // 0. 校验注册策略
+    if let Err(response) = check_registration_policy(config, &register_request) {
+        return Ok(response);
+    }
+
+    // 自助注册的用户只能是普通用户，需审核时置为待审核状态
+    let requires_approval = config.mode == RegistrationMode::Approval;
+    let mut create_request = CreateUserRequest {
+        username: register_request.username,
+        email: register_request.email,
+        password: register_request.password,
+        role: UserRole::User,
+        status: Some(if requires_approval {
+            UserStatus::Pending
+        } else {
+            UserStatus::Active
+        }),
+        profile: register_request.profile,
+    };
 
     // 1. 检查用户名是否已存在
     if let Err(response) = check_username_exists(&storage, &create_request.username).await {
@@ -45,7 +75,17 @@ pub async fn handle_register(
             // 4. 创建用户
             match storage.create_user(create_request).await {
                 Ok(user) => {
-                    Ok(HttpResponse::Created().json(ApiResponse::success(user, "注册成功")))
+                    let mailer = service.get_mailer(request);
+                    if let Err(e) = send_verification_email(mailer.as_ref(), &user).await {
+                        tracing::error!("Failed to send verification email: {}", e);
+                    }
+
+                    let message = if requires_approval {
+                        "注册成功，请等待管理员审核"
+                    } else {
+                        "注册成功"
+                    };
+                    Ok(HttpResponse::Created().json(ApiResponse::success(user, message)))
                 }
                 Err(e) => Ok(
                     HttpResponse::InternalServerError().json(ApiResponse::error_empty(
@@ -64,6 +104,33 @@ pub async fn handle_register(
     }
 }
 
+fn check_registration_policy(
+    config: &RegistrationConfig,
+    register_request: &RegisterRequest,
+) -> Result<(), HttpResponse> {
+    match config.mode {
+        RegistrationMode::Open | RegistrationMode::Approval => Ok(()),
+        RegistrationMode::Closed => Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::RegistrationClosed,
+            "Registration is closed",
+        ))),
+        RegistrationMode::InviteOnly => {
+            let valid = register_request
+                .invite_code
+                .as_ref()
+                .is_some_and(|code| config.invite_codes.contains(code));
+            if valid {
+                Ok(())
+            } else {
+                Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                    ErrorCode::InviteCodeInvalid,
+                    "Invalid or missing invite code",
+                )))
+            }
+        }
+    }
+}
+
 async fn check_username_exists(
     storage: &std::sync::Arc<dyn crate::repository::Storage>,
     username: &str,
@@ -0,0 +1,168 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::middlewares::require_jwt::RequireJWT;
+use crate::models::auth::responses::{SessionListResponse, SessionResponse};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+use crate::utils::client_ip::client_ip;
+use crate::utils::jwt::{self, Claims};
+
+use super::AuthService;
+use super::token::store_refresh_token;
+
+// User-Agent 最大记录长度
+const MAX_USER_AGENT_LENGTH: usize = 255;
+
+// 为新的登录创建会话并记录首个 refresh token
+pub(super) async fn create_login_session(
+    storage: &Arc<dyn Storage>,
+    request: &HttpRequest,
+    user_id: i64,
+    remember_me: bool,
+    refresh_claims: &Claims,
+) -> crate::errors::Result<()> {
+    let session_id = refresh_claims.fid.as_deref().unwrap_or(&refresh_claims.jti);
+    let expires_at =
+        chrono::DateTime::from_timestamp(refresh_claims.exp as i64, 0).unwrap_or_default();
+    let user_agent = request
+        .headers()
+        .get(actix_web::http::header::USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            value
+                .chars()
+                .take(MAX_USER_AGENT_LENGTH)
+                .collect::<String>()
+        });
+    let ip_address = client_ip(request).map(|ip| ip.to_string());
+
+    storage
+        .create_session(
+            session_id,
+            user_id,
+            user_agent.as_deref(),
+            ip_address.as_deref(),
+            remember_me,
+            expires_at,
+        )
+        .await?;
+    store_refresh_token(storage, user_id, refresh_claims).await?;
+
+    Ok(())
+}
+
+// 列出用户的会话，并标记发起请求的当前会话
+pub(crate) async fn list_sessions_response(
+    storage: &Arc<dyn Storage>,
+    user_id: i64,
+    current_session_id: Option<&str>,
+) -> HttpResponse {
+    match storage.list_user_sessions(user_id).await {
+        Ok(sessions) => {
+            let items = sessions
+                .into_iter()
+                .map(|session| SessionResponse {
+                    is_current: current_session_id == Some(session.id.as_str()),
+                    session,
+                })
+                .collect();
+            HttpResponse::Ok().json(ApiResponse::success(
+                SessionListResponse { items },
+                "Sessions retrieved successfully",
+            ))
+        }
+        Err(e) => {
+            error!("Failed to list sessions: {}", e);
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to list sessions: {e}"),
+            ))
+        }
+    }
+}
+
+// 终止属于指定用户的会话，同时使该会话签发的 access token 失效
+pub(crate) async fn revoke_session_response(
+    storage: &Arc<dyn Storage>,
+    cache: &Arc<dyn crate::cache::ObjectCache>,
+    user_id: i64,
+    session_id: &str,
+) -> HttpResponse {
+    match storage.get_session_by_id(session_id).await {
+        Ok(Some(session)) if session.user_id == user_id && session.revoked_at.is_none() => {}
+        Ok(_) => {
+            return HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::SessionNotFound,
+                "Session not found",
+            ));
+        }
+        Err(e) => {
+            error!("Failed to query session: {}", e);
+            return HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to terminate session: {e}"),
+            ));
+        }
+    }
+
+    match storage.revoke_session(session_id).await {
+        Ok(_) => {
+            jwt::JwtUtils::revoke_session_access_tokens(cache.as_ref(), session_id).await;
+            info!("Session {} of user {} terminated", session_id, user_id);
+            HttpResponse::Ok().json(ApiResponse::success_empty(
+                "Session terminated successfully",
+            ))
+        }
+        Err(e) => {
+            error!("Failed to revoke session: {}", e);
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to terminate session: {e}"),
+            ))
+        }
+    }
+}
+
+pub async fn handle_list_sessions(
+    service: &AuthService,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user_id = match RequireJWT::extract_user_id(request) {
+        Some(user_id) => user_id,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized access, please login",
+            )));
+        }
+    };
+    let current_session_id =
+        RequireJWT::extract_access_claims(request).and_then(|claims| claims.fid);
+
+    Ok(list_sessions_response(&storage, user_id, current_session_id.as_deref()).await)
+}
+
+pub async fn handle_revoke_session(
+    service: &AuthService,
+    request: &HttpRequest,
+    session_id: &str,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let user_id = match RequireJWT::extract_user_id(request) {
+        Some(user_id) => user_id,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized access, please login",
+            )));
+        }
+    };
+
+    Ok(revoke_session_response(&storage, &cache, user_id, session_id).await)
+}
@@ -1,11 +1,16 @@
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+use tracing::{error, warn};
 
 use crate::middlewares::require_jwt::RequireJWT;
+use crate::models::auth::entities::RefreshToken;
 use crate::models::auth::responses::{
     RefreshTokenResponse, TokenVerificationResponse, UserInfoResponse,
 };
+use crate::models::users::entities::UserStatus;
 use crate::models::{ApiResponse, ErrorCode};
-use crate::utils::jwt;
+use crate::repository::Storage;
+use crate::utils::jwt::{self, Claims};
 
 use super::AuthService;
 
@@ -13,42 +18,140 @@ pub async fn handle_refresh_token(
     service: &AuthService,
     request: &HttpRequest,
 ) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
     let config = service.get_config();
+
     // 从 cookie 中提取 refresh token
-    match jwt::JwtUtils::extract_refresh_token_from_cookie(request) {
-        Some(refresh_token) => {
-            // 验证 refresh token 并生成新的 access token
-            match jwt::JwtUtils::refresh_access_token(&refresh_token) {
-                Ok(new_access_token) => {
-                    let response = RefreshTokenResponse {
-                        access_token: new_access_token,
-                        expires_in: config.jwt.access_token_expiry,
-                    };
-                    Ok(HttpResponse::Ok().json(ApiResponse::success(
-                        response,
-                        "Token refreshed successfully",
-                    )))
-                }
-                Err(e) => {
-                    tracing::error!("Refresh token failed: {}", e);
-
-                    // 清除无效的 refresh token cookie
-                    let empty_cookie = jwt::JwtUtils::create_empty_refresh_token_cookie();
-
-                    Ok(HttpResponse::Unauthorized().cookie(empty_cookie).json(
-                        ApiResponse::error_empty(
-                            ErrorCode::Unauthorized,
-                            "Login expired or invalid, please login again",
-                        ),
-                    ))
-                }
-            }
+    let refresh_token = match jwt::JwtUtils::extract_refresh_token_from_cookie(request) {
+        Some(refresh_token) => refresh_token,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized access, please login",
+            )));
         }
-        None => Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
-            ErrorCode::Unauthorized,
-            "Unauthorized access, please login",
-        ))),
+    };
+
+    let claims = match jwt::JwtUtils::verify_refresh_token(&refresh_token) {
+        Ok(claims) => claims,
+        Err(e) => {
+            error!("Refresh token failed: {}", e);
+            return Ok(invalid_refresh_token_response());
+        }
+    };
+
+    // 未记录在存储中的令牌一律拒绝
+    let record = match storage.get_refresh_token(&claims.jti).await {
+        Ok(Some(record)) => record,
+        Ok(None) => {
+            warn!("Unknown refresh token presented: {}", claims.jti);
+            return Ok(invalid_refresh_token_response());
+        }
+        Err(e) => return Ok(refresh_failed_response(e)),
+    };
+
+    // 已吊销的令牌再次出现视为重放，吊销整个令牌族
+    if record.revoked_at.is_some() {
+        return Ok(handle_refresh_token_reuse(&storage, &record).await);
+    }
+
+    // 吊销旧令牌，并发刷新同一令牌时只有一个请求能够成功
+    match storage.revoke_refresh_token(&record.jti).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(handle_refresh_token_reuse(&storage, &record).await),
+        Err(e) => return Ok(refresh_failed_response(e)),
+    }
+
+    // 重新读取用户，确保新令牌使用最新的角色与状态
+    let user = match storage.get_user_by_id(record.user_id).await {
+        Ok(Some(user)) if user.status == UserStatus::Active => user,
+        Ok(_) => {
+            let _ = storage.revoke_session(&record.family_id).await;
+            return Ok(invalid_refresh_token_response());
+        }
+        Err(e) => return Ok(refresh_failed_response(e)),
+    };
+
+    let role = user.role.to_string();
+    let tokens = jwt::JwtUtils::rotate_refresh_token(&claims, &role).and_then(
+        |(refresh_token, refresh_claims)| {
+            jwt::JwtUtils::generate_access_token(user.id, &role, Some(&record.family_id))
+                .map(|access_token| (access_token, refresh_token, refresh_claims))
+        },
+    );
+    let (access_token, refresh_token, refresh_claims) = match tokens {
+        Ok(tokens) => tokens,
+        Err(e) => return Ok(refresh_failed_response(e)),
+    };
+
+    if let Err(e) = store_refresh_token(&storage, user.id, &refresh_claims).await {
+        return Ok(refresh_failed_response(e));
     }
+    if let Err(e) = storage.touch_session(&record.family_id).await {
+        error!("Failed to update session last used time: {}", e);
+    }
+
+    let response = RefreshTokenResponse {
+        access_token,
+        expires_in: config.jwt.access_token_expiry * 60, // 转换为秒
+    };
+
+    Ok(HttpResponse::Ok()
+        .cookie(jwt::JwtUtils::create_refresh_token_cookie(&refresh_token))
+        .json(ApiResponse::success(
+            response,
+            "Token refreshed successfully",
+        )))
+}
+
+// 记录新签发的 refresh token，供后续轮换与吊销使用
+pub(super) async fn store_refresh_token(
+    storage: &Arc<dyn Storage>,
+    user_id: i64,
+    claims: &Claims,
+) -> crate::errors::Result<RefreshToken> {
+    let family_id = claims.fid.as_deref().unwrap_or(&claims.jti);
+    let expires_at = chrono::DateTime::from_timestamp(claims.exp as i64, 0).unwrap_or_default();
+
+    storage
+        .create_refresh_token(&claims.jti, family_id, user_id, expires_at)
+        .await
+}
+
+async fn handle_refresh_token_reuse(
+    storage: &Arc<dyn Storage>,
+    record: &RefreshToken,
+) -> HttpResponse {
+    warn!(
+        "Refresh token reuse detected for user {}, terminating session {}",
+        record.user_id, record.family_id
+    );
+
+    if let Err(e) = storage.revoke_session(&record.family_id).await {
+        error!("Failed to revoke session: {}", e);
+    }
+
+    invalid_refresh_token_response()
+}
+
+fn invalid_refresh_token_response() -> HttpResponse {
+    // 清除无效的 refresh token cookie
+    let empty_cookie = jwt::JwtUtils::create_empty_refresh_token_cookie();
+
+    HttpResponse::Unauthorized()
+        .cookie(empty_cookie)
+        .json(ApiResponse::error_empty(
+            ErrorCode::Unauthorized,
+            "Login expired or invalid, please login again",
+        ))
+}
+
+fn refresh_failed_response(e: impl std::fmt::Display) -> HttpResponse {
+    error!("Refresh token failed: {}", e);
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        "Token refresh failed, please try again later",
+    ))
 }
 
 pub async fn handle_verify_token(
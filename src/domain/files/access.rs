@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use actix_web::HttpResponse;
+
+use crate::domain::grades::get::can_grade;
+use crate::errors::Result;
+use crate::models::files::entities::File;
+use crate::models::users::entities::{User, UserRole};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+
+/// 判断用户能否读取文件
+///
+/// 管理员与上传者始终可以读取；作业附件对作业所属班级的全体成员可见，
+/// 提交附件对提交者与有权为该提交评分的用户（班级教师、配置允许时的课代表）可见。
+pub(crate) async fn can_read_file(
+    storage: &Arc<dyn Storage>,
+    file: &File,
+    user: &User,
+) -> Result<bool> {
+    if user.role == UserRole::Admin || file.user_id == user.id {
+        return Ok(true);
+    }
+
+    for homework in storage
+        .list_homeworks_by_attachment(&file.submission_token)
+        .await?
+    {
+        if storage
+            .get_class_user_by_user_id_and_class_id(user.id, homework.class_id)
+            .await?
+            .is_some()
+        {
+            return Ok(true);
+        }
+    }
+
+    for submission in storage
+        .list_submissions_by_attachment(&file.submission_token)
+        .await?
+    {
+        if submission.creator_id == user.id {
+            return Ok(true);
+        }
+        let Some(homework) = storage.get_homework_by_id(submission.homework_id).await? else {
+            continue;
+        };
+        let class_user = storage
+            .get_class_user_by_user_id_and_class_id(user.id, homework.class_id)
+            .await?;
+        if can_grade(user, class_user.as_ref()) {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// 获取用户有权读取的文件，文件不存在时返回 404，无权读取时返回 403
+pub(crate) async fn get_readable_file(
+    storage: &Arc<dyn Storage>,
+    submission_token: &str,
+    user: &User,
+) -> std::result::Result<File, HttpResponse> {
+    let file = match storage.get_file_by_token(submission_token).await {
+        Ok(Some(file)) => file,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::FileNotFound,
+                "File not found",
+            )));
+        }
+        Err(e) => {
+            return Err(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("File query failed: {e}"),
+                )),
+            );
+        }
+    };
+
+    match can_read_file(storage, &file, user).await {
+        Ok(true) => Ok(file),
+        Ok(false) => Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::FileAccessDenied,
+            "You do not have permission to access this file",
+        ))),
+        Err(e) => Err(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to check file permission: {e}"),
+            )),
+        ),
+    }
+}
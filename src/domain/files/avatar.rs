@@ -0,0 +1,56 @@
+use actix_web::{HttpResponse, Result as ActixResult, http::header, web};
+use std::path::Path;
+
+use super::FileService;
+use crate::errors::HWSystemError;
+use crate::models::{ApiResponse, ErrorCode};
+use crate::system::app_config::AppConfig;
+use crate::utils::avatar;
+
+// 头像文件名中包含每次上传生成的版本号，内容不会变化，可长期缓存
+const AVATAR_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+pub async fn handle_avatar(_service: &FileService, file_name: String) -> ActixResult<HttpResponse> {
+    if !avatar::is_valid_file_name(&file_name) {
+        return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::FileNotFound,
+            "Avatar not found",
+        )));
+    }
+
+    let config = AppConfig::get();
+    let file_path = Path::new(&config.upload.dir)
+        .join("avatars")
+        .join(&file_name);
+
+    let data = match web::block(move || std::fs::read(file_path)).await? {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::FileNotFound,
+                "Avatar not found",
+            )));
+        }
+        Err(e) => {
+            tracing::error!("{:?}", HWSystemError::file_operation(format!("{e:?}")));
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "File read failed",
+                )),
+            );
+        }
+    };
+
+    let content_type = if file_name.ends_with(".png") {
+        "image/png"
+    } else {
+        "image/jpeg"
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header((header::CACHE_CONTROL, AVATAR_CACHE_CONTROL))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .body(data))
+}
@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::errors::Result;
+use crate::models::submissions::requests::SubmissionListQuery;
+use crate::repository::Storage;
+
+// 分页读取提交时每页的数量
+const SUBMISSION_PAGE_SIZE: i64 = 100;
+
+/// 重新统计文件被作业与提交引用的次数
+///
+/// 按实际引用重新计算而不是增减计数，并发修改或重复调用都不会使计数偏离；
+/// 失败时只记录日志，计数由文件回收任务定期校正。
+pub(crate) async fn refresh_citations<'a>(
+    storage: &Arc<dyn Storage>,
+    tokens: impl IntoIterator<Item = &'a String>,
+) {
+    let tokens: HashSet<&String> = tokens.into_iter().collect();
+    for token in tokens {
+        if let Err(e) = refresh_citation(storage, token).await {
+            warn!("Failed to refresh citation count of file {}: {}", token, e);
+        }
+    }
+}
+
+// 同一作业或提交中重复引用同一文件时按一次计
+async fn refresh_citation(storage: &Arc<dyn Storage>, token: &str) -> Result<()> {
+    let homeworks = storage.list_homeworks_by_attachment(token).await?;
+    let submissions = storage.list_submissions_by_attachment(token).await?;
+    storage
+        .set_file_citation_count(token, (homeworks.len() + submissions.len()) as i32)
+        .await?;
+    Ok(())
+}
+
+/// 列出作业全部提交的附件，用于删除作业前确定级联删除后需要重新统计的文件
+pub(crate) async fn list_submission_attachments(
+    storage: &Arc<dyn Storage>,
+    homework_id: i64,
+) -> Result<Vec<String>> {
+    let mut attachments = Vec::new();
+    let mut page = 1;
+    loop {
+        let response = storage
+            .list_submissions_with_pagination(SubmissionListQuery {
+                page: Some(page),
+                size: Some(SUBMISSION_PAGE_SIZE),
+                homework_id,
+                status: None,
+            })
+            .await?;
+        for submission in response.items {
+            attachments.extend(submission.attachments);
+        }
+        if page >= response.pagination.pages {
+            return Ok(attachments);
+        }
+        page += 1;
+    }
+}
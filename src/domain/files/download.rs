@@ -1,12 +1,105 @@
-use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, http::header};
-use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use actix_web::http::header::{
+    self, CacheControl, CacheDirective, Charset, ContentDisposition, ContentEncoding, ContentRange,
+    ContentRangeSpec, DispositionParam, DispositionType, ETag, EntityTag, ExtendedValue,
+    HeaderValue, HttpDate, IfModifiedSince, IfNoneMatch, IfRange, LastModified, Range,
+};
+use actix_web::{HttpMessage, HttpRequest, HttpResponse, Result as ActixResult};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use super::FileService;
-use crate::errors::HWSystemError;
+use super::access::get_readable_file;
+use crate::middlewares::RequireJWT;
+use crate::models::files::entities::File;
+use crate::models::users::entities::UserStatus;
 use crate::models::{ApiResponse, ErrorCode};
-use crate::system::app_config::AppConfig;
+use crate::utils::download_token::DownloadTokenUtils;
+
+// 文件的缓存校验信息
+struct Validators {
+    etag: EntityTag,
+    last_modified: SystemTime,
+}
+
+impl Validators {
+    // 满足 If-None-Match 或 If-Modified-Since 时返回 304
+    fn not_modified(&self, request: &HttpRequest) -> bool {
+        // 同时提供时只看 If-None-Match
+        if let Some(if_none_match) = request.get_header::<IfNoneMatch>() {
+            return match if_none_match {
+                IfNoneMatch::Any => true,
+                IfNoneMatch::Items(tags) => tags.iter().any(|tag| tag.weak_eq(&self.etag)),
+            };
+        }
+        match request.get_header::<IfModifiedSince>() {
+            Some(IfModifiedSince(since)) => SystemTime::from(since) >= self.last_modified,
+            None => false,
+        }
+    }
+
+    // 没有 If-Range 或 If-Range 与当前文件一致时才按 Range 返回部分内容
+    fn range_allowed(&self, request: &HttpRequest) -> bool {
+        match request.get_header::<IfRange>() {
+            None => true,
+            Some(IfRange::EntityTag(tag)) => tag.strong_eq(&self.etag),
+            Some(IfRange::Date(date)) => SystemTime::from(date) == self.last_modified,
+        }
+    }
+
+    fn headers(&self) -> (ETag, LastModified) {
+        (
+            ETag(self.etag.clone()),
+            LastModified(HttpDate::from(self.last_modified)),
+        )
+    }
+}
+
+// 可以在浏览器中直接打开的类型，响应同时带有 nosniff，浏览器不会按内容猜测为其他类型；
+// HTML、SVG 等可以执行脚本的类型仍作为附件下载
+const INLINE_TYPES: &[&str] = &[
+    "application/pdf",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "text/plain",
+];
+
+fn is_inline_type(content_type: &str) -> bool {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    INLINE_TYPES
+        .iter()
+        .any(|inline| inline.eq_ignore_ascii_case(essence))
+}
+
+// 文件名中的非 ASCII 字符按 RFC 5987 写入 filename*，filename 保留 ASCII 部分供旧客户端使用
+fn content_disposition(file_name: &str, content_type: &str) -> ContentDisposition {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut parameters = vec![DispositionParam::Filename(fallback)];
+    if !file_name.is_ascii() {
+        parameters.push(DispositionParam::FilenameExt(ExtendedValue {
+            charset: Charset::Ext("UTF-8".to_string()),
+            language_tag: None,
+            value: file_name.as_bytes().to_vec(),
+        }));
+    }
+    ContentDisposition {
+        disposition: if is_inline_type(content_type) {
+            DispositionType::Inline
+        } else {
+            DispositionType::Attachment
+        },
+        parameters,
+    }
+}
 
 pub async fn handle_download(
     service: &FileService,
@@ -15,63 +108,164 @@ pub async fn handle_download(
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
 
-    let db_file = match storage.get_file_by_token(&file_token).await {
-        Ok(Some(f)) => f,
-        Ok(None) => {
-            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
-                ErrorCode::FileNotFound,
-                "File not found",
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
             )));
         }
+    };
+
+    match get_readable_file(&storage, &file_token, &user).await {
+        Ok(db_file) => send_file(service, request, db_file).await,
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// 通过签名链接下载文件，不需要 Authorization 头
+///
+/// 下载时仍按签发链接的用户重新检查权限，用户被停用或移出班级后链接随即失效。
+pub async fn handle_signed_download(
+    service: &FileService,
+    request: &HttpRequest,
+    file_token: String,
+    token: String,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let link_invalid = || {
+        HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::FileLinkInvalid,
+            "Download link is invalid or expired",
+        ))
+    };
+
+    let Some(user_id) = DownloadTokenUtils::decode(&token, &file_token)
+        .ok()
+        .and_then(|claims| claims.sub.parse::<i64>().ok())
+    else {
+        return Ok(link_invalid());
+    };
+
+    let user = match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) if user.status == UserStatus::Active => user,
+        Ok(_) => return Ok(link_invalid()),
         Err(e) => {
             return Ok(
                 HttpResponse::InternalServerError().json(ApiResponse::error_empty(
                     ErrorCode::InternalServerError,
-                    format!("File query failed: {e}"),
+                    format!("User query failed: {e}"),
                 )),
             );
         }
     };
 
-    let config = AppConfig::get();
-    let upload_dir = &config.upload.dir;
-    let file_path = format!("{}/{}.bin", upload_dir, db_file.submission_token);
+    match get_readable_file(&storage, &file_token, &user).await {
+        Ok(db_file) => send_file(service, request, db_file).await,
+        Err(resp) => Ok(resp),
+    }
+}
 
-    if !Path::new(&file_path).exists() {
-        return Ok(HttpResponse::NotFound()
-            .json(ApiResponse::error_empty(ErrorCode::NotFound, "文件不存在")));
+async fn send_file(
+    service: &FileService,
+    request: &HttpRequest,
+    db_file: File,
+) -> ActixResult<HttpResponse> {
+    // 文件上传后内容不再变化，提交令牌即可作为强 ETag；HTTP 日期只精确到秒
+    let size = db_file.file_size.max(0) as u64;
+    let validators = Validators {
+        etag: EntityTag::new_strong(db_file.submission_token.clone()),
+        last_modified: UNIX_EPOCH
+            + Duration::from_secs(db_file.uploaded_at.timestamp().max(0) as u64),
+    };
+
+    if validators.not_modified(request) {
+        let (etag, last_modified) = validators.headers();
+        return Ok(HttpResponse::NotModified()
+            .insert_header(etag)
+            .insert_header(last_modified)
+            .finish());
     }
 
-    let mut file = match File::open(&file_path) {
-        Ok(f) => f,
+    // 只支持单个范围，多个范围或无法解析时返回完整文件
+    let range = match request.get_header::<Range>() {
+        Some(Range::Bytes(specs)) if specs.len() == 1 && validators.range_allowed(request) => {
+            match specs[0].to_satisfiable_range(size) {
+                Some(range) => Some(range),
+                None => {
+                    return Ok(HttpResponse::RangeNotSatisfiable()
+                        .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                            range: None,
+                            instance_length: Some(size),
+                        }))
+                        .json(ApiResponse::error_empty(
+                            ErrorCode::FileRangeNotSatisfiable,
+                            "Requested range not satisfiable",
+                        )));
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let body = match service
+        .get_blob_store(request)
+        .get(db_file.blob_key(), range)
+        .await
+    {
+        Ok(Some(body)) => body,
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound()
+                .json(ApiResponse::error_empty(ErrorCode::NotFound, "文件不存在")));
+        }
         Err(e) => {
-            tracing::error!("{:?}", HWSystemError::file_operation(format!("{e:?}")));
+            tracing::error!("{}", e);
             return Ok(
                 HttpResponse::InternalServerError().json(ApiResponse::error_empty(
                     ErrorCode::InternalServerError,
-                    "File open failed",
+                    "File read failed",
                 )),
             );
         }
     };
 
-    let mut buf = Vec::new();
-    if file.read_to_end(&mut buf).is_err() {
-        tracing::error!("{:?}", HWSystemError::file_operation("File read failed"));
-        return Ok(
-            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
-                ErrorCode::InternalServerError,
-                "File read failed",
-            )),
-        );
-    }
+    let content_type = HeaderValue::from_str(&db_file.file_type)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream"));
 
-    // 使用数据库中的原始文件名
-    Ok(HttpResponse::Ok()
-        .insert_header((header::CONTENT_TYPE, "application/octet-stream"))
-        .insert_header((
-            header::CONTENT_DISPOSITION,
-            format!("attachment; filename=\"{}\"", db_file.file_name),
-        ))
-        .body(buf))
+    let mut response = match range {
+        Some(_) => HttpResponse::PartialContent(),
+        None => HttpResponse::Ok(),
+    };
+    let (etag, last_modified) = validators.headers();
+    response
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .insert_header(content_disposition(&db_file.file_name, &db_file.file_type))
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header(etag)
+        .insert_header(last_modified)
+        // 允许浏览器缓存，但每次使用前需要按 ETag 重新校验
+        .insert_header(CacheControl(vec![
+            CacheDirective::Private,
+            CacheDirective::NoCache,
+        ]))
+        // 按字节范围返回的内容不能再被压缩
+        .insert_header(ContentEncoding::Identity);
+
+    let length = match range {
+        Some((start, end)) => {
+            response.insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: Some((start, end)),
+                instance_length: Some(size),
+            }));
+            end - start + 1
+        }
+        None => size,
+    };
+
+    Ok(response.no_chunking(length).streaming(body))
 }
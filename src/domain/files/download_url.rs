@@ -0,0 +1,59 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::error;
+
+use super::FileService;
+use super::access::get_readable_file;
+use crate::middlewares::RequireJWT;
+use crate::models::files::responses::FileDownloadUrlResponse;
+use crate::models::{ApiResponse, ErrorCode};
+use crate::system::app_config::AppConfig;
+use crate::utils::download_token::DownloadTokenUtils;
+
+pub async fn create_download_url(
+    service: &FileService,
+    request: &HttpRequest,
+    file_token: String,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    // 只为当前用户有权读取的文件签发链接
+    let db_file = match get_readable_file(&storage, &file_token, &user).await {
+        Ok(db_file) => db_file,
+        Err(resp) => return Ok(resp),
+    };
+
+    let expiry = chrono::Duration::seconds(AppConfig::get().upload.download_url_expiry);
+    let token = match DownloadTokenUtils::generate(user.id, &db_file.submission_token, expiry) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("Failed to generate download token: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Failed to generate download link",
+                )),
+            );
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        FileDownloadUrlResponse {
+            url: format!(
+                "/api/v1/files/signed/{}?token={token}",
+                db_file.submission_token
+            ),
+            expires_at: chrono::Utc::now() + expiry,
+        },
+        "Download link created successfully",
+    )))
+}
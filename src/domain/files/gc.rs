@@ -0,0 +1,324 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{error, info, warn};
+
+use super::citation::refresh_citations;
+use super::resumable::remove_upload_chunks;
+use crate::blob_store::BlobStore;
+use crate::errors::Result;
+use crate::repository::Storage;
+use crate::system::app_config::AppConfig;
+
+// 每批删除的文件或上传会话数
+const GC_BATCH_SIZE: i64 = 100;
+// 内容删除标记的有效期（秒），超过后视为回收任务中途退出遗留的标记
+const CONTENT_DELETION_TIMEOUT: i64 = 60;
+// 等待内容删除完成时查询标记的间隔
+const CONTENT_DELETION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 配置了回收间隔时，启动定期删除未被引用文件与过期上传会话的后台任务
+pub fn start_gc(storage: Arc<dyn Storage>, blob_store: Arc<dyn BlobStore>) {
+    let config = &AppConfig::get().upload;
+    if config.gc_interval == 0 {
+        return;
+    }
+
+    let period = Duration::from_secs(config.gc_interval);
+    actix_web::rt::spawn(async move {
+        let mut interval = actix_web::rt::time::interval(period);
+        // 第一次触发是立即的，跳过以免与启动过程争抢资源
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match collect_garbage(&storage, blob_store.as_ref()).await {
+                Ok(0) => {}
+                Ok(removed) => info!("File garbage collection removed {} files", removed),
+                Err(e) => error!("File garbage collection failed: {}", e),
+            }
+            match remove_expired_uploads(&storage, blob_store.as_ref()).await {
+                Ok(0) => {}
+                Ok(removed) => info!("Removed {} expired upload sessions", removed),
+                Err(e) => error!("Failed to remove expired upload sessions: {}", e),
+            }
+        }
+    });
+    info!(
+        "File garbage collection scheduled every {} seconds",
+        config.gc_interval
+    );
+}
+
+// 校正引用次数后，删除上传超过保留期且未被引用的文件，返回删除的文件数
+async fn collect_garbage(storage: &Arc<dyn Storage>, blob_store: &dyn BlobStore) -> Result<usize> {
+    reconcile_citations(storage).await?;
+
+    let grace_period = chrono::Duration::seconds(AppConfig::get().upload.gc_grace_period);
+    let uploaded_before = chrono::Utc::now() - grace_period;
+    let mut removed = 0;
+    loop {
+        let files = storage
+            .list_unreferenced_files(uploaded_before, GC_BATCH_SIZE)
+            .await?;
+        let mut removed_in_batch = 0;
+        for file in &files {
+            // 列出后被引用的文件不会被删除
+            if !storage
+                .delete_unreferenced_file(&file.submission_token)
+                .await?
+            {
+                continue;
+            }
+            removed_in_batch += 1;
+
+            match &file.content_hash {
+                Some(content_hash) => delete_content(storage, blob_store, content_hash).await?,
+                None => {
+                    if let Err(e) = blob_store.delete(file.blob_key()).await {
+                        warn!(
+                            "Failed to delete content of file {}: {}",
+                            file.submission_token, e
+                        );
+                    }
+                }
+            }
+        }
+        removed += removed_in_batch;
+        if (files.len() as i64) < GC_BATCH_SIZE || removed_in_batch == 0 {
+            return Ok(removed);
+        }
+    }
+}
+
+// 内容不再被任何文件记录使用时删除
+//
+// 删除期间保留删除标记，上传相同内容时等待标记清除后再写入，
+// 避免统计记录数之后写入的内容被随后的删除覆盖
+async fn delete_content(
+    storage: &Arc<dyn Storage>,
+    blob_store: &dyn BlobStore,
+    content_hash: &str,
+) -> Result<()> {
+    // 其他实例正在删除同一内容时等待其完成
+    while !storage
+        .begin_content_deletion(content_hash, content_deletion_stale_before())
+        .await?
+    {
+        wait_for_content_deletion(storage, content_hash).await?;
+    }
+
+    let result = match storage.count_files_by_content_hash(content_hash).await {
+        Ok(0) => {
+            if let Err(e) = blob_store.delete(content_hash).await {
+                warn!("Failed to delete content {}: {}", content_hash, e);
+            }
+            Ok(())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => Err(e),
+    };
+
+    // 清除失败时标记在过期后失效
+    if let Err(e) = storage.finish_content_deletion(content_hash).await {
+        warn!(
+            "Failed to clear deletion mark of content {}: {}",
+            content_hash, e
+        );
+    }
+    result
+}
+
+/// 等待回收任务删除指定内容完成
+///
+/// 上传在写入文件记录之后、写入内容之前调用：写入记录后开始的删除会统计到该记录而保留内容，
+/// 写入记录前开始的删除在此等待结束，随后写入的内容不会被删除。
+pub(crate) async fn wait_for_content_deletion(
+    storage: &Arc<dyn Storage>,
+    content_hash: &str,
+) -> Result<()> {
+    while storage
+        .is_content_being_deleted(content_hash, content_deletion_stale_before())
+        .await?
+    {
+        actix_web::rt::time::sleep(CONTENT_DELETION_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+fn content_deletion_stale_before() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() - chrono::Duration::seconds(CONTENT_DELETION_TIMEOUT)
+}
+
+// 作业、提交随班级或用户级联删除时不会经过业务层更新引用次数，由数据库统计找出计数不符的文件
+// 找到后重新查询实际引用，避免把统计期间新增的引用当作不存在
+async fn reconcile_citations(storage: &Arc<dyn Storage>) -> Result<()> {
+    let mismatched = storage.list_citation_mismatches().await?;
+    if !mismatched.is_empty() {
+        info!("Correcting citation count of {} files", mismatched.len());
+        refresh_citations(storage, &mismatched).await;
+    }
+    Ok(())
+}
+
+// 删除过期的上传会话及其已接收的分块，返回删除的会话数
+async fn remove_expired_uploads(
+    storage: &Arc<dyn Storage>,
+    blob_store: &dyn BlobStore,
+) -> Result<usize> {
+    let now = chrono::Utc::now();
+    let mut removed = 0;
+    loop {
+        let sessions = storage
+            .list_expired_upload_sessions(now, GC_BATCH_SIZE)
+            .await?;
+        let mut removed_in_batch = 0;
+        for session in &sessions {
+            // 已由其他实例删除时跳过
+            if storage.delete_upload_session(&session.id).await? {
+                remove_upload_chunks(blob_store, session).await;
+                removed_in_batch += 1;
+            }
+        }
+        removed += removed_in_batch;
+        if (sessions.len() as i64) < GC_BATCH_SIZE || removed_in_batch == 0 {
+            return Ok(removed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::homeworks::requests::CreateHomeworkRequest;
+    use crate::models::users::entities::UserRole;
+    use crate::test_utils::TestContext;
+
+    async fn upload(ctx: &TestContext, token: &str, content_hash: &str, user_id: i64) {
+        ctx.storage
+            .upload_file(token, token, &1, "text/plain", content_hash, user_id)
+            .await
+            .unwrap();
+    }
+
+    async fn citation_count(ctx: &TestContext, token: &str) -> i32 {
+        let file = ctx.storage.get_file_by_token(token).await.unwrap().unwrap();
+        file.citation_count
+    }
+
+    #[actix_web::test]
+    async fn citation_counts_are_reconciled_from_attachments() {
+        let ctx = TestContext::new().await;
+        let teacher = ctx.create_user("gc_teacher", UserRole::Teacher).await;
+        let class = ctx.create_class(&teacher, &[]).await;
+        for token in ["gc-a", "gc-b", "gc-c"] {
+            upload(&ctx, token, token, teacher.id).await;
+        }
+        ctx.storage
+            .set_file_citation_count("gc-c", 2)
+            .await
+            .unwrap();
+        for attachments in [vec!["gc-a", "gc-a", "gc-b"], vec!["gc-a"]] {
+            ctx.storage
+                .create_homework(
+                    class.id,
+                    teacher.id,
+                    CreateHomeworkRequest {
+                        title: "Homework".to_string(),
+                        content: None,
+                        attachments: attachments.into_iter().map(String::from).collect(),
+                        max_score: 100.0,
+                        deadline: None,
+                        allow_late_submission: false,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut mismatched = ctx.storage.list_citation_mismatches().await.unwrap();
+        mismatched.sort();
+        assert_eq!(mismatched, vec!["gc-a", "gc-b", "gc-c"]);
+
+        reconcile_citations(&ctx.storage).await.unwrap();
+        assert_eq!(citation_count(&ctx, "gc-a").await, 2);
+        assert_eq!(citation_count(&ctx, "gc-b").await, 1);
+        assert_eq!(citation_count(&ctx, "gc-c").await, 0);
+        assert!(
+            ctx.storage
+                .list_citation_mismatches()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[actix_web::test]
+    async fn shared_content_is_kept_and_uploads_wait_for_deletion() {
+        let ctx = TestContext::new().await;
+        let user = ctx.create_user("gc_owner", UserRole::User).await;
+        let content_hash = uuid::Uuid::new_v4().simple().to_string();
+        upload(&ctx, "gc-first", &content_hash, user.id).await;
+        upload(&ctx, "gc-second", &content_hash, user.id).await;
+        ctx.blob_store
+            .put(&content_hash, "content".into())
+            .await
+            .unwrap();
+
+        // 另一个文件仍使用该内容时保留
+        ctx.storage.delete_file("gc-first").await.unwrap();
+        delete_content(&ctx.storage, ctx.blob_store.as_ref(), &content_hash)
+            .await
+            .unwrap();
+        assert!(
+            ctx.blob_store
+                .get(&content_hash, None)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // 删除标记存在期间上传相同内容需要等待
+        assert!(
+            ctx.storage
+                .begin_content_deletion(&content_hash, content_deletion_stale_before())
+                .await
+                .unwrap()
+        );
+        assert!(
+            !ctx.storage
+                .begin_content_deletion(&content_hash, content_deletion_stale_before())
+                .await
+                .unwrap()
+        );
+        let storage = ctx.storage.clone();
+        let hash = content_hash.clone();
+        let waiting =
+            actix_web::rt::spawn(async move { wait_for_content_deletion(&storage, &hash).await });
+        actix_web::rt::time::sleep(Duration::from_millis(300)).await;
+        assert!(!waiting.is_finished());
+        ctx.storage
+            .finish_content_deletion(&content_hash)
+            .await
+            .unwrap();
+        waiting.await.unwrap().unwrap();
+
+        // 最后一个文件删除后内容随之删除
+        ctx.storage.delete_file("gc-second").await.unwrap();
+        delete_content(&ctx.storage, ctx.blob_store.as_ref(), &content_hash)
+            .await
+            .unwrap();
+        assert!(
+            ctx.blob_store
+                .get(&content_hash, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            !ctx.storage
+                .is_content_being_deleted(&content_hash, content_deletion_stale_before())
+                .await
+                .unwrap()
+        );
+    }
+}
@@ -1,10 +1,18 @@
+pub mod access;
+pub mod avatar;
+pub mod citation;
 pub mod download;
+pub mod download_url;
+pub mod gc;
+pub mod resumable;
 pub mod upload;
 
 use actix_multipart::Multipart;
-use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web::Payload};
 use std::sync::Arc;
 
+use crate::blob_store::BlobStore;
+use crate::models::files::requests::CreateUploadSessionRequest;
 use crate::repository::Storage;
 
 pub struct FileService {
@@ -28,6 +36,14 @@ impl FileService {
         }
     }
 
+    pub(crate) fn get_blob_store(&self, request: &HttpRequest) -> Arc<dyn BlobStore> {
+        request
+            .app_data::<actix_web::web::Data<Arc<dyn BlobStore>>>()
+            .expect("Blob store not found in app data")
+            .get_ref()
+            .clone()
+    }
+
     // Handle file upload
     pub async fn handle_upload(
         &self,
@@ -45,4 +61,75 @@ impl FileService {
     ) -> ActixResult<HttpResponse> {
         download::handle_download(self, request, file_token).await
     }
+
+    // 通过签名链接下载文件
+    pub async fn handle_signed_download(
+        &self,
+        request: &HttpRequest,
+        file_token: String,
+        token: String,
+    ) -> ActixResult<HttpResponse> {
+        download::handle_signed_download(self, request, file_token, token).await
+    }
+
+    // 签发短期下载链接
+    pub async fn create_download_url(
+        &self,
+        request: &HttpRequest,
+        file_token: String,
+    ) -> ActixResult<HttpResponse> {
+        download_url::create_download_url(self, request, file_token).await
+    }
+
+    // 创建可续传上传会话
+    pub async fn create_upload_session(
+        &self,
+        request: &HttpRequest,
+        upload: CreateUploadSessionRequest,
+    ) -> ActixResult<HttpResponse> {
+        resumable::create_upload_session(self, request, upload).await
+    }
+
+    // 查询上传会话状态
+    pub async fn get_upload_session(
+        &self,
+        request: &HttpRequest,
+        upload_id: String,
+    ) -> ActixResult<HttpResponse> {
+        resumable::get_upload_session(self, request, upload_id).await
+    }
+
+    // 上传一个分块
+    pub async fn upload_chunk(
+        &self,
+        request: &HttpRequest,
+        upload_id: String,
+        offset: i64,
+        payload: Payload,
+    ) -> ActixResult<HttpResponse> {
+        resumable::upload_chunk(self, request, upload_id, offset, payload).await
+    }
+
+    // 合并分块完成上传
+    pub async fn complete_upload(
+        &self,
+        request: &HttpRequest,
+        upload_id: String,
+    ) -> ActixResult<HttpResponse> {
+        resumable::complete_upload(self, request, upload_id).await
+    }
+
+    // 取消上传并删除已接收的分块
+    pub async fn cancel_upload(
+        &self,
+        request: &HttpRequest,
+        upload_id: String,
+    ) -> ActixResult<HttpResponse> {
+        resumable::cancel_upload(self, request, upload_id).await
+    }
+
+    // 获取公开的头像图片
+    pub async fn handle_avatar(&self, file_name: String) -> ActixResult<HttpResponse> {
+        avatar::handle_avatar(self, file_name).await
+    }
 }
@@ -0,0 +1,421 @@
+use std::sync::Arc;
+
+use actix_web::web::{BytesMut, Payload};
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use futures_util::StreamExt;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::FileService;
+use super::gc::wait_for_content_deletion;
+use super::upload::{generate_submission_token, is_type_allowed};
+use crate::blob_store::{BlobStore, MIN_COMPOSE_PART_SIZE};
+use crate::middlewares::RequireJWT;
+use crate::models::files::entities::UploadSession;
+use crate::models::files::requests::CreateUploadSessionRequest;
+use crate::models::files::responses::{FileUploadResponse, UploadSessionResponse};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+use crate::system::app_config::AppConfig;
+use crate::utils::sha256_state::Sha256State;
+
+fn unauthorized() -> HttpResponse {
+    HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+        ErrorCode::Unauthorized,
+        "Unauthorized: missing user claims",
+    ))
+}
+
+fn session_expiry() -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc::now() + chrono::Duration::seconds(AppConfig::get().upload.session_expiry)
+}
+
+// 获取当前用户未过期的上传会话，其他用户的会话同样视为不存在
+async fn get_own_session(
+    storage: &Arc<dyn Storage>,
+    upload_id: &str,
+    user_id: i64,
+) -> std::result::Result<UploadSession, HttpResponse> {
+    match storage.get_upload_session(upload_id).await {
+        Ok(Some(session))
+            if session.user_id == user_id && session.expires_at > chrono::Utc::now() =>
+        {
+            Ok(session)
+        }
+        Ok(_) => Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UploadSessionNotFound,
+            "Upload session not found or expired",
+        ))),
+        Err(e) => Err(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Upload session query failed: {e}"),
+            )),
+        ),
+    }
+}
+
+// 会话中保存的哈希中间状态，升级前创建且已接收分块的会话没有保存状态，无法继续
+fn received_hash(session: &UploadSession) -> Option<Sha256State> {
+    if session.hash_state.is_empty() && session.upload_offset == 0 {
+        return Some(Sha256State::new());
+    }
+    Sha256State::decode(&session.hash_state)
+}
+
+fn session_not_resumable() -> HttpResponse {
+    HttpResponse::Conflict().json(ApiResponse::error_empty(
+        ErrorCode::UploadSessionNotFound,
+        "Upload session can no longer be resumed, please start a new upload",
+    ))
+}
+
+/// 删除上传会话已写入的分块，失败时只记录日志
+pub(crate) async fn remove_upload_chunks(blob_store: &dyn BlobStore, session: &UploadSession) {
+    let mut keys = session.chunk_keys();
+    // 升级前创建的会话没有记录分块 ID，分块按序号命名
+    if keys.is_empty() && session.upload_offset > 0 {
+        keys = (0..=session.upload_offset / session.chunk_size)
+            .map(|index| format!("partial/{}/{index}", session.id))
+            .collect();
+    }
+    for key in keys {
+        if let Err(e) = blob_store.delete(&key).await {
+            warn!("Failed to delete upload chunk {}: {}", key, e);
+        }
+    }
+}
+
+pub async fn create_upload_session(
+    service: &FileService,
+    request: &HttpRequest,
+    upload: CreateUploadSessionRequest,
+) -> ActixResult<HttpResponse> {
+    let config = &AppConfig::get().upload;
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+
+    if upload.file_name.is_empty() || upload.file_name.chars().count() > 255 {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::BadRequest,
+            "File name must be 1 to 255 characters",
+        )));
+    }
+    if !is_type_allowed(&upload.file_type) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::FileTypeNotAllowed,
+            "File type not allowed",
+        )));
+    }
+    // 空文件没有可以拼接的分块，使用普通上传即可
+    if upload.size <= 0 {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::BadRequest,
+            "Resumable uploads must not be empty",
+        )));
+    }
+    if upload.size as u64 > config.resumable_max_size as u64 {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::FileSizeExceeded,
+            "File size exceeds the limit",
+        )));
+    }
+
+    // 拼接分块时除最后一块外每块不能小于文件存储的下限
+    let chunk_size = config.chunk_size.max(MIN_COMPOSE_PART_SIZE) as i64;
+    let id = Uuid::new_v4().to_string();
+
+    match service
+        .get_storage(request)
+        .create_upload_session(
+            &id,
+            user_id,
+            &upload,
+            chunk_size,
+            config.max_open_sessions,
+            session_expiry(),
+        )
+        .await
+    {
+        Ok(Some(session)) => Ok(HttpResponse::Created().json(ApiResponse::success(
+            UploadSessionResponse::from(session),
+            "Upload session created",
+        ))),
+        Ok(None) => Ok(
+            HttpResponse::TooManyRequests().json(ApiResponse::error_empty(
+                ErrorCode::UploadSessionLimitExceeded,
+                "Too many unfinished uploads, complete or cancel one first",
+            )),
+        ),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                format!("Failed to create upload session: {e}"),
+            )),
+        ),
+    }
+}
+
+pub async fn get_upload_session(
+    service: &FileService,
+    request: &HttpRequest,
+    upload_id: String,
+) -> ActixResult<HttpResponse> {
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+
+    match get_own_session(&service.get_storage(request), &upload_id, user_id).await {
+        Ok(session) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            UploadSessionResponse::from(session),
+            "Upload session retrieved",
+        ))),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// 接收一个分块
+///
+/// 分块必须从会话已接收的字节数开始，除最后一块外大小必须恰好为 `chunk_size`。
+/// 只有完整接收的分块才会计入进度，连接中断后从会话返回的 `offset` 重新发送即可。
+/// 每个分块写入新的键，并随会话进度一起更新内容哈希的中间状态。
+pub async fn upload_chunk(
+    service: &FileService,
+    request: &HttpRequest,
+    upload_id: String,
+    offset: i64,
+    mut payload: Payload,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+    let session = match get_own_session(&storage, &upload_id, user_id).await {
+        Ok(session) => session,
+        Err(resp) => return Ok(resp),
+    };
+
+    let current_offset = session.upload_offset;
+    if offset != current_offset {
+        return Ok(HttpResponse::Conflict().json(ApiResponse::error(
+            ErrorCode::UploadOffsetMismatch,
+            UploadSessionResponse::from(session),
+            format!("Upload offset mismatch, expected {current_offset}"),
+        )));
+    }
+
+    let expected = session.chunk_size.min(session.file_size - offset) as usize;
+    if expected == 0 {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UploadChunkInvalid,
+            "All data has been received",
+        )));
+    }
+
+    let mut chunk = BytesMut::with_capacity(expected);
+    while let Some(data) = payload.next().await {
+        let data = data?;
+        if chunk.len() + data.len() > expected {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::UploadChunkInvalid,
+                format!("Chunk must be exactly {expected} bytes"),
+            )));
+        }
+        chunk.extend_from_slice(&data);
+    }
+    if chunk.len() != expected {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UploadChunkInvalid,
+            format!("Chunk must be exactly {expected} bytes"),
+        )));
+    }
+
+    let Some(mut hash) = received_hash(&session) else {
+        return Ok(session_not_resumable());
+    };
+    hash.update(&chunk);
+
+    // 并发写入同一位置的请求使用不同的键，未能更新会话的一方只删除自己写入的分块
+    let chunk_id = Uuid::new_v4().simple().to_string();
+    let key = session.chunk_key(session.chunk_keys().len(), &chunk_id);
+    let blob_store = service.get_blob_store(request);
+    if let Err(e) = blob_store.put(&key, chunk.freeze()).await {
+        error!("Failed to store upload chunk {}: {}", key, e);
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                "Failed to store chunk",
+            )),
+        );
+    }
+
+    let new_offset = offset + expected as i64;
+    let chunk_ids = format!("{} {chunk_id}", session.chunk_ids)
+        .trim_start()
+        .to_string();
+    let hash_state = hash.encode();
+    let expires_at = session_expiry();
+    let advanced = storage
+        .advance_upload_session(
+            &upload_id,
+            offset,
+            new_offset,
+            &chunk_ids,
+            &hash_state,
+            expires_at,
+        )
+        .await;
+    if !matches!(advanced, Ok(true))
+        && let Err(e) = blob_store.delete(&key).await
+    {
+        warn!("Failed to delete upload chunk {}: {}", key, e);
+    }
+
+    match advanced {
+        Ok(true) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            UploadSessionResponse::from(UploadSession {
+                upload_offset: new_offset,
+                chunk_ids,
+                hash_state,
+                expires_at,
+                ..session
+            }),
+            "Chunk uploaded successfully",
+        ))),
+        // 同一位置的分块已由并发的请求写入，或会话已被取消
+        Ok(false) => Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::UploadOffsetMismatch,
+            "Upload session changed, query its status before retrying",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                format!("Failed to update upload session: {e}"),
+            )),
+        ),
+    }
+}
+
+/// 全部分块接收完成后合并为文件，返回与普通上传相同的文件信息
+pub async fn complete_upload(
+    service: &FileService,
+    request: &HttpRequest,
+    upload_id: String,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let blob_store = service.get_blob_store(request);
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+    let session = match get_own_session(&storage, &upload_id, user_id).await {
+        Ok(session) => session,
+        Err(resp) => return Ok(resp),
+    };
+
+    if session.upload_offset != session.file_size {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error(
+            ErrorCode::UploadIncomplete,
+            UploadSessionResponse::from(session),
+            "File has not been fully uploaded",
+        )));
+    }
+
+    // 内容哈希在接收分块时已逐块计算，无需重新读取分块
+    let chunks = session.chunk_keys();
+    let chunk_count = (session.file_size + session.chunk_size - 1) / session.chunk_size;
+    let Some(hash) = received_hash(&session).filter(|_| chunks.len() as i64 == chunk_count) else {
+        return Ok(session_not_resumable());
+    };
+    let content_hash = hash.finalize();
+
+    // 与普通上传相同，先写入数据库记录再写入内容
+    let submission_token = generate_submission_token();
+    let file = match storage
+        .upload_file(
+            &submission_token,
+            &session.file_name,
+            &session.file_size,
+            &session.file_type,
+            &content_hash,
+            user_id,
+        )
+        .await
+    {
+        Ok(file) => file,
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::FileUploadFailed,
+                    format!("Failed to upload file: {e}"),
+                )),
+            );
+        }
+    };
+
+    let stored = match wait_for_content_deletion(&storage, &content_hash).await {
+        Ok(()) => blob_store.compose(file.blob_key(), &chunks).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        error!("Failed to assemble upload {}: {}", upload_id, e);
+        if let Err(e) = storage.delete_file(&submission_token).await {
+            warn!("Failed to remove file record {}: {}", submission_token, e);
+        }
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                "Failed to store file",
+            )),
+        );
+    }
+
+    // 分块已合并，删除会话失败时由回收任务在会话过期后清理
+    match storage.delete_upload_session(&upload_id).await {
+        Ok(_) => remove_upload_chunks(blob_store.as_ref(), &session).await,
+        Err(e) => warn!("Failed to delete upload session {}: {}", upload_id, e),
+    }
+
+    Ok(HttpResponse::Ok().json(ApiResponse::success(
+        FileUploadResponse {
+            submission_token: file.submission_token,
+            file_name: file.file_name,
+            size: file.file_size,
+            content_type: file.file_type,
+            uploaded_at: file.uploaded_at,
+        },
+        "File uploaded successfully",
+    )))
+}
+
+pub async fn cancel_upload(
+    service: &FileService,
+    request: &HttpRequest,
+    upload_id: String,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let Some(user_id) = RequireJWT::extract_user_id(request) else {
+        return Ok(unauthorized());
+    };
+    let session = match get_own_session(&storage, &upload_id, user_id).await {
+        Ok(session) => session,
+        Err(resp) => return Ok(resp),
+    };
+
+    match storage.delete_upload_session(&upload_id).await {
+        Ok(_) => {
+            remove_upload_chunks(service.get_blob_store(request).as_ref(), &session).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty("Upload cancelled")))
+        }
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to cancel upload: {e}"),
+            )),
+        ),
+    }
+}
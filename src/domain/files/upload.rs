@@ -1,19 +1,32 @@
 use actix_multipart::Multipart;
+use actix_web::web::BytesMut;
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 use futures_util::TryStreamExt;
 use futures_util::stream::StreamExt;
-use std::fs;
-use std::io::Write;
-use std::{fs::File, path::Path};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 use super::FileService;
-use crate::errors::HWSystemError;
+use super::gc::wait_for_content_deletion;
 use crate::middlewares::RequireJWT;
 use crate::models::ErrorCode;
 use crate::models::{ApiResponse, files::responses::FileUploadResponse};
 use crate::system::app_config::AppConfig;
 
+// 文件标识，也是文件的提交令牌
+pub(super) fn generate_submission_token() -> String {
+    format!("{}-{}", chrono::Utc::now().timestamp(), Uuid::new_v4())
+}
+
+/// 校验文件类型是否在允许上传的类型中
+pub(super) fn is_type_allowed(file_type: &str) -> bool {
+    AppConfig::get()
+        .upload
+        .allowed_types
+        .iter()
+        .any(|t| file_type.contains(t))
+}
+
 pub async fn handle_upload(
     service: &FileService,
     req: &HttpRequest,
@@ -21,19 +34,7 @@ pub async fn handle_upload(
 ) -> ActixResult<HttpResponse> {
     // 获取配置
     let config = AppConfig::get();
-    let upload_dir = &config.upload.dir;
     let max_size = config.upload.max_size;
-    let allowed_types = &config.upload.allowed_types;
-
-    // 确保上传目录存在
-    if !Path::new(upload_dir).exists() {
-        fs::create_dir_all(upload_dir).map_err(|e| {
-            tracing::error!("{}", HWSystemError::file_operation(format!("{e}")));
-            actix_web::error::ErrorInternalServerError(HWSystemError::file_operation(
-                "file create error",
-            ))
-        })?;
-    }
 
     // 文件相关信息
     let mut submission_token = String::new();
@@ -41,6 +42,8 @@ pub async fn handle_upload(
     let mut file_size: i64 = 0;
     let mut file_uploaded = false;
     let mut file_type = String::new();
+    let mut content = BytesMut::new();
+    let mut hasher = Sha256::new();
 
     while let Ok(Some(mut field)) = payload.try_next().await {
         let content_disposition = field.content_disposition();
@@ -63,7 +66,7 @@ pub async fn handle_upload(
                 .map(|ct| ct.to_string())
                 .unwrap_or_default();
             // 校验类型
-            if !allowed_types.iter().any(|t| file_type.contains(t)) {
+            if !is_type_allowed(&file_type) {
                 return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
                     ErrorCode::FileTypeNotAllowed,
                     "File type not allowed",
@@ -76,30 +79,21 @@ pub async fn handle_upload(
                 .map(|s| s.to_string())
                 .unwrap_or_default();
 
-            submission_token = format!("{}-{}", chrono::Utc::now().timestamp(), Uuid::new_v4());
-            let file_path = format!("{upload_dir}/{submission_token}.bin");
-            let mut f = File::create(&file_path).map_err(|e| {
-                tracing::error!("{}", HWSystemError::file_operation(format!("{e}")));
-                actix_web::error::ErrorInternalServerError(HWSystemError::file_operation(
-                    "file create error",
-                ))
-            })?;
+            submission_token = generate_submission_token();
 
-            let mut total_size: usize = 0;
             while let Some(chunk) = field.next().await {
                 let data = chunk?;
-                total_size += data.len();
                 // 校验大小
-                if total_size > max_size {
-                    let _ = fs::remove_file(&file_path);
+                if content.len() + data.len() > max_size {
                     return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
                         ErrorCode::FileSizeExceeded,
                         "File size exceeds the limit",
                     )));
                 }
-                f.write_all(&data)?;
+                hasher.update(&data);
+                content.extend_from_slice(&data);
             }
-            file_size = total_size as i64;
+            file_size = content.len() as i64;
         }
     }
 
@@ -111,27 +105,27 @@ pub async fn handle_upload(
     }
 
     let storage = service.get_storage(req);
+    let blob_store = service.get_blob_store(req);
 
     let user_id = RequireJWT::extract_user_id(req)
         .ok_or_else(|| actix_web::error::ErrorUnauthorized("User not authenticated"))?;
 
-    let db_file = match storage
+    // 内容相同的文件共用以内容哈希为键的同一份存储
+    let content_hash = format!("{:x}", hasher.finalize());
+
+    // 先写入数据库记录，回收任务据此判断内容是否仍被使用，不会删除正在写入的内容
+    let file = match storage
         .upload_file(
             &submission_token,
             &file_name,
             &file_size,
             &file_type,
+            &content_hash,
             user_id,
         )
         .await
     {
-        Ok(file) => FileUploadResponse {
-            submission_token: file.submission_token,
-            file_name: file.file_name,
-            size: file.file_size,
-            content_type: file.file_type,
-            uploaded_at: file.uploaded_at,
-        },
+        Ok(file) => file,
         Err(e) => {
             return Ok(
                 HttpResponse::InternalServerError().json(ApiResponse::error_empty(
@@ -142,5 +136,32 @@ pub async fn handle_upload(
         }
     };
 
+    // 相同内容会写入同一个键，重复写入可以补全此前写入失败的内容
+    // 回收任务正在删除相同内容时，等待删除完成后再写入
+    let stored = match wait_for_content_deletion(&storage, &content_hash).await {
+        Ok(()) => blob_store.put(file.blob_key(), content.freeze()).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        tracing::error!("Failed to store uploaded file: {}", e);
+        if let Err(e) = storage.delete_file(&submission_token).await {
+            tracing::warn!("Failed to remove file record {}: {}", submission_token, e);
+        }
+        return Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::FileUploadFailed,
+                "Failed to store file",
+            )),
+        );
+    }
+
+    let db_file = FileUploadResponse {
+        submission_token: file.submission_token,
+        file_name: file.file_name,
+        size: file.file_size,
+        content_type: file.file_type,
+        uploaded_at: file.uploaded_at,
+    };
+
     Ok(HttpResponse::Ok().json(ApiResponse::success(db_file, "File uploaded successfully")))
 }
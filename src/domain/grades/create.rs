@@ -0,0 +1,84 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use super::GradeService;
+use super::get::{can_grade, get_submission_with_member};
+use crate::middlewares::RequireJWT;
+use crate::models::grades::requests::CreateGradeRequest;
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn create_grade(
+    service: &GradeService,
+    request: &HttpRequest,
+    submission_id: i64,
+    grade_data: CreateGradeRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    let (submission, homework, class_user) =
+        match get_submission_with_member(&storage, submission_id, &user).await {
+            Ok(result) => result,
+            Err(resp) => return Ok(resp),
+        };
+
+    if !can_grade(&user, class_user.as_ref()) {
+        return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ClassPermissionDenied,
+            "You do not have permission to grade this submission",
+        )));
+    }
+
+    // 不允许为自己的提交评分
+    if submission.creator_id == user.id {
+        return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ClassPermissionDenied,
+            "Cannot grade your own submission",
+        )));
+    }
+
+    // 分数必须在 0 到作业最高分之间
+    if !(grade_data.score.is_finite()
+        && grade_data.score >= 0.0
+        && grade_data.score <= homework.max_score)
+    {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::GradeInvalid,
+            format!("Score must be between 0 and {}", homework.max_score),
+        )));
+    }
+
+    match storage
+        .create_grade(submission_id, user.id, grade_data)
+        .await
+    {
+        Ok(grade) => {
+            info!(
+                "Submission {} graded {} by {}",
+                submission_id, grade.score, user.id
+            );
+            Ok(HttpResponse::Created().json(ApiResponse::success(
+                grade,
+                "Submission graded successfully",
+            )))
+        }
+        Err(e) => {
+            error!("Grade creation failed: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::GradeCreationFailed,
+                    format!("Grade creation failed: {e}"),
+                )),
+            )
+        }
+    }
+}
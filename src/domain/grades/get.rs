@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::GradeService;
+use crate::domain::submissions::get::get_homework_with_member;
+use crate::middlewares::RequireJWT;
+use crate::models::class_users::entities::{ClassUser, ClassUserRole};
+use crate::models::homeworks::entities::Homework;
+use crate::models::submissions::entities::Submission;
+use crate::models::users::entities::{User, UserRole};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+use crate::system::app_config::AppConfig;
+
+pub async fn get_latest_grade(
+    service: &GradeService,
+    request: &HttpRequest,
+    submission_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    if let Err(resp) = check_grade_readable(&storage, submission_id, &user).await {
+        return Ok(resp);
+    }
+
+    match storage
+        .get_latest_grade_by_submission_id(submission_id)
+        .await
+    {
+        Ok(Some(grade)) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            grade,
+            "Grade information retrieved successfully",
+        ))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::GradeNotFound,
+            "Submission has not been graded yet",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to get grade information: {e}"),
+            )),
+        ),
+    }
+}
+
+/// 获取提交及其所属作业，并返回当前用户在班级中的身份
+pub(crate) async fn get_submission_with_member(
+    storage: &Arc<dyn Storage>,
+    submission_id: i64,
+    user: &User,
+) -> Result<(Submission, Homework, Option<ClassUser>), HttpResponse> {
+    let submission = match storage.get_submission_by_id(submission_id).await {
+        Ok(Some(submission)) => submission,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::SubmissionNotFound,
+                "Submission not found",
+            )));
+        }
+        Err(e) => {
+            return Err(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to get submission information: {e}"),
+                )),
+            );
+        }
+    };
+
+    let (homework, class_user) =
+        get_homework_with_member(storage, submission.homework_id, user).await?;
+
+    Ok((submission, homework, class_user))
+}
+
+/// 判断用户是否可以评分：管理员、班级教师，以及配置允许时的课代表
+pub(crate) fn can_grade(user: &User, class_user: Option<&ClassUser>) -> bool {
+    if user.role == UserRole::Admin {
+        return true;
+    }
+
+    match class_user.map(|cu| &cu.role) {
+        Some(ClassUserRole::Teacher) => true,
+        Some(ClassUserRole::ClassRepresentative) => {
+            AppConfig::get().homework.class_representative_can_grade
+        }
+        _ => false,
+    }
+}
+
+/// 评分查看权限校验：提交者本人或有评分权限的用户
+pub(crate) async fn check_grade_readable(
+    storage: &Arc<dyn Storage>,
+    submission_id: i64,
+    user: &User,
+) -> Result<(), HttpResponse> {
+    let (submission, _, class_user) =
+        get_submission_with_member(storage, submission_id, user).await?;
+
+    if submission.creator_id != user.id && !can_grade(user, class_user.as_ref()) {
+        return Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ClassPermissionDenied,
+            "You do not have permission to view this grade",
+        )));
+    }
+
+    Ok(())
+}
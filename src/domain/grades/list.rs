@@ -0,0 +1,38 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::GradeService;
+use super::get::check_grade_readable;
+use crate::middlewares::RequireJWT;
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn list_grades(
+    service: &GradeService,
+    request: &HttpRequest,
+    submission_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    if let Err(resp) = check_grade_readable(&storage, submission_id, &user).await {
+        return Ok(resp);
+    }
+
+    match storage.list_grades_by_submission_id(submission_id).await {
+        Ok(resp) => Ok(HttpResponse::Ok().json(ApiResponse::success(resp, "获取评分历史成功"))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("获取评分历史失败: {e}"),
+            )),
+        ),
+    }
+}
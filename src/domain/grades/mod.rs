@@ -0,0 +1,59 @@
+pub mod create;
+pub mod get;
+pub mod list;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+
+use crate::models::grades::requests::CreateGradeRequest;
+use crate::repository::Storage;
+
+pub struct GradeService {
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl GradeService {
+    pub fn new_lazy() -> Self {
+        Self { storage: None }
+    }
+
+    pub(crate) fn get_storage(&self, request: &HttpRequest) -> Arc<dyn Storage> {
+        if let Some(storage) = &self.storage {
+            storage.clone()
+        } else {
+            request
+                .app_data::<actix_web::web::Data<Arc<dyn Storage>>>()
+                .expect("Storage not found in app data")
+                .get_ref()
+                .clone()
+        }
+    }
+
+    // 为提交评分
+    pub async fn create_grade(
+        &self,
+        request: &HttpRequest,
+        submission_id: i64,
+        grade_data: CreateGradeRequest,
+    ) -> ActixResult<HttpResponse> {
+        create::create_grade(self, request, submission_id, grade_data).await
+    }
+
+    // 获取提交的最新评分
+    pub async fn get_latest_grade(
+        &self,
+        request: &HttpRequest,
+        submission_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        get::get_latest_grade(self, request, submission_id).await
+    }
+
+    // 获取提交的评分历史
+    pub async fn list_grades(
+        &self,
+        request: &HttpRequest,
+        submission_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        list::list_grades(self, request, submission_id).await
+    }
+}
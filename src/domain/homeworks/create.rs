@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use super::HomeworkService;
+use crate::domain::files::access::can_read_file;
+use crate::domain::files::citation::refresh_citations;
+use crate::middlewares::RequireJWT;
+use crate::models::homeworks::requests::CreateHomeworkRequest;
+use crate::models::users::entities::User;
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+
+pub async fn create_homework(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    class_id: i64,
+    homework_data: CreateHomeworkRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+    let uid = user.id;
+
+    // 参数校验
+    if let Err(resp) =
+        validate_homework_fields(Some(&homework_data.title), Some(homework_data.max_score))
+    {
+        return Ok(resp);
+    }
+
+    // 附件校验
+    if let Err(resp) = check_attachments_exist(&storage, &homework_data.attachments, &user).await {
+        return Ok(resp);
+    }
+
+    // 管理员不经过班级成员校验，需要确认班级存在
+    match storage.get_class_by_id(class_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::ClassNotFound,
+                "Class not found",
+            )));
+        }
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to get class information: {e}"),
+                )),
+            );
+        }
+    }
+
+    match storage.create_homework(class_id, uid, homework_data).await {
+        Ok(homework) => {
+            info!(
+                "Homework {} created in class {} by {}",
+                homework.id, class_id, uid
+            );
+            refresh_citations(&storage, &homework.attachments).await;
+            Ok(HttpResponse::Created().json(ApiResponse::success(
+                homework,
+                "Homework created successfully",
+            )))
+        }
+        Err(e) => {
+            error!("Homework creation failed: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::HomeworkCreationFailed,
+                    format!("Homework creation failed: {e}"),
+                )),
+            )
+        }
+    }
+}
+
+/// 作业字段校验辅助函数
+pub(crate) fn validate_homework_fields(
+    title: Option<&str>,
+    max_score: Option<f64>,
+) -> Result<(), HttpResponse> {
+    if let Some(title) = title
+        && (title.trim().is_empty() || title.chars().count() > 128)
+    {
+        return Err(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::HomeworkInvalid,
+            "Homework title must be between 1 and 128 characters",
+        )));
+    }
+
+    if let Some(max_score) = max_score
+        && !(max_score.is_finite() && max_score > 0.0)
+    {
+        return Err(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::HomeworkInvalid,
+            "Homework max_score must be a positive number",
+        )));
+    }
+
+    Ok(())
+}
+
+/// 附件校验辅助函数，确保所有附件均为已上传且当前用户有权读取的文件
+/// 附件对班级成员可见，不允许引用无权读取的文件来获取访问权限
+pub(crate) async fn check_attachments_exist(
+    storage: &Arc<dyn Storage>,
+    attachments: &[String],
+    user: &User,
+) -> Result<(), HttpResponse> {
+    for token in attachments {
+        let file = match storage.get_file_by_token(token).await {
+            Ok(Some(file)) => file,
+            Ok(None) => {
+                return Err(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                    ErrorCode::FileNotFound,
+                    format!("Attachment not found: {token}"),
+                )));
+            }
+            Err(e) => {
+                return Err(
+                    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                        ErrorCode::InternalServerError,
+                        format!("Failed to check attachment: {e}"),
+                    )),
+                );
+            }
+        };
+        match can_read_file(storage, &file, user).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                    ErrorCode::FileAccessDenied,
+                    format!("No permission to use attachment: {token}"),
+                )));
+            }
+            Err(e) => {
+                return Err(
+                    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                        ErrorCode::InternalServerError,
+                        format!("Failed to check attachment: {e}"),
+                    )),
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::warn;
+
+use super::HomeworkService;
+use super::get::get_class_homework;
+use crate::domain::files::citation::{list_submission_attachments, refresh_citations};
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn delete_homework(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    class_id: i64,
+    homework_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    // 确认作业属于该班级
+    let homework = match get_class_homework(&storage, class_id, homework_id).await {
+        Ok(homework) => homework,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 提交会随作业级联删除，先记录其附件以便删除后重新统计引用次数
+    let mut attachments = homework.attachments;
+    match list_submission_attachments(&storage, homework_id).await {
+        Ok(submission_attachments) => attachments.extend(submission_attachments),
+        Err(e) => warn!(
+            "Failed to list submission attachments of homework {}: {}",
+            homework_id, e
+        ),
+    }
+
+    match storage.delete_homework(homework_id).await {
+        Ok(true) => {
+            refresh_citations(&storage, &attachments).await;
+            Ok(
+                HttpResponse::Ok()
+                    .json(ApiResponse::success_empty("Homework deleted successfully")),
+            )
+        }
+        Ok(false) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::HomeworkNotFound,
+            "Homework not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::HomeworkDeleteFailed,
+                format!("Homework deletion failed: {e}"),
+            )),
+        ),
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::HomeworkService;
+use crate::models::homeworks::entities::Homework;
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+
+pub async fn get_homework(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    class_id: i64,
+    homework_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    match get_class_homework(&storage, class_id, homework_id).await {
+        Ok(homework) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            homework,
+            "Homework information retrieved successfully",
+        ))),
+        Err(resp) => Ok(resp),
+    }
+}
+
+/// 获取指定班级下的作业，作业不存在或不属于该班级时返回 404
+pub(crate) async fn get_class_homework(
+    storage: &Arc<dyn Storage>,
+    class_id: i64,
+    homework_id: i64,
+) -> Result<Homework, HttpResponse> {
+    match storage.get_homework_by_id(homework_id).await {
+        Ok(Some(homework)) if homework.class_id == class_id => Ok(homework),
+        Ok(_) => Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::HomeworkNotFound,
+            "Homework not found",
+        ))),
+        Err(e) => Err(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to get homework information: {e}"),
+            )),
+        ),
+    }
+}
@@ -1,16 +1,71 @@
-use crate::models::{ApiResponse, ErrorCode, homeworks::requests::HomeworkListQuery};
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 
 use super::HomeworkService;
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    homeworks::requests::{HomeworkListParams, HomeworkListQuery},
+    users::entities::UserRole,
+};
 
 pub async fn list_homeworks(
     service: &HomeworkService,
     request: &HttpRequest,
-    query: HomeworkListQuery,
+    query: HomeworkListParams,
+) -> ActixResult<HttpResponse> {
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    let mut list_query = build_list_query(query);
+
+    // 管理员可查看全部作业，其他用户只能查看自己所在班级的作业
+    if user.role != UserRole::Admin {
+        list_query.user_id = Some(user.id);
+    }
+
+    query_homeworks(service, request, list_query).await
+}
+
+pub async fn list_class_homeworks(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    class_id: i64,
+    query: HomeworkListParams,
+) -> ActixResult<HttpResponse> {
+    let mut list_query = build_list_query(query);
+    list_query.class_id = Some(class_id);
+
+    query_homeworks(service, request, list_query).await
+}
+
+fn build_list_query(query: HomeworkListParams) -> HomeworkListQuery {
+    HomeworkListQuery {
+        page: Some(query.pagination.page),
+        size: Some(query.pagination.size),
+        class_id: None,
+        user_id: None,
+        status: query.status,
+        search: query.search,
+        order_by: query.order_by,
+        order: query.order,
+    }
+}
+
+async fn query_homeworks(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    list_query: HomeworkListQuery,
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
 
-    match storage.list_homeworks_with_pagination(query).await {
+    match storage.list_homeworks_with_pagination(list_query).await {
         Ok(resp) => Ok(HttpResponse::Ok().json(ApiResponse::success(resp, "获取作业列表成功"))),
         Err(e) => Ok(
             HttpResponse::InternalServerError().json(ApiResponse::error_empty(
@@ -1,11 +1,16 @@
+pub mod create;
+pub mod delete;
+pub mod get;
 pub mod list;
+pub mod update;
 
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 use std::sync::Arc;
 
-use crate::models::homeworks::requests::HomeworkListQuery;
+use crate::models::homeworks::requests::{
+    CreateHomeworkRequest, HomeworkListParams, UpdateHomeworkRequest,
+};
 use crate::repository::Storage;
-use crate::system::app_config::AppConfig;
 
 pub struct HomeworkService {
     storage: Option<Arc<dyn Storage>>,
@@ -28,15 +33,63 @@ impl HomeworkService {
         }
     }
 
-    pub(crate) fn get_config(&self) -> &AppConfig {
-        AppConfig::get()
-    }
-
+    // 获取当前用户可见的作业列表
     pub async fn list_homeworks(
         &self,
         request: &HttpRequest,
-        query: HomeworkListQuery,
+        query: HomeworkListParams,
     ) -> ActixResult<HttpResponse> {
         list::list_homeworks(self, request, query).await
     }
+
+    // 获取班级作业列表
+    pub async fn list_class_homeworks(
+        &self,
+        request: &HttpRequest,
+        class_id: i64,
+        query: HomeworkListParams,
+    ) -> ActixResult<HttpResponse> {
+        list::list_class_homeworks(self, request, class_id, query).await
+    }
+
+    // 发布作业
+    pub async fn create_homework(
+        &self,
+        request: &HttpRequest,
+        class_id: i64,
+        homework_data: CreateHomeworkRequest,
+    ) -> ActixResult<HttpResponse> {
+        create::create_homework(self, request, class_id, homework_data).await
+    }
+
+    // 获取作业详情
+    pub async fn get_homework(
+        &self,
+        request: &HttpRequest,
+        class_id: i64,
+        homework_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        get::get_homework(self, request, class_id, homework_id).await
+    }
+
+    // 更新作业
+    pub async fn update_homework(
+        &self,
+        request: &HttpRequest,
+        class_id: i64,
+        homework_id: i64,
+        update_data: UpdateHomeworkRequest,
+    ) -> ActixResult<HttpResponse> {
+        update::update_homework(self, request, class_id, homework_id, update_data).await
+    }
+
+    // 删除作业
+    pub async fn delete_homework(
+        &self,
+        request: &HttpRequest,
+        class_id: i64,
+        homework_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        delete::delete_homework(self, request, class_id, homework_id).await
+    }
 }
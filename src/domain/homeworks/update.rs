@@ -0,0 +1,79 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::error;
+
+use super::HomeworkService;
+use super::create::{check_attachments_exist, validate_homework_fields};
+use super::get::get_class_homework;
+use crate::domain::files::citation::refresh_citations;
+use crate::middlewares::RequireJWT;
+use crate::models::homeworks::requests::UpdateHomeworkRequest;
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn update_homework(
+    service: &HomeworkService,
+    request: &HttpRequest,
+    class_id: i64,
+    homework_id: i64,
+    update_data: UpdateHomeworkRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    // 确认作业属于该班级
+    let previous = match get_class_homework(&storage, class_id, homework_id).await {
+        Ok(homework) => homework,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 参数校验
+    if let Err(resp) = validate_homework_fields(update_data.title.as_deref(), update_data.max_score)
+    {
+        return Ok(resp);
+    }
+
+    // 附件校验
+    if let Some(attachments) = &update_data.attachments
+        && let Err(resp) = check_attachments_exist(&storage, attachments, &user).await
+    {
+        return Ok(resp);
+    }
+
+    match storage.update_homework(homework_id, update_data).await {
+        Ok(Some(homework)) => {
+            // 附件变化时重新统计新旧附件的引用次数
+            if homework.attachments != previous.attachments {
+                refresh_citations(
+                    &storage,
+                    previous.attachments.iter().chain(&homework.attachments),
+                )
+                .await;
+            }
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                homework,
+                "Homework updated successfully",
+            )))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::HomeworkNotFound,
+            "Homework not found",
+        ))),
+        Err(e) => {
+            error!("Homework update failed: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::HomeworkUpdateFailed,
+                    format!("Homework update failed: {e}"),
+                )),
+            )
+        }
+    }
+}
@@ -2,7 +2,9 @@ pub mod auth;
 pub mod class_users;
 pub mod classes;
 pub mod files;
+pub mod grades;
 pub mod homeworks;
+pub mod submissions;
 pub mod system;
 pub mod users;
 
@@ -10,6 +12,8 @@ pub use auth::AuthService;
 pub use class_users::ClassUserService;
 pub use classes::ClassService;
 pub use files::FileService;
+pub use grades::GradeService;
 pub use homeworks::HomeworkService;
+pub use submissions::SubmissionService;
 pub use system::SystemService;
 pub use users::UserService;
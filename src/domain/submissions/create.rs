@@ -0,0 +1,120 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use super::SubmissionService;
+use super::get::get_homework_with_member;
+use crate::domain::files::citation::refresh_citations;
+use crate::domain::homeworks::create::check_attachments_exist;
+use crate::middlewares::RequireJWT;
+use crate::models::class_users::entities::ClassUserRole;
+use crate::models::submissions::requests::CreateSubmissionRequest;
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn create_submission(
+    service: &SubmissionService,
+    request: &HttpRequest,
+    homework_id: i64,
+    submission_data: CreateSubmissionRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    let (homework, class_user) = match get_homework_with_member(&storage, homework_id, &user).await
+    {
+        Ok(result) => result,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 只有班级中的学生和课代表可以提交作业
+    match class_user.map(|cu| cu.role) {
+        Some(ClassUserRole::Student) | Some(ClassUserRole::ClassRepresentative) => {}
+        _ => {
+            return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+                ErrorCode::SubmissionForbidden,
+                "Only students of this class can submit homework",
+            )));
+        }
+    }
+
+    // 参数校验，内容和附件不能同时为空
+    if submission_data.content.trim().is_empty() && submission_data.attachments.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::SubmissionInvalid,
+            "Submission content and attachments cannot both be empty",
+        )));
+    }
+
+    // 附件校验
+    if let Err(resp) = check_attachments_exist(&storage, &submission_data.attachments, &user).await
+    {
+        return Ok(resp);
+    }
+
+    // 截止时间校验，允许迟交时标记为迟交
+    let is_late = homework.is_expired_at(chrono::Utc::now());
+    if is_late && !homework.allow_late_submission {
+        return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::SubmissionDeadlinePassed,
+            "Homework deadline has passed and late submission is not allowed",
+        )));
+    }
+
+    // 重复提交会覆盖之前的附件，记录旧附件以便重新统计引用次数
+    let previous_attachments = match storage
+        .get_submission_by_homework_and_creator(homework_id, user.id)
+        .await
+    {
+        Ok(previous) => previous.map(|s| s.attachments).unwrap_or_default(),
+        Err(e) => {
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to get submission information: {e}"),
+                )),
+            );
+        }
+    };
+
+    match storage
+        .create_submission(homework_id, user.id, submission_data, is_late)
+        .await
+    {
+        Ok(Some(submission)) => {
+            info!(
+                "Submission {} created for homework {} by {} (late: {})",
+                submission.id, homework_id, user.id, is_late
+            );
+            refresh_citations(
+                &storage,
+                previous_attachments.iter().chain(&submission.attachments),
+            )
+            .await;
+            Ok(HttpResponse::Created().json(ApiResponse::success(
+                submission,
+                "Homework submitted successfully",
+            )))
+        }
+        Ok(None) => Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::SubmissionAlreadyGraded,
+            "Submission has already been graded and cannot be resubmitted",
+        ))),
+        Err(e) => {
+            error!("Submission creation failed: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::SubmissionCreationFailed,
+                    format!("Submission creation failed: {e}"),
+                )),
+            )
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::SubmissionService;
+use crate::middlewares::RequireJWT;
+use crate::models::class_users::entities::ClassUser;
+use crate::models::homeworks::entities::Homework;
+use crate::models::users::entities::{User, UserRole};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::repository::Storage;
+
+pub async fn get_my_submission(
+    service: &SubmissionService,
+    request: &HttpRequest,
+    homework_id: i64,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    if let Err(resp) = get_homework_with_member(&storage, homework_id, &user).await {
+        return Ok(resp);
+    }
+
+    match storage
+        .get_submission_by_homework_and_creator(homework_id, user.id)
+        .await
+    {
+        Ok(Some(submission)) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            submission,
+            "Submission information retrieved successfully",
+        ))),
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::SubmissionNotFound,
+            "Submission not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to get submission information: {e}"),
+            )),
+        ),
+    }
+}
+
+/// 获取作业及当前用户在作业所属班级中的身份
+/// 作业不存在时返回 404，非班级成员返回 403；管理员不属于班级时身份为 None
+pub(crate) async fn get_homework_with_member(
+    storage: &Arc<dyn Storage>,
+    homework_id: i64,
+    user: &User,
+) -> Result<(Homework, Option<ClassUser>), HttpResponse> {
+    let homework = match storage.get_homework_by_id(homework_id).await {
+        Ok(Some(homework)) => homework,
+        Ok(None) => {
+            return Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+                ErrorCode::HomeworkNotFound,
+                "Homework not found",
+            )));
+        }
+        Err(e) => {
+            return Err(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to get homework information: {e}"),
+                )),
+            );
+        }
+    };
+
+    let class_user = match storage
+        .get_class_user_by_user_id_and_class_id(user.id, homework.class_id)
+        .await
+    {
+        Ok(class_user) => class_user,
+        Err(e) => {
+            return Err(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to get class user information: {e}"),
+                )),
+            );
+        }
+    };
+
+    if class_user.is_none() && user.role != UserRole::Admin {
+        return Err(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ClassPermissionDenied,
+            "You are not a member of this class",
+        )));
+    }
+
+    Ok((homework, class_user))
+}
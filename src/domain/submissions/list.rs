@@ -0,0 +1,61 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::SubmissionService;
+use super::get::get_homework_with_member;
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    class_users::entities::ClassUserRole,
+    submissions::requests::{SubmissionListParams, SubmissionListQuery},
+    users::entities::UserRole,
+};
+
+pub async fn list_submissions(
+    service: &SubmissionService,
+    request: &HttpRequest,
+    homework_id: i64,
+    query: SubmissionListParams,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let user = match RequireJWT::extract_user_claims(request) {
+        Some(user) => user,
+        None => {
+            return Ok(HttpResponse::Unauthorized().json(ApiResponse::error_empty(
+                ErrorCode::Unauthorized,
+                "Unauthorized: missing user claims",
+            )));
+        }
+    };
+
+    let class_user = match get_homework_with_member(&storage, homework_id, &user).await {
+        Ok((_, class_user)) => class_user,
+        Err(resp) => return Ok(resp),
+    };
+
+    // 只有班级教师和管理员可以查看全部提交
+    let is_teacher = class_user.is_some_and(|cu| cu.role == ClassUserRole::Teacher);
+    if !is_teacher && user.role != UserRole::Admin {
+        return Ok(HttpResponse::Forbidden().json(ApiResponse::error_empty(
+            ErrorCode::ClassPermissionDenied,
+            "Only teachers of this class can view submissions",
+        )));
+    }
+
+    let list_query = SubmissionListQuery {
+        page: Some(query.pagination.page),
+        size: Some(query.pagination.size),
+        homework_id,
+        status: query.status,
+    };
+
+    match storage.list_submissions_with_pagination(list_query).await {
+        Ok(resp) => Ok(HttpResponse::Ok().json(ApiResponse::success(resp, "获取提交列表成功"))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("获取提交列表失败: {e}"),
+            )),
+        ),
+    }
+}
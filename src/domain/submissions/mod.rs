@@ -0,0 +1,60 @@
+pub mod create;
+pub mod get;
+pub mod list;
+
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use std::sync::Arc;
+
+use crate::models::submissions::requests::{CreateSubmissionRequest, SubmissionListParams};
+use crate::repository::Storage;
+
+pub struct SubmissionService {
+    storage: Option<Arc<dyn Storage>>,
+}
+
+impl SubmissionService {
+    pub fn new_lazy() -> Self {
+        Self { storage: None }
+    }
+
+    pub(crate) fn get_storage(&self, request: &HttpRequest) -> Arc<dyn Storage> {
+        if let Some(storage) = &self.storage {
+            storage.clone()
+        } else {
+            request
+                .app_data::<actix_web::web::Data<Arc<dyn Storage>>>()
+                .expect("Storage not found in app data")
+                .get_ref()
+                .clone()
+        }
+    }
+
+    // 提交作业
+    pub async fn create_submission(
+        &self,
+        request: &HttpRequest,
+        homework_id: i64,
+        submission_data: CreateSubmissionRequest,
+    ) -> ActixResult<HttpResponse> {
+        create::create_submission(self, request, homework_id, submission_data).await
+    }
+
+    // 获取当前用户的提交
+    pub async fn get_my_submission(
+        &self,
+        request: &HttpRequest,
+        homework_id: i64,
+    ) -> ActixResult<HttpResponse> {
+        get::get_my_submission(self, request, homework_id).await
+    }
+
+    // 获取作业的提交列表
+    pub async fn list_submissions(
+        &self,
+        request: &HttpRequest,
+        homework_id: i64,
+        query: SubmissionListParams,
+    ) -> ActixResult<HttpResponse> {
+        list::list_submissions(self, request, homework_id, query).await
+    }
+}
@@ -0,0 +1,14 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, http::header};
+
+use super::SystemService;
+use crate::utils::jwt_keys::JwtKeys;
+
+// 密钥轮换时新公钥需要尽快被其他服务获取，只短时间缓存
+const JWKS_CACHE_CONTROL: &str = "public, max-age=300";
+
+// 返回标准 JWK Set 格式，不包装为 ApiResponse，便于其他服务直接使用
+pub async fn get_jwks(_service: &SystemService, _req: &HttpRequest) -> ActixResult<HttpResponse> {
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, JWKS_CACHE_CONTROL))
+        .json(JwtKeys::get().jwks()))
+}
@@ -1,3 +1,4 @@
+pub mod jwks;
 pub mod settings;
 
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
@@ -19,4 +20,9 @@ impl SystemService {
     pub async fn get_settings(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
         settings::get_settings(self, request).await
     }
+
+    // 获取用于验证令牌的公钥集合
+    pub async fn get_jwks(&self, request: &HttpRequest) -> ActixResult<HttpResponse> {
+        jwks::get_jwks(self, request).await
+    }
 }
@@ -4,7 +4,7 @@ use tracing::error;
 use super::UserService;
 use crate::models::{
     ApiResponse, ErrorCode,
-    users::{requests::CreateUserRequest, responses::UserResponse},
+    users::{entities::UserStatus, requests::CreateUserRequest, responses::UserResponse},
 };
 use crate::utils::password::hash_password;
 use crate::utils::validate::{validate_email, validate_username};
@@ -26,6 +26,14 @@ pub async fn create_user(
             .json(ApiResponse::error_empty(ErrorCode::UserEmailInvalid, msg)));
     }
 
+    // 待审核状态只能由自助注册设置，否则审核队列中会混入管理员创建的账号
+    if user_data.status == Some(UserStatus::Pending) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UserStatusInvalid,
+            "Pending status can only be set by self-registration",
+        )));
+    }
+
     user_data.password = match hash_password(&user_data.password) {
         Ok(hash) => hash,
         Err(e) => {
@@ -47,7 +55,7 @@ pub async fn create_user(
             let msg = format!("User creation failed: {e}");
             error!("{}", msg);
             // 判断是否唯一约束冲突
-            if msg.contains("UNIQUE constraint failed") {
+            if e.is_unique_violation() {
                 Ok(HttpResponse::Conflict().json(ApiResponse::error_empty(
                     ErrorCode::UserAlreadyExists,
                     "Username or email already exists",
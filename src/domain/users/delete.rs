@@ -12,19 +12,22 @@ pub async fn delete_user(
     request: &HttpRequest,
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
 
-    if let Some(current_user_id) = RequireJWT::extract_user_id(request) {
-        if user_id == current_user_id || user_id == 1 {
-            // 禁止删除超级管理员用户和当前用户
-            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
-                ErrorCode::CanNotDeleteCurrentUser,
-                "Cannot delete current user",
-            )));
-        }
+    if let Some(current_user_id) = RequireJWT::extract_user_id(request)
+        && (user_id == current_user_id || user_id == 1)
+    {
+        // 禁止删除超级管理员用户和当前用户
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::CanNotDeleteCurrentUser,
+            "Cannot delete current user",
+        )));
     }
 
     match storage.delete_user(user_id).await {
         Ok(true) => {
+            // 已删除用户的缓存信息必须立即失效，避免其令牌继续通过认证
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user_id).await;
             Ok(HttpResponse::Ok().json(ApiResponse::success_empty("User deleted successfully")))
         }
         Ok(false) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
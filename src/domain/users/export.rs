@@ -0,0 +1,218 @@
+use actix_web::{
+    HttpRequest, HttpResponse, Result as ActixResult,
+    http::header,
+    web::{self, Bytes},
+};
+use futures_util::{StreamExt, stream};
+use std::sync::Arc;
+use tracing::error;
+
+use super::UserService;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    users::{
+        entities::User,
+        requests::{UserExportFormat, UserExportParams, UserListQuery},
+    },
+};
+use crate::repository::Storage;
+use crate::utils::{csv, xlsx::XlsxWriter};
+
+// 每次从数据库读取的用户数，与列表接口的分页上限一致
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+const XLSX_CONTENT_TYPE: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+const COLUMNS: [&str; 9] = [
+    "id",
+    "username",
+    "email",
+    "email_verified",
+    "profile_name",
+    "role",
+    "status",
+    "last_login",
+    "created_at",
+];
+
+fn user_row(user: &User) -> [String; 9] {
+    [
+        user.id.to_string(),
+        user.username.clone(),
+        user.email.clone(),
+        user.email_verified.to_string(),
+        user.profile.profile_name.clone(),
+        user.role.to_string(),
+        user.status.to_string(),
+        user.last_login
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default(),
+        user.created_at.to_rfc3339(),
+    ]
+}
+
+// CSV 逐行输出；XLSX 的行暂存在临时文件中，结束时输出整个文件
+enum Encoder {
+    Csv,
+    Xlsx(Box<XlsxWriter>),
+}
+
+impl Encoder {
+    fn write_row<S: AsRef<str>>(&mut self, cells: &[S]) -> Result<Vec<u8>, String> {
+        match self {
+            Encoder::Csv => csv::write_row(cells).map_err(|e| e.to_string()),
+            Encoder::Xlsx(writer) => writer
+                .write_row(cells)
+                .map(|_| Vec::new())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    async fn finish(self) -> Result<Vec<u8>, String> {
+        match self {
+            Encoder::Csv => Ok(Vec::new()),
+            // 压缩整个文件耗时较长，放到阻塞线程池中执行
+            Encoder::Xlsx(writer) => web::block(move || writer.finish())
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+// 导出流的状态，encoder 为 None 表示已输出结尾
+struct ExportState {
+    storage: Arc<dyn Storage>,
+    params: UserExportParams,
+    encoder: Option<Encoder>,
+    next_page: i64,
+    pages: i64,
+}
+
+impl ExportState {
+    fn query(&self, page: i64) -> UserListQuery {
+        UserListQuery {
+            page: Some(page),
+            size: Some(EXPORT_PAGE_SIZE),
+            role: self.params.role.clone(),
+            status: self.params.status.clone(),
+            search: self.params.search.clone(),
+        }
+    }
+
+    // 输出下一页的数据，最后一页之后输出文件结尾
+    async fn next_chunk(mut self) -> Option<(Result<Bytes, actix_web::Error>, Self)> {
+        self.encoder.as_ref()?;
+        if self.next_page > self.pages {
+            let tail = match self.encoder.take()?.finish().await {
+                Ok(tail) => tail,
+                Err(e) => return Some((Err(self.abort(&e)), self)),
+            };
+            return Some((Ok(Bytes::from(tail)), self));
+        }
+
+        let response = match self
+            .storage
+            .list_users_with_pagination(self.query(self.next_page))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                let error = self.abort(&e.to_string());
+                return Some((Err(error), self));
+            }
+        };
+
+        let encoder = self.encoder.as_mut()?;
+        let mut chunk = Vec::new();
+        for user in &response.items {
+            match encoder.write_row(&user_row(user)) {
+                Ok(bytes) => chunk.extend(bytes),
+                Err(e) => return Some((Err(self.abort(&e)), self)),
+            }
+        }
+        self.next_page += 1;
+        Some((Ok(Bytes::from(chunk)), self))
+    }
+
+    // 响应头已发送，只能中断连接
+    fn abort(&mut self, message: &str) -> actix_web::Error {
+        error!("User export failed on page {}: {}", self.next_page, message);
+        self.encoder = None;
+        actix_web::error::ErrorInternalServerError(message.to_string())
+    }
+}
+
+fn export_failed(message: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        format!("Failed to export users: {message}"),
+    ))
+}
+
+pub async fn export_users(
+    service: &UserService,
+    params: UserExportParams,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let mut state = ExportState {
+        storage,
+        params,
+        encoder: None,
+        next_page: 1,
+        pages: 0,
+    };
+
+    // 先查询第一页，确保查询失败时仍能返回正常的错误响应
+    let first = match state
+        .storage
+        .list_users_with_pagination(state.query(1))
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => return Ok(export_failed(e)),
+    };
+    state.pages = first.pagination.pages;
+
+    let format = state.params.format;
+    let (mut encoder, mut head) = match format {
+        // 带 BOM 以便电子表格软件按 UTF-8 识别
+        UserExportFormat::Csv => (Encoder::Csv, "\u{feff}".as_bytes().to_vec()),
+        UserExportFormat::Xlsx => match XlsxWriter::new("users") {
+            Ok(writer) => (Encoder::Xlsx(Box::new(writer)), Vec::new()),
+            Err(e) => return Ok(export_failed(e)),
+        },
+    };
+    let rows = std::iter::once(COLUMNS.map(String::from)).chain(first.items.iter().map(user_row));
+    for row in rows {
+        match encoder.write_row(&row) {
+            Ok(bytes) => head.extend(bytes),
+            Err(e) => return Ok(export_failed(e)),
+        }
+    }
+    state.encoder = Some(encoder);
+    state.next_page = 2;
+
+    let (content_type, extension) = match format {
+        UserExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        UserExportFormat::Xlsx => (XLSX_CONTENT_TYPE, "xlsx"),
+    };
+    let filename = format!(
+        "users-{}.{}",
+        chrono::Utc::now().format("%Y%m%d"),
+        extension
+    );
+
+    let body = stream::once(async move { Ok::<_, actix_web::Error>(Bytes::from(head)) })
+        .chain(stream::unfold(state, ExportState::next_chunk));
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        ))
+        .streaming(body))
+}
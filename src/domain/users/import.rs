@@ -0,0 +1,353 @@
+use actix_multipart::Multipart;
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use futures_util::stream::StreamExt;
+use futures_util::{TryStreamExt, future};
+use std::collections::{HashMap, HashSet};
+use tracing::{error, info};
+
+use super::UserService;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    users::{
+        entities::{UserProfile, UserRole, UserStatus},
+        requests::{CreateUserRequest, UserImportQuery},
+        responses::{UserImportResponse, UserImportRowResult},
+    },
+};
+use crate::system::app_config::AppConfig;
+use crate::utils::password::hash_password;
+use crate::utils::random_code::generate_random_code;
+use crate::utils::validate::{validate_email, validate_password, validate_username};
+use crate::utils::{csv, xlsx};
+
+// 未提供密码时生成的初始密码长度
+const INITIAL_PASSWORD_LENGTH: usize = 12;
+
+// 表头中可识别的列
+const COLUMN_USERNAME: &str = "username";
+const COLUMN_EMAIL: &str = "email";
+const COLUMN_PROFILE_NAME: &str = "profile_name";
+const COLUMN_ROLE: &str = "role";
+const COLUMN_PASSWORD: &str = "password";
+
+fn import_invalid(message: impl Into<String>) -> HttpResponse {
+    HttpResponse::BadRequest().json(ApiResponse::error_empty(
+        ErrorCode::UserImportInvalid,
+        message.into(),
+    ))
+}
+
+// 校验通过、等待创建的行
+struct PendingUser {
+    index: usize,
+    username: String,
+    email: String,
+    profile_name: String,
+    role: UserRole,
+    password: Option<String>,
+}
+
+pub async fn import_users(
+    service: &UserService,
+    query: UserImportQuery,
+    mut payload: Multipart,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let config = &AppConfig::get().user_import;
+
+    // 1. 读取上传的文件
+    let mut data: Option<Vec<u8>> = None;
+    while let Ok(Some(mut field)) = payload.try_next().await {
+        let name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_name())
+            .unwrap_or_default()
+            .to_string();
+        if name != "file" {
+            continue;
+        }
+        if data.is_some() {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                ErrorCode::MuitifileUploadNotAllowed,
+                "Only one file can be uploaded at a time",
+            )));
+        }
+
+        let mut buf = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk?;
+            if buf.len() + chunk.len() > config.max_file_size {
+                return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+                    ErrorCode::FileSizeExceeded,
+                    "File size exceeds the limit",
+                )));
+            }
+            buf.extend_from_slice(&chunk);
+        }
+        data = Some(buf);
+    }
+
+    let Some(data) = data else {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::FileNotFound,
+            "No file found in upload payload",
+        )));
+    };
+
+    // 2. 按文件头区分 XLSX 与 CSV，解析放到阻塞线程池中执行
+    let rows = match web::block(move || {
+        if xlsx::is_xlsx(&data) {
+            xlsx::read_rows(&data)
+        } else {
+            let text = String::from_utf8(data)
+                .map_err(|_| "CSV file must be UTF-8 encoded".to_string())?;
+            csv::parse(&text)
+        }
+    })
+    .await
+    {
+        Ok(Ok(rows)) => rows,
+        Ok(Err(e)) => return Ok(import_invalid(format!("Failed to parse file: {e}"))),
+        Err(e) => {
+            error!("Failed to parse import file: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Failed to parse file",
+                )),
+            );
+        }
+    };
+
+    let Some((header, records)) = rows.split_first() else {
+        return Ok(import_invalid("File is empty"));
+    };
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.trim().to_ascii_lowercase(), index))
+        .collect();
+    for required in [COLUMN_USERNAME, COLUMN_EMAIL] {
+        if !columns.contains_key(required) {
+            return Ok(import_invalid(format!(
+                "Missing required column '{required}'"
+            )));
+        }
+    }
+    if records.is_empty() {
+        return Ok(import_invalid("File has no data rows"));
+    }
+    if records.len() > config.max_rows {
+        return Ok(import_invalid(format!(
+            "File has {} rows, at most {} rows can be imported at once",
+            records.len(),
+            config.max_rows
+        )));
+    }
+
+    // 3. 逐行校验
+    let mut results = Vec::with_capacity(records.len());
+    let mut pending = Vec::with_capacity(records.len());
+    let mut seen_usernames = HashSet::new();
+    let mut seen_emails = HashSet::new();
+
+    for (index, record) in records.iter().enumerate() {
+        let cell = |column: &str| {
+            columns
+                .get(column)
+                .and_then(|&i| record.get(i))
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default()
+        };
+        let username = cell(COLUMN_USERNAME);
+        let email = cell(COLUMN_EMAIL);
+        let profile_name = cell(COLUMN_PROFILE_NAME);
+        let role = cell(COLUMN_ROLE);
+        let password = cell(COLUMN_PASSWORD);
+
+        let mut errors = Vec::new();
+        if let Err(msg) = validate_username(&username) {
+            errors.push(msg.to_string());
+        } else if !seen_usernames.insert(username.to_ascii_lowercase()) {
+            errors.push("Username is duplicated in the file".to_string());
+        }
+        if let Err(msg) = validate_email(&email) {
+            errors.push(msg.to_string());
+        } else if !seen_emails.insert(email.to_ascii_lowercase()) {
+            errors.push("Email is duplicated in the file".to_string());
+        }
+        let role = if role.is_empty() {
+            Some(UserRole::User)
+        } else {
+            match role.to_ascii_lowercase().parse::<UserRole>() {
+                Ok(role) => Some(role),
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            }
+        };
+        if !password.is_empty()
+            && let Err(msg) = validate_password(&password)
+        {
+            errors.push(msg.to_string());
+        }
+
+        // 格式正确时再检查是否与已有用户冲突
+        if errors.is_empty() {
+            match storage.get_user_by_username(&username).await {
+                Ok(Some(_)) => errors.push("Username already exists".to_string()),
+                Ok(None) => {}
+                Err(e) => return Ok(lookup_failed(e)),
+            }
+            match storage.get_user_by_email(&email).await {
+                Ok(Some(_)) => errors.push("Email already exists".to_string()),
+                Ok(None) => {}
+                Err(e) => return Ok(lookup_failed(e)),
+            }
+        }
+
+        if errors.is_empty()
+            && let Some(role) = role.clone()
+        {
+            pending.push(PendingUser {
+                index,
+                username: username.clone(),
+                email: email.clone(),
+                profile_name: if profile_name.is_empty() {
+                    username.clone()
+                } else {
+                    profile_name
+                },
+                role,
+                password: (!password.is_empty()).then_some(password),
+            });
+        }
+
+        results.push(UserImportRowResult {
+            // 表头为第 1 行
+            row: index + 2,
+            username,
+            email,
+            role,
+            errors,
+            user_id: None,
+            initial_password: None,
+        });
+    }
+
+    let invalid = results.iter().filter(|row| !row.errors.is_empty()).count();
+    let mut report = UserImportResponse {
+        dry_run: query.dry_run,
+        total: results.len(),
+        valid: results.len() - invalid,
+        invalid,
+        created: 0,
+        rows: results,
+    };
+
+    if query.dry_run {
+        return Ok(HttpResponse::Ok().json(ApiResponse::success(
+            report,
+            "Import file validated, no users were created",
+        )));
+    }
+    if invalid > 0 {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error(
+            ErrorCode::UserImportInvalid,
+            report,
+            "Import file contains invalid rows, no users were created",
+        )));
+    }
+
+    // 4. 生成缺失的初始密码并计算哈希，Argon2 较慢，在阻塞线程池中并行执行
+    let passwords: Vec<(String, bool)> = pending
+        .iter()
+        .map(|user| match &user.password {
+            Some(password) => (password.clone(), false),
+            None => (generate_random_code(INITIAL_PASSWORD_LENGTH), true),
+        })
+        .collect();
+    let hashes = match future::try_join_all(passwords.iter().map(|(password, _)| {
+        let password = password.clone();
+        web::block(move || hash_password(&password))
+    }))
+    .await
+    {
+        Ok(hashes) => match hashes.into_iter().collect::<Result<Vec<_>, _>>() {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                return Ok(
+                    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                        ErrorCode::InternalServerError,
+                        format!("Password hashing failed: {e}"),
+                    )),
+                );
+            }
+        },
+        Err(e) => {
+            error!("Password hashing task failed: {}", e);
+            return Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    "Password hashing failed",
+                )),
+            );
+        }
+    };
+
+    let requests = pending
+        .iter()
+        .zip(hashes)
+        .map(|(user, password)| CreateUserRequest {
+            username: user.username.clone(),
+            email: user.email.clone(),
+            password,
+            role: user.role.clone(),
+            status: Some(UserStatus::Active),
+            profile: UserProfile {
+                profile_name: user.profile_name.clone(),
+                avatar_url: None,
+            },
+        })
+        .collect();
+
+    // 5. 在同一事务中创建，任一行失败时全部回滚
+    let created = match storage.create_users(requests).await {
+        Ok(created) => created,
+        Err(e) => {
+            let msg = format!("User import failed: {e}");
+            error!("{}", msg);
+            // 校验后到写入前被其他请求占用了用户名或邮箱
+            return Ok(if e.is_unique_violation() {
+                HttpResponse::Conflict().json(ApiResponse::error_empty(
+                    ErrorCode::UserAlreadyExists,
+                    "Username or email already exists, no users were created",
+                ))
+            } else {
+                HttpResponse::InternalServerError()
+                    .json(ApiResponse::error_empty(ErrorCode::UserCreationFailed, msg))
+            });
+        }
+    };
+
+    for ((user, (password, generated)), created) in pending.iter().zip(passwords).zip(&created) {
+        let row = &mut report.rows[user.index];
+        row.user_id = Some(created.id);
+        if generated {
+            row.initial_password = Some(password);
+        }
+    }
+    report.created = created.len();
+
+    info!("Imported {} users", report.created);
+    Ok(HttpResponse::Created().json(ApiResponse::success(report, "Users imported successfully")))
+}
+
+fn lookup_failed(e: crate::errors::HWSystemError) -> HttpResponse {
+    HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+        ErrorCode::InternalServerError,
+        format!("Failed to check existing users: {e}"),
+    ))
+}
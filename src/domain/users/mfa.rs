@@ -0,0 +1,32 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::UserService;
+use crate::models::{ApiResponse, ErrorCode};
+
+// 重置用户的两步验证（用户丢失验证器与恢复码时），用户下次登录时可重新绑定
+pub async fn reset_user_mfa(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    match storage.delete_user_mfa(user_id).await {
+        Ok(true) => {
+            tracing::info!("Two-factor authentication reset for user {}", user_id);
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+                "Two-factor authentication reset successfully",
+            )))
+        }
+        Ok(false) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::MfaNotEnabled,
+            "Two-factor authentication is not enabled for this user",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to reset two-factor authentication: {e}"),
+            )),
+        ),
+    }
+}
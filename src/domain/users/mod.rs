@@ -1,13 +1,24 @@
 pub mod create;
 pub mod delete;
+pub mod export;
 pub mod get;
+pub mod import;
 pub mod list;
+pub mod mfa;
+pub mod registrations;
+pub mod sessions;
+pub mod unlock;
 pub mod update;
 
+use actix_multipart::Multipart;
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 use std::sync::Arc;
 
-use crate::models::users::requests::{CreateUserRequest, UpdateUserRequest, UserListParams};
+use crate::cache::ObjectCache;
+use crate::models::common::PaginationQuery;
+use crate::models::users::requests::{
+    CreateUserRequest, UpdateUserRequest, UserExportParams, UserImportQuery, UserListParams,
+};
 use crate::repository::Storage;
 
 pub struct UserService {
@@ -31,6 +42,14 @@ impl UserService {
         }
     }
 
+    pub(crate) fn get_cache(&self, request: &HttpRequest) -> Arc<dyn ObjectCache> {
+        request
+            .app_data::<actix_web::web::Data<Arc<dyn ObjectCache>>>()
+            .expect("Cache not found in app data")
+            .get_ref()
+            .clone()
+    }
+
     // 获取用户列表
     pub async fn list_users(
         &self,
@@ -49,6 +68,25 @@ impl UserService {
         create::create_user(self, user_data, request).await
     }
 
+    // 从 CSV 或 XLSX 文件批量导入用户
+    pub async fn import_users(
+        &self,
+        query: UserImportQuery,
+        payload: Multipart,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        import::import_users(self, query, payload, request).await
+    }
+
+    // 以 CSV 或 XLSX 格式导出用户
+    pub async fn export_users(
+        &self,
+        params: UserExportParams,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        export::export_users(self, params, request).await
+    }
+
     // 根据ID获取用户
     pub async fn get_user(&self, user_id: i64, request: &HttpRequest) -> ActixResult<HttpResponse> {
         get::get_user(self, user_id, request).await
@@ -72,4 +110,77 @@ impl UserService {
     ) -> ActixResult<HttpResponse> {
         delete::delete_user(self, user_id, request).await
     }
+
+    // 列出用户的登录会话
+    pub async fn list_user_sessions(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        sessions::list_user_sessions(self, user_id, request).await
+    }
+
+    // 终止用户的指定会话
+    pub async fn revoke_user_session(
+        &self,
+        user_id: i64,
+        session_id: &str,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        sessions::revoke_user_session(self, user_id, session_id, request).await
+    }
+
+    // 终止用户的全部会话
+    pub async fn revoke_all_user_sessions(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        sessions::revoke_all_user_sessions(self, user_id, request).await
+    }
+
+    // 解除用户的登录锁定
+    pub async fn unlock_user(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        unlock::unlock_user(self, user_id, request).await
+    }
+
+    // 重置用户的两步验证
+    pub async fn reset_user_mfa(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        mfa::reset_user_mfa(self, user_id, request).await
+    }
+
+    // 获取待审核的注册申请
+    pub async fn list_pending_registrations(
+        &self,
+        query: PaginationQuery,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        registrations::list_pending_registrations(self, query, request).await
+    }
+
+    // 通过注册申请
+    pub async fn approve_registration(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        registrations::approve_registration(self, user_id, request).await
+    }
+
+    // 拒绝注册申请
+    pub async fn reject_registration(
+        &self,
+        user_id: i64,
+        request: &HttpRequest,
+    ) -> ActixResult<HttpResponse> {
+        registrations::reject_registration(self, user_id, request).await
+    }
 }
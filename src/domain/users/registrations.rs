@@ -0,0 +1,144 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::UserService;
+use crate::middlewares::RequireJWT;
+use crate::models::{
+    ApiResponse, ErrorCode,
+    common::PaginationQuery,
+    users::{
+        entities::{User, UserStatus},
+        requests::{UpdateUserRequest, UserListQuery},
+        responses::UserResponse,
+    },
+};
+use crate::repository::Storage;
+
+// 待审核的注册申请即自助注册后处于待审核状态的用户
+pub async fn list_pending_registrations(
+    service: &UserService,
+    query: PaginationQuery,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    let list_query = UserListQuery {
+        page: Some(query.page),
+        size: Some(query.size),
+        role: None,
+        status: Some(UserStatus::Pending),
+        search: None,
+    };
+
+    match storage.list_users_with_pagination(list_query).await {
+        Ok(response) => Ok(HttpResponse::Ok().json(ApiResponse::success(
+            response,
+            "Pending registrations retrieved successfully",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to retrieve pending registrations: {e}"),
+            )),
+        ),
+    }
+}
+
+pub async fn approve_registration(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    if let Err(response) = get_pending_registration(storage.as_ref(), user_id).await {
+        return Ok(response);
+    }
+
+    let update = UpdateUserRequest {
+        email: None,
+        password: None,
+        role: None,
+        status: Some(UserStatus::Active),
+        profile: None,
+    };
+
+    match storage.update_user(user_id, update).await {
+        Ok(Some(user)) => {
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+            tracing::info!("Registration approved for user {}", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                UserResponse { user },
+                "Registration approved successfully",
+            )))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::UserUpdateFailed,
+                format!("Failed to approve registration: {e}"),
+            )),
+        ),
+    }
+}
+
+// 拒绝注册申请会直接删除该账号，以便用户名和邮箱可以重新注册
+pub async fn reject_registration(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    let user = match get_pending_registration(storage.as_ref(), user_id).await {
+        Ok(user) => user,
+        Err(response) => return Ok(response),
+    };
+
+    match storage.delete_user(user_id).await {
+        Ok(true) => {
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user_id).await;
+            tracing::info!("Registration rejected for user {}", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+                "Registration rejected successfully",
+            )))
+        }
+        Ok(false) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::UserDeleteFailed,
+                format!("Failed to reject registration: {e}"),
+            )),
+        ),
+    }
+}
+
+async fn get_pending_registration(
+    storage: &dyn Storage,
+    user_id: i64,
+) -> Result<User, HttpResponse> {
+    match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) if user.status == UserStatus::Pending => Ok(user),
+        Ok(Some(_)) => Err(HttpResponse::Conflict().json(ApiResponse::error_empty(
+            ErrorCode::RegistrationNotPending,
+            "User is not a pending registration",
+        ))),
+        Ok(None) => Err(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ))),
+        Err(e) => Err(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to get user information: {e}"),
+            )),
+        ),
+    }
+}
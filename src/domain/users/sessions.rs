@@ -0,0 +1,58 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+use tracing::{error, info};
+
+use super::UserService;
+use crate::domain::auth::sessions::{list_sessions_response, revoke_session_response};
+use crate::models::{ApiResponse, ErrorCode};
+use crate::utils::jwt::JwtUtils;
+
+pub async fn list_user_sessions(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+
+    Ok(list_sessions_response(&storage, user_id, None).await)
+}
+
+pub async fn revoke_user_session(
+    service: &UserService,
+    user_id: i64,
+    session_id: &str,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    Ok(revoke_session_response(&storage, &cache, user_id, session_id).await)
+}
+
+pub async fn revoke_all_user_sessions(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    match storage.revoke_user_sessions(user_id).await {
+        Ok(revoked) => {
+            // 此前签发的 access token 全部失效
+            JwtUtils::revoke_user_access_tokens(cache.as_ref(), user_id).await;
+            info!("{} sessions of user {} terminated", revoked, user_id);
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty(
+                "All sessions terminated successfully",
+            )))
+        }
+        Err(e) => {
+            error!("Failed to revoke user sessions: {}", e);
+            Ok(
+                HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                    ErrorCode::InternalServerError,
+                    format!("Failed to terminate sessions: {e}"),
+                )),
+            )
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
+
+use super::UserService;
+use crate::domain::auth::lockout::{self, LoginSubject};
+use crate::models::{ApiResponse, ErrorCode};
+
+pub async fn unlock_user(
+    service: &UserService,
+    user_id: i64,
+    request: &HttpRequest,
+) -> ActixResult<HttpResponse> {
+    let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    match storage.get_user_by_id(user_id).await {
+        Ok(Some(user)) => {
+            lockout::clear_failures(cache.as_ref(), &LoginSubject::User(user.id)).await;
+            tracing::info!("Login lockout cleared for user {}", user.username);
+            Ok(HttpResponse::Ok().json(ApiResponse::success_empty("User unlocked successfully")))
+        }
+        Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
+            ErrorCode::UserNotFound,
+            "User not found",
+        ))),
+        Err(e) => Ok(
+            HttpResponse::InternalServerError().json(ApiResponse::error_empty(
+                ErrorCode::InternalServerError,
+                format!("Failed to unlock user: {e}"),
+            )),
+        ),
+    }
+}
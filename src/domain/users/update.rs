@@ -1,9 +1,10 @@
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult};
 
 use super::UserService;
+use crate::middlewares::RequireJWT;
 use crate::models::{
     ApiResponse, ErrorCode,
-    users::{requests::UpdateUserRequest, responses::UserResponse},
+    users::{entities::UserStatus, requests::UpdateUserRequest, responses::UserResponse},
 };
 
 pub async fn update_user(
@@ -13,6 +14,15 @@ pub async fn update_user(
     request: &HttpRequest,
 ) -> ActixResult<HttpResponse> {
     let storage = service.get_storage(request);
+    let cache = service.get_cache(request);
+
+    // 待审核状态只能由自助注册设置
+    if update_data.status == Some(UserStatus::Pending) {
+        return Ok(HttpResponse::BadRequest().json(ApiResponse::error_empty(
+            ErrorCode::UserStatusInvalid,
+            "Pending status can only be set by self-registration",
+        )));
+    }
 
     if let Some(password) = update_data.password {
         match crate::utils::password::hash_password(&password) {
@@ -29,10 +39,14 @@ pub async fn update_user(
     }
 
     match storage.update_user(user_id, update_data).await {
-        Ok(Some(user)) => Ok(HttpResponse::Ok().json(ApiResponse::success(
-            UserResponse { user },
-            "User information updated successfully",
-        ))),
+        Ok(Some(user)) => {
+            // 角色或状态可能已变更，立即丢弃缓存中的旧用户信息
+            RequireJWT::invalidate_user_cache(cache.as_ref(), user.id).await;
+            Ok(HttpResponse::Ok().json(ApiResponse::success(
+                UserResponse { user },
+                "User information updated successfully",
+            )))
+        }
         Ok(None) => Ok(HttpResponse::NotFound().json(ApiResponse::error_empty(
             ErrorCode::UserNotFound,
             "User not found",
@@ -6,11 +6,17 @@ pub enum HWSystemError {
     DatabaseConfig(String),
     DatabaseConnection(String),
     DatabaseOperation(String),
+    UniqueViolation(String),
     FileOperation(String),
     Validation(String),
     Serialization(String),
     StoragePluginNotFound(String),
     DateParse(String),
+    Mail(String),
+    JwtKey(String),
+    Http(String),
+    Ldap(String),
+    BlobStore(String),
 }
 
 impl fmt::Display for HWSystemError {
@@ -20,11 +26,17 @@ impl fmt::Display for HWSystemError {
             HWSystemError::DatabaseConfig(msg) => write!(f, "数据库配置错误: {msg}"),
             HWSystemError::DatabaseConnection(msg) => write!(f, "数据库连接错误: {msg}"),
             HWSystemError::DatabaseOperation(msg) => write!(f, "数据库操作错误: {msg}"),
+            HWSystemError::UniqueViolation(msg) => write!(f, "唯一约束冲突: {msg}"),
             HWSystemError::FileOperation(msg) => write!(f, "文件操作错误: {msg}"),
             HWSystemError::Validation(msg) => write!(f, "验证错误: {msg}"),
             HWSystemError::Serialization(msg) => write!(f, "序列化错误: {msg}"),
             HWSystemError::StoragePluginNotFound(msg) => write!(f, "存储插件未找到: {msg}"),
             HWSystemError::DateParse(msg) => write!(f, "日期解析错误: {msg}"),
+            HWSystemError::Mail(msg) => write!(f, "邮件发送错误: {msg}"),
+            HWSystemError::JwtKey(msg) => write!(f, "JWT 密钥错误: {msg}"),
+            HWSystemError::Http(msg) => write!(f, "HTTP 请求错误: {msg}"),
+            HWSystemError::Ldap(msg) => write!(f, "LDAP 错误: {msg}"),
+            HWSystemError::BlobStore(msg) => write!(f, "文件存储错误: {msg}"),
         }
     }
 }
@@ -49,6 +61,22 @@ impl HWSystemError {
         HWSystemError::DatabaseOperation(msg.into())
     }
 
+    /// 数据库错误，唯一约束冲突单独归类，`context` 说明执行的操作
+    pub fn database_write(err: sqlx::Error, context: &str) -> Self {
+        let msg = format!("{context}: {err}");
+        match &err {
+            sqlx::Error::Database(e) if e.is_unique_violation() => {
+                HWSystemError::UniqueViolation(msg)
+            }
+            _ => HWSystemError::DatabaseOperation(msg),
+        }
+    }
+
+    /// 是否为唯一约束冲突
+    pub fn is_unique_violation(&self) -> bool {
+        matches!(self, HWSystemError::UniqueViolation(_))
+    }
+
     pub fn file_operation<T: Into<String>>(msg: T) -> Self {
         HWSystemError::FileOperation(msg.into())
     }
@@ -68,12 +96,33 @@ impl HWSystemError {
     pub fn date_parse<T: Into<String>>(msg: T) -> Self {
         HWSystemError::DateParse(msg.into())
     }
+
+    pub fn mail<T: Into<String>>(msg: T) -> Self {
+        HWSystemError::Mail(msg.into())
+    }
+    pub fn jwt_key<T: Into<String>>(msg: T) -> Self {
+        HWSystemError::JwtKey(msg.into())
+    }
+    pub fn http<T: Into<String>>(msg: T) -> Self {
+        HWSystemError::Http(msg.into())
+    }
+    pub fn ldap<T: Into<String>>(msg: T) -> Self {
+        HWSystemError::Ldap(msg.into())
+    }
+    pub fn blob_store<T: Into<String>>(msg: T) -> Self {
+        HWSystemError::BlobStore(msg.into())
+    }
 }
 
 // 为常见的错误类型实现 From trait
 impl From<sqlx::Error> for HWSystemError {
     fn from(err: sqlx::Error) -> Self {
-        HWSystemError::DatabaseOperation(err.to_string())
+        match &err {
+            sqlx::Error::Database(e) if e.is_unique_violation() => {
+                HWSystemError::UniqueViolation(err.to_string())
+            }
+            _ => HWSystemError::DatabaseOperation(err.to_string()),
+        }
     }
 }
 
@@ -0,0 +1,22 @@
+#[macro_export]
+macro_rules! declare_mailer_plugin {
+    ($name:expr, $ty:ty) => {
+        #[ctor::ctor]
+        fn __register_mailer_plugin() {
+            use std::sync::Arc;
+            use $crate::mailer::register::register_mailer_plugin;
+
+            register_mailer_plugin(
+                $name,
+                Arc::new(|| {
+                    Box::pin(async {
+                        match <$ty>::new() {
+                            Ok(mailer) => Ok(Box::new(mailer) as Box<dyn $crate::mailer::Mailer>),
+                            Err(e) => Err($crate::errors::HWSystemError::mail(e)),
+                        }
+                    })
+                }),
+            );
+        }
+    };
+}
@@ -0,0 +1,22 @@
+pub mod macros;
+pub mod register;
+pub mod transports;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+
+/// 待发送的邮件
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// 邮件发送 trait，具体实现通过 `declare_mailer_plugin!` 注册
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    /// 发送一封纯文本邮件
+    async fn send(&self, message: MailMessage) -> Result<()>;
+}
@@ -0,0 +1,37 @@
+use crate::errors::Result;
+use crate::mailer::Mailer;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+};
+
+pub type BoxedMailerFuture = Pin<Box<dyn Future<Output = Result<Box<dyn Mailer>>> + Send>>;
+pub type MailerConstructor = Arc<dyn Fn() -> BoxedMailerFuture + Send + Sync>;
+
+static MAILER_REGISTRY: Lazy<RwLock<HashMap<String, MailerConstructor>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub fn register_mailer_plugin<S: Into<String>>(name: S, constructor: MailerConstructor) {
+    let name = name.into();
+    let mut registry = MAILER_REGISTRY.write().unwrap();
+    registry.insert(name, constructor);
+}
+
+pub fn get_mailer_plugin(name: &str) -> Option<MailerConstructor> {
+    MAILER_REGISTRY.read().unwrap().get(name).cloned()
+}
+
+pub fn debug_mailer_registry() {
+    let registry = MAILER_REGISTRY.read().unwrap();
+    if registry.is_empty() {
+        tracing::debug!("No mailer plugins registered.");
+    } else {
+        tracing::debug!("Registered mailer plugins:");
+        for key in registry.keys() {
+            tracing::debug!(" - {}", key);
+        }
+    }
+}
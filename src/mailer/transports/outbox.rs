@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tracing::{debug, info};
+
+use crate::declare_mailer_plugin;
+use crate::errors::{HWSystemError, Result};
+use crate::mailer::{MailMessage, Mailer};
+use crate::system::app_config::AppConfig;
+
+declare_mailer_plugin!("outbox", OutboxMailer);
+
+/// 将邮件写入本地目录而不实际发送，便于开发和测试时查看邮件内容
+pub struct OutboxMailer {
+    dir: PathBuf,
+    from: String,
+}
+
+impl OutboxMailer {
+    pub fn new() -> std::result::Result<Self, String> {
+        let config = AppConfig::get();
+        let dir = PathBuf::from(&config.mail.outbox.dir);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create outbox directory: {e}"))?;
+
+        debug!("OutboxMailer initialized with directory: {}", dir.display());
+        Ok(Self {
+            dir,
+            from: config.mail.from.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for OutboxMailer {
+    async fn send(&self, message: MailMessage) -> Result<()> {
+        let now = chrono::Utc::now();
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%d%H%M%S"),
+            uuid::Uuid::new_v4()
+        ));
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\n\r\n{}\r\n",
+            self.from,
+            message.to,
+            message.subject,
+            now.to_rfc2822(),
+            message.body
+        );
+
+        let file_path = path.clone();
+        actix_web::web::block(move || std::fs::write(file_path, content))
+            .await
+            .map_err(|e| HWSystemError::mail(format!("Failed to write outbox mail: {e}")))??;
+
+        info!(
+            "Mail to {} ({}) written to outbox: {}",
+            message.to,
+            message.subject,
+            path.display()
+        );
+        Ok(())
+    }
+}
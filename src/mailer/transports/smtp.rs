@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, header::ContentType},
+    transport::smtp::authentication::Credentials,
+};
+use tracing::{debug, info};
+
+use crate::declare_mailer_plugin;
+use crate::errors::{HWSystemError, Result};
+use crate::mailer::{MailMessage, Mailer};
+use crate::system::app_config::AppConfig;
+
+declare_mailer_plugin!("smtp", SmtpMailer);
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new() -> std::result::Result<Self, String> {
+        let config = AppConfig::get();
+        let smtp = &config.mail.smtp;
+
+        let builder = match smtp.tls.as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host),
+            "none" => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                &smtp.host,
+            )),
+            other => return Err(format!("Unsupported SMTP TLS mode: {other}")),
+        }
+        .map_err(|e| format!("Failed to create SMTP transport: {e}"))?
+        .port(smtp.port);
+
+        let builder = if smtp.username.is_empty() {
+            builder
+        } else {
+            builder.credentials(Credentials::new(
+                smtp.username.clone(),
+                smtp.password.clone(),
+            ))
+        };
+
+        let from = config
+            .mail
+            .from
+            .parse::<Mailbox>()
+            .map_err(|e| format!("Invalid mail sender address: {e}"))?;
+
+        debug!(
+            "SmtpMailer initialized for {}:{} ({})",
+            smtp.host, smtp.port, smtp.tls
+        );
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: MailMessage) -> Result<()> {
+        let to = message
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| HWSystemError::mail(format!("Invalid recipient address: {e}")))?;
+
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|e| HWSystemError::mail(format!("Failed to build mail: {e}")))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|e| HWSystemError::mail(format!("Failed to send mail: {e}")))?;
+
+        info!("Mail to {} ({}) sent via SMTP", message.to, message.subject);
+        Ok(())
+    }
+}
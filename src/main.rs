@@ -5,14 +5,18 @@ use dotenv::dotenv;
 use human_panic::setup_panic;
 use tracing::{debug, warn};
 
+mod blob_store;
 mod cache;
 mod domain;
 mod errors;
+mod mailer;
 mod middlewares;
 mod models;
 mod repository;
 mod routes;
 mod system;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 
 use crate::models::AppStartTime;
@@ -72,6 +76,8 @@ async fn main() -> std::io::Result<()> {
 
     let storage = startup.storage.clone();
     let cache = startup.cache.clone();
+    let mailer = startup.mailer.clone();
+    let blob_store = startup.blob_store.clone();
 
     // 输出预处理时间
     debug!(
@@ -88,6 +94,7 @@ async fn main() -> std::io::Result<()> {
     // Start the HTTP server
     let server = HttpServer::new(move || {
         App::new()
+            .wrap(middlewares::RateLimit::new("global")) // 全局请求限流
             .wrap(
                 Cors::default()
                     .allow_any_origin()
@@ -109,6 +116,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::JsonConfig::default().error_handler(json_error_handler)) // 设置JSON错误处理器
             .app_data(web::Data::new(storage.clone()))
             .app_data(web::Data::new(cache.clone()))
+            .app_data(web::Data::new(mailer.clone()))
+            .app_data(web::Data::new(blob_store.clone()))
             .app_data(web::Data::new(app_start_time.clone()))
             .app_data(web::PayloadConfig::new(
                 config.server.limits.max_payload_size,
@@ -116,8 +125,10 @@ async fn main() -> std::io::Result<()> {
             .configure(routes::configure_auth_routes) // 配置认证相关路由
             .configure(routes::configure_user_routes) // 配置用户相关路由
             .configure(routes::configure_class_users_routes) //配置班级成员相关路由
+            .configure(routes::configure_grades_routes) // 配置作业评分相关路由
+            .configure(routes::configure_submissions_routes) // 配置作业提交相关路由（需在作业路由之前注册）
+            .configure(routes::configure_homeworks_routes) // 配置作业相关路由（需在班级路由之前注册）
             .configure(routes::configure_classes_routes) // 配置班级相关路由
-            .configure(routes::configure_homeworks_routes) // 配置作业相关路由
             .configure(routes::configure_file_routes) // 配置文件相关路由
             .configure(routes::configure_system_routes) // 配置系统相关路由
     })
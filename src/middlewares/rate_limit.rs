@@ -0,0 +1,313 @@
+/*!
+ * 请求限流中间件
+ *
+ * 按固定时间窗口计数限制请求频率，计数由 ObjectCache 原子递增，使用 redis 缓存时多个实例共享计数。
+ * 窗口长度为补满容量所需的时间，窗口内最多允许 `capacity` 个请求，长期平均速率即 `refill_per_minute`。
+ * 已登录用户按用户 ID 计数，未登录请求按客户端 IP 计数。
+ *
+ * ## 使用方法
+ *
+ * ```rust
+ * use actix_web::{web, App, HttpServer};
+ * use crate::middlewares::RateLimit;
+ *
+ * HttpServer::new(|| {
+ *     App::new()
+ *         .wrap(RateLimit::new("global"))  // 全局限流
+ *         .service(
+ *             web::scope("/api/v1/auth")
+ *                 .wrap(RateLimit::new("auth"))  // 使用 rate_limit.scopes.auth 配置的策略
+ *                 .route("/login", web::post().to(login))
+ *         )
+ * })
+ * ```
+ *
+ * 未在 `rate_limit.scopes` 中配置的作用域使用 `rate_limit.default` 策略。
+ * 超出限制时返回 429，并携带 `Retry-After` 与 `X-RateLimit-*` 响应头。
+ */
+
+use actix_service::{Service, Transform};
+use actix_web::{
+    Error, HttpMessage,
+    body::EitherBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        StatusCode,
+        header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER},
+    },
+};
+use futures_util::future::{LocalBoxFuture, Ready, ready};
+use std::{rc::Rc, sync::Arc};
+use tracing::{info, warn};
+
+use crate::cache::ObjectCache;
+use crate::middlewares::RequireJWT;
+use crate::models::{ErrorCode, users::entities};
+use crate::system::app_config::{AppConfig, RateLimitPolicy};
+use crate::utils::client_ip::client_ip;
+use crate::utils::jwt::JwtUtils;
+
+use super::create_error_response;
+
+const X_RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+const X_RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+const X_RATELIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+#[derive(Clone)]
+pub struct RateLimit {
+    scope: &'static str,
+}
+
+impl RateLimit {
+    /// 创建使用指定作用域限流策略的中间件
+    pub fn new(scope: &'static str) -> Self {
+        Self { scope }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            scope: self.scope,
+            policy: AppConfig::get().rate_limit.policy(self.scope),
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    scope: &'static str,
+    policy: Option<RateLimitPolicy>,
+}
+
+// 一次计数的结果
+struct RateLimitDecision {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    reset_after: u64,
+    retry_after: u64,
+}
+
+impl RateLimitDecision {
+    fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(X_RATELIMIT_LIMIT, HeaderValue::from(self.limit));
+        headers.insert(X_RATELIMIT_REMAINING, HeaderValue::from(self.remaining));
+        headers.insert(X_RATELIMIT_RESET, HeaderValue::from(self.reset_after));
+        if !self.allowed {
+            headers.insert(RETRY_AFTER, HeaderValue::from(self.retry_after));
+        }
+    }
+}
+
+// 限流计数对象：已登录用户按用户 ID，否则按客户端 IP
+fn rate_limit_identity(req: &ServiceRequest) -> String {
+    if let Some(user_id) = req.extensions().get::<entities::User>().map(|user| user.id) {
+        return format!("user:{user_id}");
+    }
+
+    // 位于 RequireJWT 之外时请求扩展中还没有用户信息，直接解析 Access Token
+    if let Some(claims) = RequireJWT::extract_bearer_token(req.headers())
+        .and_then(|token| JwtUtils::verify_access_token(token).ok())
+    {
+        return format!("user:{}", claims.sub);
+    }
+
+    // 与登录锁定共用客户端 IP 解析，只信任来自受信任代理的转发请求头
+    match client_ip(req.request()) {
+        Some(ip) => format!("ip:{ip}"),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+// 计数窗口长度（秒）：按补充速率补满容量所需的时间，不补充时为一天
+fn window_seconds(policy: &RateLimitPolicy) -> u64 {
+    if policy.refill_per_minute == 0 {
+        return 86400;
+    }
+    (u64::from(policy.capacity) * 60)
+        .div_ceil(u64::from(policy.refill_per_minute))
+        .clamp(1, 86400)
+}
+
+// 在当前窗口的计数器上原子加一，窗口按时间对齐，键中带窗口序号，窗口切换时自然从零开始
+// 缓存不可用时放行请求，限流不应使整个服务不可用
+async fn take_token(
+    cache: &dyn ObjectCache,
+    key: &str,
+    policy: &RateLimitPolicy,
+) -> RateLimitDecision {
+    let window = window_seconds(policy);
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let window_index = now / window;
+    let reset_after = (window_index + 1) * window - now;
+
+    let count = match cache
+        .increment(&format!("{key}:{window_index}"), window)
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => {
+            warn!("Rate limit counter is unavailable, allowing request: {}", e);
+            0
+        }
+    };
+
+    let limit = u64::from(policy.capacity);
+    RateLimitDecision {
+        allowed: count <= limit,
+        limit: policy.capacity,
+        remaining: limit.saturating_sub(count) as u32,
+        reset_after,
+        retry_after: reset_after,
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &self,
+        ctx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let srv = self.service.clone();
+        let scope = self.scope;
+        let policy = self.policy.clone();
+
+        Box::pin(async move {
+            // 未启用限流或 OPTIONS 预检请求直接放行
+            let Some(policy) = policy.filter(|_| req.method() != actix_web::http::Method::OPTIONS)
+            else {
+                return Ok(srv.call(req).await?.map_into_left_body());
+            };
+
+            let cache = req
+                .app_data::<actix_web::web::Data<Arc<dyn ObjectCache>>>()
+                .expect("Cache not found in app data")
+                .get_ref()
+                .clone();
+
+            let identity = rate_limit_identity(&req);
+            let decision = take_token(
+                cache.as_ref(),
+                &format!("rate_limit:{scope}:{identity}"),
+                &policy,
+            )
+            .await;
+
+            if decision.allowed {
+                let mut res = srv.call(req).await?.map_into_left_body();
+                // 内层作用域已写入的限流头更具体，不再覆盖
+                if !res.headers().contains_key(X_RATELIMIT_LIMIT) {
+                    decision.apply_headers(res.headers_mut());
+                }
+                Ok(res)
+            } else {
+                info!(
+                    "Rate limit exceeded for {} in scope {} on {}",
+                    identity,
+                    scope,
+                    req.path()
+                );
+                let mut response = create_error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    ErrorCode::TooManyRequests,
+                    "Too many requests, please try again later",
+                );
+                decision.apply_headers(response.headers_mut());
+                Ok(req.into_response(response.map_into_right_body()))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::object_cache::moka::MokaCacheWrapper;
+
+    #[actix_web::test]
+    async fn rejects_requests_beyond_capacity_within_window() {
+        let cache = MokaCacheWrapper::new().unwrap();
+        let policy = RateLimitPolicy {
+            capacity: 3,
+            refill_per_minute: 1,
+        };
+
+        for remaining in (0..3).rev() {
+            let decision = take_token(&cache, "rate_limit:test:ip:a", &policy).await;
+            assert!(decision.allowed);
+            assert_eq!(decision.remaining, remaining);
+        }
+        let decision = take_token(&cache, "rate_limit:test:ip:a", &policy).await;
+        assert!(!decision.allowed);
+        assert!(decision.retry_after > 0 && decision.retry_after <= window_seconds(&policy));
+
+        // 不同计数对象互不影响
+        assert!(
+            take_token(&cache, "rate_limit:test:ip:b", &policy)
+                .await
+                .allowed
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_requests_never_exceed_capacity() {
+        let cache = Arc::new(MokaCacheWrapper::new().unwrap());
+        let policy = RateLimitPolicy {
+            capacity: 10,
+            refill_per_minute: 1,
+        };
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let (cache, policy) = (cache.clone(), policy.clone());
+                tokio::spawn(async move {
+                    take_token(cache.as_ref(), "rate_limit:test:user:1", &policy)
+                        .await
+                        .allowed
+                })
+            })
+            .collect();
+        let mut allowed = 0;
+        for task in tasks {
+            if task.await.unwrap() {
+                allowed += 1;
+            }
+        }
+
+        assert_eq!(allowed, 10);
+    }
+
+    #[test]
+    fn window_is_time_to_refill_capacity() {
+        let policy = |capacity, refill_per_minute| RateLimitPolicy {
+            capacity,
+            refill_per_minute,
+        };
+        assert_eq!(window_seconds(&policy(120, 120)), 60);
+        assert_eq!(window_seconds(&policy(20, 10)), 120);
+        assert_eq!(window_seconds(&policy(20, 0)), 86400);
+    }
+}
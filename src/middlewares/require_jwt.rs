@@ -52,10 +52,10 @@
  *
  * ## 认证流程
  *
- * 1. 客户端在请求头中包含 `Authorization: Bearer <JWT_TOKEN>`
- * 2. 中间件提取并验证JWT令牌
+ * 1. 客户端在请求头中包含 `Authorization: Bearer <JWT_TOKEN>`，脚本也可以使用个人访问令牌 `Bearer hwpat_...`
+ * 2. 中间件提取并验证JWT令牌或个人访问令牌
  * 3. 如果令牌有效，将用户信息存储在请求扩展中，继续处理请求
- * 4. 如果令牌无效或缺失，返回401未授权错误
+ * 4. 如果令牌无效或缺失，返回401未授权错误；个人访问令牌的权限范围不允许该请求时返回403
  *
  * ## 配置
  *
@@ -67,13 +67,15 @@ use crate::models::users::entities::UserRole;
 use crate::models::{ErrorCode, users::entities};
 use crate::repository::Storage;
 use crate::system::app_config::AppConfig;
+use crate::utils::api_token;
+use crate::utils::jwt::{Claims, JwtUtils};
 use actix_service::{Service, Transform};
 use actix_web::{
     Error, HttpMessage, HttpResponse,
     body::EitherBody,
     dev::{ServiceRequest, ServiceResponse},
     http::StatusCode,
-    http::header::CONTENT_TYPE,
+    http::header::{CONTENT_TYPE, HeaderMap},
 };
 use futures_util::future::{LocalBoxFuture, Ready, ready};
 use std::{rc::Rc, sync::Arc};
@@ -81,10 +83,37 @@ use tracing::{debug, info};
 
 const BEARER_PREFIX: &str = "Bearer ";
 const AUTHORIZATION_HEADER: &str = "Authorization";
+// 个人访问令牌最后使用时间的更新间隔（秒），避免每个请求都写数据库
+const API_TOKEN_TOUCH_INTERVAL: i64 = 60;
 
 #[derive(Clone)]
 pub struct RequireJWT;
 
+/// 使用个人访问令牌认证时存入请求扩展的令牌信息，此时请求扩展中没有 Claims
+#[derive(Debug, Clone)]
+pub struct ApiTokenContext {
+    pub token_id: i64,
+    pub scopes: Vec<String>,
+}
+
+// 请求携带的凭据
+enum Credential {
+    Jwt(Claims),
+    ApiToken(ApiTokenContext),
+}
+
+// 认证失败的原因
+enum AuthError {
+    Unauthorized(String),
+    ScopeDenied,
+}
+
+impl From<String> for AuthError {
+    fn from(message: String) -> Self {
+        AuthError::Unauthorized(message)
+    }
+}
+
 // 辅助函数：创建错误响应
 fn create_error_response(status: StatusCode, message: &str) -> HttpResponse {
     match status {
@@ -104,57 +133,105 @@ fn create_error_response(status: StatusCode, message: &str) -> HttpResponse {
     }
 }
 
-// 辅助函数：提取并验证 JWT access token
-async fn extract_and_validate_jwt(req: &ServiceRequest) -> Result<entities::User, String> {
-    let token = req
-        .headers()
-        .get(AUTHORIZATION_HEADER)
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.strip_prefix(BEARER_PREFIX))
+// 辅助函数：提取并验证 JWT access token 或个人访问令牌
+async fn extract_and_validate_jwt(
+    req: &ServiceRequest,
+) -> Result<(entities::User, Credential), AuthError> {
+    let token = RequireJWT::extract_bearer_token(req.headers())
         .ok_or_else(|| "Missing or invalid Authorization header".to_string())?;
 
-    crate::utils::jwt::JwtUtils::verify_access_token(token).map_err(|err| {
+    if api_token::is_api_token(token) {
+        return validate_api_token(req, token).await;
+    }
+
+    let claims = JwtUtils::verify_access_token(token).map_err(|err| {
         info!("JWT token validation failed: {}", err);
         "Invalid JWT token".to_string()
     })?;
 
-    let cache = req
-        .app_data::<actix_web::web::Data<Arc<dyn ObjectCache>>>()
-        .expect("Cache not found in app data")
-        .get_ref()
-        .clone();
+    // 检查令牌是否已通过登出吊销
+    let revoked = JwtUtils::is_access_token_revoked(
+        get_cache(req).as_ref(),
+        get_storage(req).as_ref(),
+        &claims,
+    )
+    .await
+    .map_err(|_| "Failed to check token revocation".to_string())?;
+    if revoked {
+        return Err("JWT token has been revoked".to_string().into());
+    }
+
+    let user_id = claims
+        .sub
+        .parse::<i64>()
+        .map_err(|_| "Invalid user ID in JWT".to_string())?;
+
+    let user = load_active_user(req, user_id).await?;
+    Ok((user, Credential::Jwt(claims)))
+}
+
+// 辅助函数：验证个人访问令牌，令牌未吊销、未过期且权限范围允许该请求方法
+async fn validate_api_token(
+    req: &ServiceRequest,
+    token: &str,
+) -> Result<(entities::User, Credential), AuthError> {
+    let storage = get_storage(req);
+    let api_token = storage
+        .get_api_token_by_hash(&api_token::hash(token))
+        .await
+        .map_err(|_| "Failed to retrieve API token from storage".to_string())?
+        .ok_or_else(|| "Invalid API token".to_string())?;
+
+    if api_token.revoked_at.is_some() {
+        return Err("API token has been revoked".to_string().into());
+    }
+
+    let now = chrono::Utc::now();
+    if api_token
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+    {
+        return Err("API token has expired".to_string().into());
+    }
 
-    // 从缓存中获取用户信息
-    match cache.get_raw(&format!("user:{token}")).await {
+    let user = load_active_user(req, api_token.user_id).await?;
+
+    let context = ApiTokenContext {
+        token_id: api_token.id,
+        scopes: api_token.scope_list(),
+    };
+    if !api_token::scopes_allow(&context.scopes, req.method()) {
+        return Err(AuthError::ScopeDenied);
+    }
+
+    let should_touch = api_token
+        .last_used_at
+        .is_none_or(|last_used_at| (now - last_used_at).num_seconds() >= API_TOKEN_TOUCH_INTERVAL);
+    if should_touch && let Err(e) = storage.touch_api_token(api_token.id).await {
+        info!("Failed to update API token {} usage: {}", api_token.id, e);
+    }
+
+    Ok((user, Credential::ApiToken(context)))
+}
+
+// 辅助函数：按用户 ID 加载用户，优先从缓存读取，按用户 ID 缓存以便用户变更时立即失效
+async fn load_active_user(req: &ServiceRequest, user_id: i64) -> Result<entities::User, String> {
+    let cache = get_cache(req);
+    let cache_key = RequireJWT::user_cache_key(user_id);
+    match cache.get_raw(&cache_key).await {
         CacheResult::Found(json) => match serde_json::from_str::<entities::User>(&json) {
             Ok(user) => return Ok(user),
             Err(_) => {
-                cache.remove(&format!("user:{token}")).await;
-                info!("Failed to deserialize user from cache for token: {}", token);
+                cache.remove(&cache_key).await;
+                info!("Failed to deserialize user from cache for ID: {}", user_id);
             }
         },
         _ => {
-            info!("User not found in cache for token: {}", token);
+            info!("User not found in cache for ID: {}", user_id);
         }
     };
 
-    let storage = req
-        .app_data::<actix_web::web::Data<Arc<dyn Storage>>>()
-        .expect("Storage not found in app data")
-        .get_ref()
-        .clone();
-
-    let claims = crate::utils::jwt::JwtUtils::decode_token(token).map_err(|err| {
-        info!("Failed to decode JWT token: {}", err);
-        "Invalid JWT token format".to_string()
-    })?;
-
-    let user_id = claims
-        .sub
-        .parse::<i64>()
-        .map_err(|_| "Invalid user ID in JWT".to_string())?;
-
-    let user = storage
+    let user = get_storage(req)
         .get_user_by_id(user_id)
         .await
         .map_err(|_| "Failed to retrieve user from storage".to_string())?
@@ -168,7 +245,7 @@ async fn extract_and_validate_jwt(req: &ServiceRequest) -> Result<entities::User
     let app_config = AppConfig::get();
     cache
         .insert_raw(
-            format!("user:{token}"),
+            cache_key,
             serde_json::to_string(&user).unwrap(),
             app_config.cache.default_ttl,
         )
@@ -177,6 +254,20 @@ async fn extract_and_validate_jwt(req: &ServiceRequest) -> Result<entities::User
     Ok(user)
 }
 
+fn get_cache(req: &ServiceRequest) -> Arc<dyn ObjectCache> {
+    req.app_data::<actix_web::web::Data<Arc<dyn ObjectCache>>>()
+        .expect("Cache not found in app data")
+        .get_ref()
+        .clone()
+}
+
+fn get_storage(req: &ServiceRequest) -> Arc<dyn Storage> {
+    req.app_data::<actix_web::web::Data<Arc<dyn Storage>>>()
+        .expect("Storage not found in app data")
+        .get_ref()
+        .clone()
+}
+
 impl<S, B> Transform<S, ServiceRequest> for RequireJWT
 where
     S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
@@ -227,14 +318,37 @@ where
 
             // 验证 JWT token
             match extract_and_validate_jwt(&req).await {
-                Ok(user) => {
+                Ok((user, credential)) => {
                     debug!("JWT authentication successful for ID: {}", user.id);
                     // 可以在这里将用户信息添加到请求扩展中，供后续处理程序使用
                     req.extensions_mut().insert(user);
+                    match credential {
+                        Credential::Jwt(claims) => {
+                            req.extensions_mut().insert(claims);
+                        }
+                        Credential::ApiToken(context) => {
+                            req.extensions_mut().insert(context);
+                        }
+                    }
                     let res = srv.call(req).await?.map_into_left_body();
                     Ok(res)
                 }
-                Err(err) => {
+                Err(AuthError::ScopeDenied) => {
+                    info!(
+                        "API token scope denied for {} request to {}",
+                        req.method(),
+                        req.path()
+                    );
+                    Ok(req.into_response(
+                        super::create_error_response(
+                            StatusCode::FORBIDDEN,
+                            ErrorCode::ApiTokenScopeDenied,
+                            "API token scope does not allow this request",
+                        )
+                        .map_into_right_body(),
+                    ))
+                }
+                Err(AuthError::Unauthorized(err)) => {
                     info!(
                         "JWT authentication failed for request to {}: {}",
                         req.path(),
@@ -255,12 +369,43 @@ where
 
 // 辅助函数：从请求中提取用户信息
 impl RequireJWT {
+    /// 用户信息在缓存中的键
+    fn user_cache_key(user_id: i64) -> String {
+        format!("user:{user_id}")
+    }
+
+    /// 使用户的缓存信息立即失效
+    /// 用户角色、状态等信息变更或用户被删除后必须调用，否则旧信息会保留到缓存过期
+    pub async fn invalidate_user_cache(cache: &dyn ObjectCache, user_id: i64) {
+        cache.remove(&Self::user_cache_key(user_id)).await;
+    }
+
+    /// 从请求头中提取 Bearer 令牌
+    pub(crate) fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
+        headers
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.strip_prefix(BEARER_PREFIX))
+    }
+
     /// 从请求扩展中提取用户Claims信息
     /// 此函数应该在应用了RequireJWT中间件的路由处理程序中使用
     pub fn extract_user_claims(req: &actix_web::HttpRequest) -> Option<entities::User> {
         req.extensions().get::<entities::User>().cloned()
     }
 
+    /// 从请求扩展中提取当前 Access Token 的 Claims
+    /// 此函数应该在应用了RequireJWT中间件的路由处理程序中使用
+    pub fn extract_access_claims(req: &actix_web::HttpRequest) -> Option<Claims> {
+        req.extensions().get::<Claims>().cloned()
+    }
+
+    /// 从请求扩展中提取个人访问令牌信息，使用 JWT 认证的请求返回 None
+    /// 此函数应该在应用了RequireJWT中间件的路由处理程序中使用
+    pub fn extract_api_token(req: &actix_web::HttpRequest) -> Option<ApiTokenContext> {
+        req.extensions().get::<ApiTokenContext>().cloned()
+    }
+
     /// 从请求扩展中提取用户ID
     /// 此函数应该在应用了RequireJWT中间件的路由处理程序中使用
     pub fn extract_user_id(req: &actix_web::HttpRequest) -> Option<i64> {
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+// 刷新令牌记录，同一次登录轮换产生的令牌共享 family_id
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RefreshToken {
+    // 令牌唯一标识 (JWT jti)
+    pub jti: String,
+    // 令牌族 ID
+    pub family_id: String,
+    // 所属用户 ID
+    pub user_id: i64,
+    // 过期时间
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    // 吊销时间，为空表示仍然有效
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+    // 签发时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// 登录会话，对应一个刷新令牌族
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Session {
+    // 会话 ID，与刷新令牌的 family_id 相同
+    pub id: String,
+    // 所属用户 ID
+    pub user_id: i64,
+    // 登录设备的 User-Agent
+    pub user_agent: Option<String>,
+    // 登录 IP
+    pub ip_address: Option<String>,
+    // 是否为“记住我”登录
+    pub remember_me: bool,
+    // 创建时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    // 最后使用时间（登录或刷新令牌）
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+    // 过期时间
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    // 终止时间，为空表示仍然有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// 用户的两步验证 (TOTP) 设置
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserMfa {
+    // 用户 ID
+    pub user_id: i64,
+    // Base32 编码的 TOTP 密钥
+    #[serde(skip_serializing)]
+    pub secret: String,
+    // 是否已启用，绑定验证器并校验验证码后才启用
+    pub enabled: bool,
+    // 创建时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    // 启用时间
+    pub enabled_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// 两步验证恢复码，仅保存哈希，每个恢复码只能使用一次
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MfaRecoveryCode {
+    pub id: i64,
+    pub user_id: i64,
+    // 恢复码哈希
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    // 使用时间，为空表示未使用
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// 登录第二步需要完成的操作
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MfaStage {
+    Verify, // 输入验证码或恢复码
+    Enroll, // 角色要求两步验证但尚未启用，需先绑定验证器
+}
+
+// 个人访问令牌，数据库中仅保存哈希，明文只在创建时返回一次
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: i64,
+    // 所属用户 ID
+    pub user_id: i64,
+    // 令牌名称
+    pub name: String,
+    // 令牌明文的前几位，用于辨认令牌
+    pub token_prefix: String,
+    // 权限范围，多个以空格分隔，为空表示拥有用户的全部权限
+    pub scopes: String,
+    // 过期时间，为空表示永不过期
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    // 最后使用时间
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    // 创建时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    // 吊销时间，为空表示仍然有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ApiToken {
+    /// 权限范围列表
+    pub fn scope_list(&self) -> Vec<String> {
+        self.scopes.split_whitespace().map(str::to_string).collect()
+    }
+}
+
+// 关联到本地用户的外部身份，同一身份提供方的 subject 唯一
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserIdentity {
+    pub id: i64,
+    // 关联的用户 ID
+    pub user_id: i64,
+    // 身份提供方名称，对应配置中的 provider
+    pub provider: String,
+    // 身份提供方中的用户标识 (sub 声明)
+    pub subject: String,
+    // 最近一次登录时身份提供方返回的邮箱
+    pub email: Option<String>,
+    // 关联时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    // 最近一次通过该身份登录的时间
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+}
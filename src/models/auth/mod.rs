@@ -1,8 +1,13 @@
+// 令牌实体
+pub mod entities;
+
 // 登录请求模型
 pub mod requests;
 
 // 登录响应模型
 pub mod responses;
 
-pub use requests::LoginRequest;
+pub use requests::{
+    ForgotPasswordRequest, LoginRequest, RegisterRequest, ResetPasswordRequest, VerifyEmailRequest,
+};
 pub use responses::LoginResponse;
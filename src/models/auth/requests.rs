@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::models::users::entities::UserProfile;
+
 // 用户登录请求（来自HTTP请求）
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
@@ -11,3 +13,100 @@ pub struct LoginRequest {
     #[serde(default)]
     pub remember_me: bool,
 }
+
+// 用户自助注册请求（来自HTTP请求），角色和状态由注册策略决定
+#[derive(Debug, Deserialize)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub profile: UserProfile,
+    /// 邀请码，仅在仅限邀请注册模式下需要
+    #[serde(default)]
+    pub invite_code: Option<String>,
+}
+
+// 邮箱验证请求
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+// 忘记密码请求
+#[derive(Debug, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+// 重置密码请求
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+// 更新个人资料请求，未提供的字段保持不变；头像只能通过上传接口修改
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub profile_name: Option<String>,
+}
+
+// 修改密码请求
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+// 登录第二步请求，code 为 6 位验证码或恢复码
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginRequest {
+    pub mfa_token: String,
+    pub code: String,
+}
+
+// 登录时绑定验证器请求（角色要求两步验证但尚未启用）
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginSetupRequest {
+    pub mfa_token: String,
+}
+
+// 两步验证码请求，code 为 6 位验证码，部分接口也接受恢复码
+#[derive(Debug, Deserialize)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+// 关闭两步验证请求
+#[derive(Debug, Deserialize)]
+pub struct DisableMfaRequest {
+    pub password: String,
+    pub code: String,
+}
+
+// 创建个人访问令牌请求
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    // 权限范围（read / write），为空表示拥有用户的全部权限
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    // 有效天数，为空表示永不过期
+    pub expires_in_days: Option<i64>,
+}
+
+// 发起单点登录的查询参数
+#[derive(Debug, Deserialize)]
+pub struct OidcLoginQuery {
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+// 身份提供方回调的查询参数，授权失败时只有 error 与 error_description
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
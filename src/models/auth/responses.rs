@@ -1,3 +1,4 @@
+use super::entities::{ApiToken, MfaStage, Session};
 use crate::models::users::entities::User;
 use serde::Serialize;
 
@@ -8,6 +9,9 @@ pub struct LoginResponse {
     pub expires_in: i64,
     pub user: User,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    // 登录时完成两步验证绑定后返回的恢复码，只展示这一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,3 +29,98 @@ pub struct UserInfoResponse {
 pub struct TokenVerificationResponse {
     pub is_valid: bool,
 }
+
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    #[serde(flatten)]
+    pub session: Session,
+    // 是否为发起请求的当前会话
+    pub is_current: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionListResponse {
+    pub items: Vec<SessionResponse>,
+}
+
+// 密码校验通过但仍需两步验证时的登录响应
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub stage: MfaStage,
+    pub expires_in: i64,
+}
+
+// 两步验证绑定信息，secret 用于手动输入，otpauth_uri 用于生成二维码
+#[derive(Debug, Serialize)]
+pub struct MfaSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+// 新生成的恢复码，只展示这一次
+#[derive(Debug, Serialize)]
+pub struct MfaRecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    // 当前角色是否必须启用两步验证
+    pub required: bool,
+    pub recovery_codes_remaining: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ApiToken> for ApiTokenResponse {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            scopes: token.scope_list(),
+            id: token.id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+// 新创建的个人访问令牌，明文令牌只展示这一次
+#[derive(Debug, Serialize)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+    pub token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiTokenListResponse {
+    pub items: Vec<ApiTokenResponse>,
+}
+
+// 可用于单点登录的身份提供方
+#[derive(Debug, Serialize)]
+pub struct OidcProviderResponse {
+    pub name: String,
+    pub display_name: String,
+    // 发起登录的地址
+    pub login_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OidcProviderListResponse {
+    pub items: Vec<OidcProviderResponse>,
+}
@@ -69,7 +69,7 @@ impl std::str::FromStr for ClassUserRole {
     }
 }
 
-// 分别为 PostgreSQL 和 SQLite 实现
+// 分别为 PostgreSQL、SQLite 和 MySQL 实现
 sqlx_enum_type!(
     sqlx::Postgres,
     sqlx::postgres::PgValueRef<'r>,
@@ -80,6 +80,7 @@ sqlx_enum_type!(
     sqlx::sqlite::SqliteValueRef<'r>,
     ClassUserRole
 );
+sqlx_enum_type!(sqlx::MySql, sqlx::mysql::MySqlValueRef<'r>, ClassUserRole);
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ClassUser {
@@ -93,10 +94,16 @@ pub struct ClassUser {
 }
 
 impl ClassUser {
-    pub fn from_row_prefix(
-        prefix: &str,
-        row: &sqlx::sqlite::SqliteRow,
-    ) -> Result<Self, sqlx::Error> {
+    // 从带前缀的联表查询结果中解析班级用户，支持不同数据库后端
+    pub fn from_row_prefix<'r, R>(prefix: &str, row: &'r R) -> Result<Self, sqlx::Error>
+    where
+        R: Row,
+        for<'a> &'a str: sqlx::ColumnIndex<R>,
+        i64: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+        Option<String>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+        ClassUserRole: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+        chrono::DateTime<chrono::Utc>: sqlx::Decode<'r, R::Database> + sqlx::Type<R::Database>,
+    {
         Ok(Self {
             id: row.try_get(&*format!("{prefix}id"))?,
             class_id: row.try_get(&*format!("{prefix}class_id"))?,
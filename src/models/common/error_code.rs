@@ -9,17 +9,49 @@ pub enum ErrorCode {
     InternalServerError = 1005, // 内部服务器错误
     NotImplemented = 1006,      // 未实现的功能
     Conflict = 1009,            // 冲突 (资源已存在)
+    TooManyRequests = 1029,     // 请求过于频繁
 
     // Auth 错误
-    AuthFailed = 2000,     // 身份验证失败
-    RegisterFailed = 2001, // 注册失败
+    AuthFailed = 2000,               // 身份验证失败
+    RegisterFailed = 2001,           // 注册失败
+    SessionNotFound = 2002,          // 登录会话未找到
+    AccountLocked = 2003,            // 账号因多次登录失败被临时锁定
+    TooManyLoginAttempts = 2004,     // 登录尝试过于频繁
+    AccountNotActive = 2005,         // 账号未激活、待审核或已暂停
+    RegistrationClosed = 2006,       // 当前不开放自助注册
+    InviteCodeInvalid = 2007,        // 注册邀请码无效
+    EmailTokenInvalid = 2008,        // 邮件链接令牌无效、已过期或已使用
+    EmailAlreadyVerified = 2009,     // 邮箱已验证
+    MfaCodeInvalid = 2010,           // 两步验证码或恢复码错误
+    MfaTokenInvalid = 2011,          // 登录第二步令牌无效或已过期
+    MfaNotEnabled = 2012,            // 未启用两步验证或尚未开始绑定
+    MfaAlreadyEnabled = 2013,        // 已启用两步验证
+    MfaRequired = 2014,              // 当前角色必须启用两步验证
+    ApiTokenNotFound = 2015,         // 个人访问令牌未找到
+    ApiTokenScopeDenied = 2016,      // 个人访问令牌的权限范围不允许该请求
+    ApiTokenNotAllowed = 2017,       // 该操作不能使用个人访问令牌
+    OidcProviderNotFound = 2018,     // 单点登录身份提供方未配置
+    OidcStateInvalid = 2019,         // 单点登录请求无效、已过期或已使用
+    OidcLoginFailed = 2020,          // 与身份提供方交换或验证令牌失败
+    OidcAccountNotLinked = 2021,     // 外部账号未关联本地用户且不允许自动创建
+    AuthServiceUnavailable = 2022,   // 外部认证服务 (例如 LDAP) 不可用
+    ExternalAccountNotLinked = 2023, // 外部认证通过但无法对应到本地用户
 
     // 文件相关错误
-    FileNotFound = 3000,              // 文件未找到
-    FileUploadFailed = 3001,          // 文件上传失败
-    FileTypeNotAllowed = 3002,        // 文件类型不被允许
-    FileSizeExceeded = 3003,          // 文件大小超出限制
-    MuitifileUploadNotAllowed = 3004, // 不允许多文件上传
+    FileNotFound = 3000,               // 文件未找到
+    FileUploadFailed = 3001,           // 文件上传失败
+    FileTypeNotAllowed = 3002,         // 文件类型不被允许
+    FileSizeExceeded = 3003,           // 文件大小超出限制
+    MuitifileUploadNotAllowed = 3004,  // 不允许多文件上传
+    ImageInvalid = 3005,               // 图片无法解码或尺寸超出限制
+    FileRangeNotSatisfiable = 3006,    // 请求的字节范围超出文件大小
+    FileAccessDenied = 3007,           // 无权访问该文件
+    FileLinkInvalid = 3008,            // 下载链接无效或已过期
+    UploadSessionNotFound = 3009,      // 上传会话不存在或已过期
+    UploadOffsetMismatch = 3010,       // 分块起始位置与已接收的字节数不一致
+    UploadChunkInvalid = 3011,         // 分块大小不符合要求
+    UploadIncomplete = 3012,           // 文件尚未全部上传
+    UploadSessionLimitExceeded = 3013, // 未完成的上传会话数量达到上限
 
     // 用户相关错误
     UserNotFound = 4000,            // 用户未找到
@@ -28,11 +60,15 @@ pub enum ErrorCode {
     UserDeleteFailed = 4003,        // 用户删除失败
     UserCreationFailed = 4004,      // 用户创建失败
     CanNotDeleteCurrentUser = 4005, // 不能删除当前用户
+    RegistrationNotPending = 4006,  // 用户不是待审核的注册申请
 
     UserNameInvalid = 4010,        // 用户名无效
     UserNameAlreadyExists = 4011,  // 用户名已存在
     UserEmailInvalid = 4012,       // 用户邮箱无效
     UserEmailAlreadyExists = 4013, // 用户邮箱已存在、
+    UserPasswordInvalid = 4014,    // 用户密码不符合要求
+    UserImportInvalid = 4015,      // 批量导入的文件无法解析或存在不合法的行
+    UserStatusInvalid = 4016,      // 待审核状态只能由自助注册设置
 
     // 班级相关错误
     ClassNotFound = 5000,          // 班级未找到
@@ -46,4 +82,24 @@ pub enum ErrorCode {
     ClassAlreadyJoined = 5012,     // 已经加入该班级
     ClassJoinForbidden = 5013,     // 加入班级被禁止
     ClassUserNotFound = 5014,      // 班级用户未找到
+
+    // 作业相关错误
+    HomeworkNotFound = 6000,       // 作业未找到
+    HomeworkCreationFailed = 6001, // 作业创建失败
+    HomeworkUpdateFailed = 6002,   // 作业更新失败
+    HomeworkDeleteFailed = 6003,   // 作业删除失败
+    HomeworkInvalid = 6004,        // 作业参数无效
+
+    // 提交相关错误
+    SubmissionNotFound = 7000,       // 提交未找到
+    SubmissionCreationFailed = 7001, // 提交失败
+    SubmissionDeadlinePassed = 7002, // 已过截止时间且不允许迟交
+    SubmissionForbidden = 7003,      // 无权提交该作业
+    SubmissionInvalid = 7004,        // 提交参数无效
+    SubmissionAlreadyGraded = 7005,  // 提交已评分，不能重新提交
+
+    // 评分相关错误
+    GradeNotFound = 8000,       // 评分未找到
+    GradeCreationFailed = 8001, // 评分失败
+    GradeInvalid = 8002,        // 评分参数无效
 }
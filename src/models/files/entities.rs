@@ -14,4 +14,61 @@ pub struct File {
     pub uploaded_at: chrono::DateTime<chrono::Utc>,
     // 用户ID
     pub user_id: i64,
+    // 被作业与提交作为附件引用的次数
+    pub citation_count: i32,
+    // 文件内容的 SHA-256，旧版本上传的文件为空
+    pub content_hash: Option<String>,
+}
+
+impl File {
+    /// 文件内容在文件存储中的键
+    /// 内容相同的文件共用以内容哈希为键的同一份内容，旧版本上传的文件以文件标识为键
+    pub fn blob_key(&self) -> &str {
+        self.content_hash
+            .as_deref()
+            .unwrap_or(&self.submission_token)
+    }
+}
+
+// 可续传上传会话
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UploadSession {
+    // 会话 ID
+    pub id: String,
+    // 上传用户 ID
+    pub user_id: i64,
+    // 文件名称
+    pub file_name: String,
+    // 文件类型
+    pub file_type: String,
+    // 文件总大小（以字节为单位）
+    pub file_size: i64,
+    // 分块大小，除最后一块外每块必须恰好为该大小
+    pub chunk_size: i64,
+    // 已接收的字节数，即下一个分块的起始位置
+    pub upload_offset: i64,
+    // 已接收内容的 SHA-256 中间状态，见 Sha256State::encode
+    pub hash_state: String,
+    // 已接收分块的 ID，按顺序以空格分隔；每次写入分块都使用新的 ID，并发写入同一位置时互不覆盖
+    pub chunk_ids: String,
+    // 创建时间
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    // 过期时间
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl UploadSession {
+    /// 分块在文件存储中的键
+    pub fn chunk_key(&self, index: usize, chunk_id: &str) -> String {
+        format!("partial/{}/{index}-{chunk_id}", self.id)
+    }
+
+    /// 已接收分块的键，按顺序排列
+    pub fn chunk_keys(&self) -> Vec<String> {
+        self.chunk_ids
+            .split_whitespace()
+            .enumerate()
+            .map(|(index, chunk_id)| self.chunk_key(index, chunk_id))
+            .collect()
+    }
 }
@@ -3,3 +3,6 @@ pub mod entities;
 
 // 文件响应模型
 pub mod responses;
+
+// 文件请求模型
+pub mod requests;
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+// 通过签名链接下载文件的查询参数
+#[derive(Debug, Deserialize)]
+pub struct SignedDownloadQuery {
+    /// 签发下载链接时生成的令牌
+    pub token: String,
+}
+
+// 创建可续传上传会话的请求
+#[derive(Debug, Deserialize)]
+pub struct CreateUploadSessionRequest {
+    /// 原始文件名
+    pub file_name: String,
+    /// 文件类型
+    pub file_type: String,
+    /// 文件总大小(字节)
+    pub size: i64,
+}
+
+// 上传分块的查询参数
+#[derive(Debug, Deserialize)]
+pub struct UploadChunkQuery {
+    /// 分块的起始位置，必须等于会话已接收的字节数
+    pub offset: i64,
+}
@@ -1,3 +1,4 @@
+use super::entities::UploadSession;
 use serde::Serialize;
 
 /// FileAttachment
@@ -14,3 +15,45 @@ pub struct FileUploadResponse {
     /// 上传时间
     pub uploaded_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// 短期下载链接
+#[derive(Serialize)]
+pub struct FileDownloadUrlResponse {
+    /// 下载地址 (相对路径)，无需携带 Authorization 头
+    pub url: String,
+    /// 过期时间
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 可续传上传会话的状态
+#[derive(Serialize)]
+pub struct UploadSessionResponse {
+    /// 会话 ID
+    pub upload_id: String,
+    /// 原始文件名
+    pub file_name: String,
+    /// 文件类型
+    pub content_type: String,
+    /// 文件总大小(字节)
+    pub size: i64,
+    /// 已接收的字节数，下一个分块从这里开始
+    pub offset: i64,
+    /// 分块大小，除最后一块外每块必须恰好为该大小
+    pub chunk_size: i64,
+    /// 过期时间，每接收一个分块顺延
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<UploadSession> for UploadSessionResponse {
+    fn from(session: UploadSession) -> Self {
+        Self {
+            upload_id: session.id,
+            file_name: session.file_name,
+            content_type: session.file_type,
+            size: session.file_size,
+            offset: session.upload_offset,
+            chunk_size: session.chunk_size,
+            expires_at: session.expires_at,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Grade {
+    // 唯一 ID
+    pub id: i64,
+    // 关联的提交 ID
+    pub submission_id: i64,
+    // 评分人 ID
+    pub grader_id: i64,
+    // 分数
+    pub score: f64,
+    // 评语
+    pub comment: Option<String>,
+    // 评分时间
+    pub graded_at: chrono::DateTime<chrono::Utc>,
+}
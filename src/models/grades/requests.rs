@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+// 评分请求，重新评分时会新增一条评分记录
+#[derive(Debug, Deserialize)]
+pub struct CreateGradeRequest {
+    pub score: f64,
+    pub comment: Option<String>,
+}
@@ -0,0 +1,8 @@
+use super::entities::Grade;
+use serde::Serialize;
+
+// 评分历史响应，按评分时间倒序排列
+#[derive(Debug, Serialize)]
+pub struct GradeListResponse {
+    pub items: Vec<Grade>,
+}
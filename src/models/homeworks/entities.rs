@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, sqlx::FromRow)]
+// 作业状态（根据截止时间计算）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeworkStatus {
+    Active,  // 进行中（未截止或无截止时间）
+    Expired, // 已截止
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Homework {
     // 唯一 ID
     pub id: i64,
@@ -10,8 +18,9 @@ pub struct Homework {
     pub title: String,
     // 作业描述
     pub content: Option<String>,
-    // 作业附件
-    pub attachments: Option<String>,
+    // 作业附件（文件 submission_token 列表）
+    #[sqlx(json)]
+    pub attachments: Vec<String>,
     // 作业最高分数
     pub max_score: f64,
     // 作业截止时间
@@ -25,3 +34,10 @@ pub struct Homework {
     // 作业更新时间
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+impl Homework {
+    /// 判断作业在指定时间是否已截止
+    pub fn is_expired_at(&self, time: chrono::DateTime<chrono::Utc>) -> bool {
+        self.deadline.is_some_and(|deadline| time > deadline)
+    }
+}
@@ -1,11 +1,54 @@
+use super::entities::HomeworkStatus;
 use crate::models::common::pagination::PaginationQuery;
 use serde::Deserialize;
 
+// 作业查询参数（来自HTTP请求）
 #[derive(Debug, Deserialize)]
-pub struct HomeworkListQuery {
+pub struct HomeworkListParams {
     #[serde(flatten)]
     pub pagination: PaginationQuery,
-    pub status: Option<String>,
+    pub status: Option<HomeworkStatus>,
+    pub search: Option<String>,
+    pub order_by: Option<String>,
+    pub order: Option<String>,
+}
+
+// 创建作业请求
+#[derive(Debug, Deserialize)]
+pub struct CreateHomeworkRequest {
+    pub title: String,
+    pub content: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    pub max_score: f64,
+    pub deadline: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub allow_late_submission: bool,
+}
+
+// 更新作业请求
+#[derive(Debug, Deserialize)]
+pub struct UpdateHomeworkRequest {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub attachments: Option<Vec<String>>,
+    pub max_score: Option<f64>,
+    // 传入 null 表示清除截止时间，不传表示不修改
+    #[serde(default, with = "::serde_with::rust::double_option")]
+    pub deadline: Option<Option<chrono::DateTime<chrono::Utc>>>,
+    pub allow_late_submission: Option<bool>,
+}
+
+// 作业列表查询参数（用于存储层）
+#[derive(Debug, Clone, Deserialize)]
+pub struct HomeworkListQuery {
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+    // 限定班级
+    pub class_id: Option<i64>,
+    // 限定为该用户所在班级的作业
+    pub user_id: Option<i64>,
+    pub status: Option<HomeworkStatus>,
     pub search: Option<String>,
     pub order_by: Option<String>,
     pub order: Option<String>,
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+// 提交状态（根据是否迟交区分）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    OnTime, // 按时提交
+    Late,   // 迟交
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Submission {
+    // 唯一 ID
+    pub id: i64,
+    // 关联的作业 ID
+    pub homework_id: i64,
+    // 提交者 ID
+    pub creator_id: i64,
+    // 提交内容
+    pub content: String,
+    // 提交附件（文件 submission_token 列表）
+    #[sqlx(json)]
+    pub attachments: Vec<String>,
+    // 是否为迟交
+    pub is_late: bool,
+    // 提交时间
+    pub submitted_at: chrono::DateTime<chrono::Utc>,
+}
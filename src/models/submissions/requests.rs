@@ -0,0 +1,28 @@
+use super::entities::SubmissionStatus;
+use crate::models::common::pagination::PaginationQuery;
+use serde::Deserialize;
+
+// 提交查询参数（来自HTTP请求）
+#[derive(Debug, Deserialize)]
+pub struct SubmissionListParams {
+    #[serde(flatten)]
+    pub pagination: PaginationQuery,
+    pub status: Option<SubmissionStatus>,
+}
+
+// 提交作业请求
+#[derive(Debug, Deserialize)]
+pub struct CreateSubmissionRequest {
+    pub content: String,
+    #[serde(default)]
+    pub attachments: Vec<String>,
+}
+
+// 提交列表查询参数（用于存储层）
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubmissionListQuery {
+    pub page: Option<i64>,
+    pub size: Option<i64>,
+    pub homework_id: i64,
+    pub status: Option<SubmissionStatus>,
+}
@@ -0,0 +1,10 @@
+use super::entities::Submission;
+use crate::models::common::pagination::PaginationInfo;
+use serde::Serialize;
+
+// 提交列表响应
+#[derive(Debug, Serialize)]
+pub struct SubmissionListResponse {
+    pub items: Vec<Submission>,
+    pub pagination: PaginationInfo,
+}
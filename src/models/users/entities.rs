@@ -71,9 +71,10 @@ impl std::str::FromStr for UserRole {
     }
 }
 
-// 分别为 PostgreSQL 和 SQLite 实现
+// 分别为 PostgreSQL、SQLite 和 MySQL 实现
 sqlx_enum_type!(sqlx::Postgres, sqlx::postgres::PgValueRef<'r>, UserRole);
 sqlx_enum_type!(sqlx::Sqlite, sqlx::sqlite::SqliteValueRef<'r>, UserRole);
+sqlx_enum_type!(sqlx::MySql, sqlx::mysql::MySqlValueRef<'r>, UserRole);
 
 // 用户状态
 #[derive(Debug, Clone, Serialize, PartialEq)]
@@ -82,6 +83,7 @@ pub enum UserStatus {
     Active,    // 活跃
     Inactive,  // 非活跃
     Suspended, // 暂停
+    Pending,   // 自助注册后等待管理员审核，只能由注册流程设置
 }
 
 impl<'de> Deserialize<'de> for UserStatus {
@@ -94,8 +96,9 @@ impl<'de> Deserialize<'de> for UserStatus {
             "active" => Ok(UserStatus::Active),
             "inactive" => Ok(UserStatus::Inactive),
             "suspended" => Ok(UserStatus::Suspended),
+            "pending" => Ok(UserStatus::Pending),
             _ => Err(serde::de::Error::custom(format!(
-                "无效的用户状态: '{s}'. 支持的状态: active, inactive, suspended"
+                "无效的用户状态: '{s}'. 支持的状态: active, inactive, suspended, pending"
             ))),
         }
     }
@@ -107,6 +110,7 @@ impl std::fmt::Display for UserStatus {
             UserStatus::Active => write!(f, "active"),
             UserStatus::Inactive => write!(f, "inactive"),
             UserStatus::Suspended => write!(f, "suspended"),
+            UserStatus::Pending => write!(f, "pending"),
         }
     }
 }
@@ -119,14 +123,16 @@ impl std::str::FromStr for UserStatus {
             "active" => Ok(UserStatus::Active),
             "inactive" => Ok(UserStatus::Inactive),
             "suspended" => Ok(UserStatus::Suspended),
+            "pending" => Ok(UserStatus::Pending),
             _ => Err(format!("Invalid user status: {s}")),
         }
     }
 }
 
-// 分别为 PostgreSQL 和 SQLite 实现
+// 分别为 PostgreSQL、SQLite 和 MySQL 实现
 sqlx_enum_type!(sqlx::Postgres, sqlx::postgres::PgValueRef<'r>, UserStatus);
 sqlx_enum_type!(sqlx::Sqlite, sqlx::sqlite::SqliteValueRef<'r>, UserStatus);
+sqlx_enum_type!(sqlx::MySql, sqlx::mysql::MySqlValueRef<'r>, UserStatus);
 
 // 用户资料
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -141,6 +147,8 @@ pub struct User {
     pub id: i64,
     pub username: String,
     pub email: String,
+    #[serde(default)]
+    pub email_verified: bool,
     #[serde(skip_serializing, default)] // 不序列化到JSON响应中
     pub password_hash: String,
     pub role: UserRole,
@@ -156,7 +164,11 @@ impl User {
     // 生成访问令牌（使用真正的 JWT）
     pub async fn generate_access_token(&self) -> String {
         // 使用 JwtUtils 生成 access token
-        match crate::utils::jwt::JwtUtils::generate_access_token(self.id, &self.role.to_string()) {
+        match crate::utils::jwt::JwtUtils::generate_access_token(
+            self.id,
+            &self.role.to_string(),
+            None,
+        ) {
             Ok(token) => token,
             Err(e) => {
                 // 如果 JWT 生成失败，返回一个简单的 token（不推荐在生产环境中使用）
@@ -178,9 +190,10 @@ impl User {
         match crate::utils::jwt::JwtUtils::generate_refresh_token(
             self.id,
             &self.role.to_string(),
+            None,
             refresh_token_expiry,
         ) {
-            Ok(token) => token,
+            Ok((token, _)) => token,
             Err(e) => {
                 tracing::error!("JWT refresh token 生成失败: {}", e);
                 format!(
@@ -19,6 +19,9 @@ pub struct CreateUserRequest {
     pub email: String,
     pub password: String,
     pub role: UserRole,
+    /// 初始状态，为空时为 Active
+    #[serde(default)]
+    pub status: Option<UserStatus>,
     pub profile: UserProfile,
 }
 
@@ -41,3 +44,30 @@ pub struct UserListQuery {
     pub status: Option<UserStatus>,
     pub search: Option<String>,
 }
+
+// 批量导入用户的查询参数
+#[derive(Debug, Deserialize)]
+pub struct UserImportQuery {
+    /// 只校验不写入
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// 用户导出格式
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+// 批量导出用户的查询参数，筛选条件与用户列表一致
+#[derive(Debug, Deserialize)]
+pub struct UserExportParams {
+    #[serde(default)]
+    pub format: UserExportFormat,
+    pub role: Option<UserRole>,
+    pub status: Option<UserStatus>,
+    pub search: Option<String>,
+}
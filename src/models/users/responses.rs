@@ -1,4 +1,4 @@
-use super::entities::User;
+use super::entities::{User, UserRole};
 use crate::models::common::PaginationInfo;
 use serde::Serialize;
 
@@ -14,3 +14,29 @@ pub struct UserListResponse {
     pub items: Vec<User>,
     pub pagination: PaginationInfo,
 }
+
+// 批量导入中单行的校验与创建结果
+#[derive(Debug, Serialize)]
+pub struct UserImportRowResult {
+    pub row: usize, // 文件中的行号，表头为第 1 行
+    pub username: String,
+    pub email: String,
+    pub role: Option<UserRole>,
+    pub errors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<i64>,
+    // 文件中未提供密码时生成的初始密码，只在实际创建后返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_password: Option<String>,
+}
+
+// 批量导入报告
+#[derive(Debug, Serialize)]
+pub struct UserImportResponse {
+    pub dry_run: bool,
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    pub created: usize,
+    pub rows: Vec<UserImportRowResult>,
+}
@@ -1,2 +1,3 @@
+pub mod mysql;
 pub mod postgresql;
 pub mod sqlite;
@@ -0,0 +1,442 @@
+use crate::errors::{HWSystemError, Result};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use sqlx::Row;
+use tracing::info;
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub version: i32,
+    pub name: String,
+    pub up_sql: String,
+}
+
+pub struct MysqlMigrationManager {
+    pool: MySqlPool,
+}
+
+impl MysqlMigrationManager {
+    pub fn new(pool: MySqlPool) -> Self {
+        Self { pool }
+    }
+
+    /// 初始化迁移表
+    pub async fn init(&self) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INT PRIMARY KEY,
+                name VARCHAR(255) NOT NULL,
+                applied_at BIGINT NOT NULL,
+                checksum VARCHAR(64)
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("创建迁移表失败: {e}")))?;
+
+        Ok(())
+    }
+
+    /// 获取当前数据库版本
+    pub async fn get_current_version(&self) -> Result<i32> {
+        let result = sqlx::query("SELECT MAX(version) as version FROM schema_migrations")
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| HWSystemError::database_operation(format!("查询数据库版本失败: {e}")))?;
+
+        match result {
+            Some(row) => {
+                let version: Option<i32> = row.get("version");
+                Ok(version.unwrap_or(0))
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// 应用单个迁移
+    pub async fn apply_migration(&self, migration: &Migration) -> Result<()> {
+        info!("应用迁移 v{}: {}", migration.version, migration.name);
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| HWSystemError::database_operation(format!("开始迁移事务失败: {e}")))?;
+
+        // 拆分多条语句按顺序执行，MySQL 的 DDL 会隐式提交，事务只能保证迁移记录的原子性
+        for stmt in migration.up_sql.split(';') {
+            let trimmed = stmt.trim();
+            if !trimmed.is_empty() {
+                sqlx::query(trimmed).execute(&mut *tx).await.map_err(|e| {
+                    HWSystemError::database_operation(format!(
+                        "执行迁移v{}失败: {}",
+                        migration.version, e
+                    ))
+                })?;
+            }
+        }
+
+        // 计算校验和
+        let mut hasher = Sha256::new();
+        hasher.update(&migration.up_sql);
+        let checksum = format!("{:x}", hasher.finalize());
+
+        sqlx::query("INSERT INTO schema_migrations (version, name, applied_at, checksum) VALUES (?, ?, ?, ?)")
+            .bind(migration.version)
+            .bind(&migration.name)
+            .bind(chrono::Utc::now().timestamp())
+            .bind(&checksum)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| HWSystemError::database_operation(format!("记录迁移v{}失败: {}", migration.version, e)))?;
+
+        tx.commit().await.map_err(|e| {
+            HWSystemError::database_operation(format!("提交迁移v{}失败: {}", migration.version, e))
+        })?;
+
+        info!("迁移 v{} 应用成功", migration.version);
+        Ok(())
+    }
+
+    /// 运行所有待应用的迁移
+    pub async fn migrate_up(&self) -> Result<()> {
+        let current_version = self.get_current_version().await?;
+        let migrations = get_all_migrations();
+
+        let pending_migrations: Vec<_> = migrations
+            .into_iter()
+            .filter(|m| m.version > current_version)
+            .collect();
+
+        if pending_migrations.is_empty() {
+            info!("数据库已是最新版本 v{}", current_version);
+            return Ok(());
+        }
+
+        info!("发现 {} 个待应用的迁移", pending_migrations.len());
+
+        for migration in pending_migrations {
+            self.apply_migration(&migration).await?;
+        }
+
+        let new_version = self.get_current_version().await?;
+        info!("数据库迁移完成，当前版本: v{}", new_version);
+
+        Ok(())
+    }
+}
+
+/// 获取所有迁移定义
+pub fn get_all_migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            name: "create_table_with_indexes".to_string(),
+            up_sql: "
+                -- 创建用户表
+                CREATE TABLE IF NOT EXISTS users (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    username VARCHAR(255) NOT NULL UNIQUE,
+                    email VARCHAR(255) NOT NULL UNIQUE,
+                    password_hash VARCHAR(255) NOT NULL,
+                    role VARCHAR(32) NOT NULL,
+                    status VARCHAR(32) NOT NULL,
+                    profile_name VARCHAR(255),
+                    avatar_url TEXT,
+                    last_login DATETIME(6),
+                    created_at DATETIME(6) NOT NULL,
+                    updated_at DATETIME(6) NOT NULL,
+                    INDEX idx_users_role (role),
+                    INDEX idx_users_status (status),
+                    INDEX idx_users_last_login (last_login)
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建班级表
+                CREATE TABLE IF NOT EXISTS classes (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    teacher_id BIGINT NOT NULL,
+                    class_name VARCHAR(255) NOT NULL UNIQUE,
+                    description TEXT,
+                    invite_code VARCHAR(32) NOT NULL UNIQUE,
+                    created_at DATETIME(6) NOT NULL,
+                    updated_at DATETIME(6) NOT NULL,
+                    INDEX idx_classes_teacher_id (teacher_id),
+                    FOREIGN KEY (teacher_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建班级学生关联表
+                CREATE TABLE IF NOT EXISTS class_users (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    class_id BIGINT NOT NULL,
+                    user_id BIGINT NOT NULL,
+                    role VARCHAR(32) NOT NULL,
+                    updated_at DATETIME(6) NOT NULL,
+                    joined_at DATETIME(6) NOT NULL,
+                    UNIQUE KEY uk_class_users_class_user (class_id, user_id),
+                    INDEX idx_class_users_user_id (user_id),
+                    INDEX idx_class_users_role (role),
+                    FOREIGN KEY (class_id) REFERENCES classes(id) ON DELETE CASCADE,
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建作业表
+                CREATE TABLE IF NOT EXISTS homeworks (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    class_id BIGINT NOT NULL,
+                    created_by BIGINT NOT NULL,
+                    title VARCHAR(255) NOT NULL,
+                    content TEXT,
+                    attachments JSON NOT NULL,
+                    max_score DOUBLE NOT NULL,
+                    deadline DATETIME(6),
+                    allow_late_submission BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at DATETIME(6) NOT NULL,
+                    updated_at DATETIME(6) NOT NULL,
+                    INDEX idx_homeworks_class_id (class_id),
+                    INDEX idx_homeworks_deadline (deadline),
+                    FOREIGN KEY (class_id) REFERENCES classes(id) ON DELETE CASCADE,
+                    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建提交表，同一学生对同一作业只保留一份提交
+                CREATE TABLE IF NOT EXISTS submissions (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    homework_id BIGINT NOT NULL,
+                    creator_id BIGINT NOT NULL,
+                    content TEXT NOT NULL,
+                    attachments JSON NOT NULL,
+                    is_late BOOLEAN NOT NULL DEFAULT FALSE,
+                    submitted_at DATETIME(6) NOT NULL,
+                    UNIQUE KEY uk_submissions_homework_creator (homework_id, creator_id),
+                    INDEX idx_submissions_creator_id (creator_id),
+                    FOREIGN KEY (homework_id) REFERENCES homeworks(id) ON DELETE CASCADE,
+                    FOREIGN KEY (creator_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建评分表
+                CREATE TABLE IF NOT EXISTS grades (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    submission_id BIGINT NOT NULL,
+                    grader_id BIGINT NOT NULL,
+                    score DOUBLE NOT NULL,
+                    comment TEXT,
+                    graded_at DATETIME(6) NOT NULL,
+                    INDEX idx_grades_submission_id (submission_id, graded_at),
+                    INDEX idx_grades_grader_id (grader_id),
+                    FOREIGN KEY (submission_id) REFERENCES submissions(id) ON DELETE CASCADE,
+                    FOREIGN KEY (grader_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 创建文件表
+                CREATE TABLE IF NOT EXISTS files (
+                    submission_token VARCHAR(255) PRIMARY KEY,
+                    file_name VARCHAR(255) NOT NULL,
+                    file_size BIGINT NOT NULL,
+                    file_type VARCHAR(255) NOT NULL,
+                    uploaded_at DATETIME(6) NOT NULL,
+                    citation_count INT DEFAULT 0,
+                    user_id BIGINT NOT NULL,
+                    INDEX idx_files_citation_count (citation_count),
+                    INDEX idx_files_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 只有在 admin 不存在时插入 (可选)
+                INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at)
+                    SELECT
+                      'admin',
+                      'admin@example.com',
+                      '$argon2id$v=19$m=65536,t=3,p=4$3pcWjxCi/qihfYIXNadQ0g$uITChD8gDEHSt6eREb/enzd7jmjfOF8KCg+zDBQvMUs',
+                      'admin',
+                      'active',
+                      'Administrator',
+                      NULL,
+                      NULL,
+                      '2024-01-01 00:00:00',
+                      '2024-01-01 00:00:00'
+                    FROM DUAL
+                    WHERE NOT EXISTS (
+                      SELECT 1 FROM users WHERE username = 'admin'
+                    );
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 2,
+            name: "create_refresh_tokens".to_string(),
+            up_sql: "
+                -- 创建刷新令牌表，用于登出吊销与轮换重放检测
+                CREATE TABLE IF NOT EXISTS refresh_tokens (
+                    jti VARCHAR(64) PRIMARY KEY,
+                    family_id VARCHAR(64) NOT NULL,
+                    user_id BIGINT NOT NULL,
+                    expires_at DATETIME(6) NOT NULL,
+                    revoked_at DATETIME(6),
+                    created_at DATETIME(6) NOT NULL,
+                    INDEX idx_refresh_tokens_family_id (family_id),
+                    INDEX idx_refresh_tokens_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 3,
+            name: "create_sessions".to_string(),
+            up_sql: "
+                -- 创建登录会话表，会话 ID 与刷新令牌族 ID 相同
+                CREATE TABLE IF NOT EXISTS sessions (
+                    id VARCHAR(64) PRIMARY KEY,
+                    user_id BIGINT NOT NULL,
+                    user_agent VARCHAR(512),
+                    ip_address VARCHAR(64),
+                    remember_me BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at DATETIME(6) NOT NULL,
+                    last_used_at DATETIME(6) NOT NULL,
+                    expires_at DATETIME(6) NOT NULL,
+                    revoked_at DATETIME(6),
+                    INDEX idx_sessions_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 4,
+            name: "add_users_email_verified".to_string(),
+            up_sql: "
+                -- 用户邮箱是否已验证
+                ALTER TABLE users ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 5,
+            name: "create_user_mfa".to_string(),
+            up_sql: "
+                -- 两步验证设置，每个用户一条
+                CREATE TABLE IF NOT EXISTS user_mfa (
+                    user_id BIGINT PRIMARY KEY,
+                    secret VARCHAR(64) NOT NULL,
+                    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+                    last_used_step BIGINT,
+                    created_at DATETIME(6) NOT NULL,
+                    enabled_at DATETIME(6),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+                -- 两步验证恢复码，仅保存哈希
+                CREATE TABLE IF NOT EXISTS mfa_recovery_codes (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    user_id BIGINT NOT NULL,
+                    code_hash VARCHAR(255) NOT NULL,
+                    used_at DATETIME(6),
+                    created_at DATETIME(6) NOT NULL,
+                    INDEX idx_mfa_recovery_codes_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 6,
+            name: "create_api_tokens".to_string(),
+            up_sql: "
+                -- 个人访问令牌，仅保存哈希
+                CREATE TABLE IF NOT EXISTS api_tokens (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    user_id BIGINT NOT NULL,
+                    name VARCHAR(100) NOT NULL,
+                    token_prefix VARCHAR(32) NOT NULL,
+                    token_hash CHAR(64) NOT NULL,
+                    scopes VARCHAR(255) NOT NULL DEFAULT '',
+                    expires_at DATETIME(6),
+                    last_used_at DATETIME(6),
+                    created_at DATETIME(6) NOT NULL,
+                    revoked_at DATETIME(6),
+                    UNIQUE INDEX idx_api_tokens_token_hash (token_hash),
+                    INDEX idx_api_tokens_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 7,
+            name: "create_user_identities".to_string(),
+            up_sql: "
+                -- 关联到本地用户的外部身份 (OpenID Connect)
+                CREATE TABLE IF NOT EXISTS user_identities (
+                    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+                    user_id BIGINT NOT NULL,
+                    provider VARCHAR(64) NOT NULL,
+                    subject VARCHAR(255) NOT NULL,
+                    email VARCHAR(255),
+                    created_at DATETIME(6) NOT NULL,
+                    last_login_at DATETIME(6),
+                    UNIQUE INDEX idx_user_identities_provider_subject (provider, subject),
+                    INDEX idx_user_identities_user_id (user_id),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 8,
+            name: "add_file_content_hash".to_string(),
+            up_sql: "
+                -- 文件内容哈希，内容相同的文件共用同一份存储
+                ALTER TABLE files
+                    ADD COLUMN content_hash CHAR(64) NULL,
+                    ADD INDEX idx_files_content_hash (content_hash),
+                    ADD INDEX idx_files_uploaded_at (uploaded_at);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 9,
+            name: "create_upload_sessions".to_string(),
+            up_sql: "
+                -- 可续传上传会话，已接收的分块保存在文件存储中
+                CREATE TABLE IF NOT EXISTS upload_sessions (
+                    id VARCHAR(64) PRIMARY KEY,
+                    user_id BIGINT NOT NULL,
+                    file_name VARCHAR(255) NOT NULL,
+                    file_type VARCHAR(255) NOT NULL,
+                    file_size BIGINT NOT NULL,
+                    chunk_size BIGINT NOT NULL,
+                    upload_offset BIGINT NOT NULL DEFAULT 0,
+                    created_at DATETIME(6) NOT NULL,
+                    expires_at DATETIME(6) NOT NULL,
+                    INDEX idx_upload_sessions_expires_at (expires_at),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 10,
+            name: "create_content_deletions".to_string(),
+            up_sql: "
+                -- 正在删除的文件内容，回收任务删除内容期间上传相同内容时等待删除完成后再写入
+                CREATE TABLE IF NOT EXISTS content_deletions (
+                    content_hash CHAR(64) PRIMARY KEY,
+                    started_at DATETIME(6) NOT NULL
+                ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 11,
+            name: "add_upload_session_progress".to_string(),
+            up_sql: "
+                -- 已接收内容的哈希中间状态与分块 ID，合并时无需重新读取分块
+                -- TEXT 列不能设置默认值，已有会话的 chunk_ids 为空字符串
+                ALTER TABLE upload_sessions
+                    ADD COLUMN hash_state VARCHAR(255) NOT NULL DEFAULT '',
+                    ADD COLUMN chunk_ids TEXT NOT NULL;
+            "
+            .to_string(),
+        },
+    ]
+}
@@ -0,0 +1,2 @@
+mod migrations;
+mod storage;
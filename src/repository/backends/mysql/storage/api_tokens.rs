@@ -0,0 +1,93 @@
+use super::{MysqlStorage, inserted_id};
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::ApiToken;
+
+pub async fn create_api_token(
+    storage: &MysqlStorage,
+    user_id: i64,
+    name: &str,
+    token_prefix: &str,
+    token_hash: &str,
+    scopes: &str,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<ApiToken> {
+    let result = sqlx::query(
+        "INSERT INTO api_tokens (user_id, name, token_prefix, token_hash, scopes, expires_at, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(name)
+    .bind(token_prefix)
+    .bind(token_hash)
+    .bind(scopes)
+    .bind(expires_at)
+    .bind(chrono::Utc::now())
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create API token: {e}")))?;
+
+    sqlx::query_as::<sqlx::MySql, ApiToken>("SELECT * FROM api_tokens WHERE id = ?")
+        .bind(inserted_id(&result))
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Query API token failed: {e}")))?
+        .ok_or_else(|| {
+            HWSystemError::database_operation("Failed to create API token: inserted row not found")
+        })
+}
+
+pub async fn get_api_token_by_hash(
+    storage: &MysqlStorage,
+    token_hash: &str,
+) -> Result<Option<ApiToken>> {
+    let result =
+        sqlx::query_as::<sqlx::MySql, ApiToken>("SELECT * FROM api_tokens WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query API token failed: {e}"))
+            })?;
+
+    Ok(result)
+}
+
+pub async fn list_user_api_tokens(storage: &MysqlStorage, user_id: i64) -> Result<Vec<ApiToken>> {
+    // 已吊销的令牌不再列出，已过期的令牌保留以便用户确认后删除
+    let tokens = sqlx::query_as::<sqlx::MySql, ApiToken>(
+        "SELECT * FROM api_tokens WHERE user_id = ? AND revoked_at IS NULL ORDER BY id DESC",
+    )
+    .bind(user_id)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query API tokens failed: {e}")))?;
+
+    Ok(tokens)
+}
+
+pub async fn revoke_api_token(storage: &MysqlStorage, user_id: i64, id: i64) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE api_tokens SET revoked_at = ? WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(chrono::Utc::now())
+    .bind(id)
+    .bind(user_id)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to revoke API token: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn touch_api_token(storage: &MysqlStorage, id: i64) -> Result<bool> {
+    let result = sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+        .bind(chrono::Utc::now())
+        .bind(id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to update API token: {e}"))
+        })?;
+
+    Ok(result.rows_affected() > 0)
+}
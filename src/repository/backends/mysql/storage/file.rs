@@ -0,0 +1,187 @@
+use super::MysqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::files::entities::File;
+
+pub async fn upload_file(
+    storage: &MysqlStorage,
+    submission_token: &str,
+    file_name: &str,
+    file_size: &i64,
+    file_type: &str,
+    content_hash: &str,
+    user_id: i64,
+) -> Result<File> {
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        "INSERT INTO files (submission_token, file_name, file_size, file_type, content_hash, uploaded_at, user_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(submission_token)
+    .bind(file_name)
+    .bind(file_size)
+    .bind(file_type)
+    .bind(content_hash)
+    .bind(now)
+    .bind(user_id)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("上传文件失败: {e}")))?;
+
+    get_file_by_token(storage, submission_token)
+        .await?
+        .ok_or_else(|| HWSystemError::database_operation("上传文件失败: 未找到写入的文件记录"))
+}
+
+pub async fn get_file_by_token(storage: &MysqlStorage, file_id: &str) -> Result<Option<File>> {
+    let result = sqlx::query_as::<_, File>("SELECT * FROM files WHERE submission_token = ?")
+        .bind(file_id)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询文件失败: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn set_file_citation_count(
+    storage: &MysqlStorage,
+    submission_token: &str,
+    count: i32,
+) -> Result<bool> {
+    let result = sqlx::query("UPDATE files SET citation_count = ? WHERE submission_token = ?")
+        .bind(count)
+        .bind(submission_token)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("更新文件引用次数失败: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_unreferenced_files(
+    storage: &MysqlStorage,
+    uploaded_before: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<File>> {
+    let result = sqlx::query_as::<_, File>(
+        "SELECT * FROM files WHERE citation_count = 0 AND uploaded_at < ? ORDER BY uploaded_at LIMIT ?",
+    )
+    .bind(uploaded_before)
+    .bind(limit)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询文件失败: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn delete_unreferenced_file(
+    storage: &MysqlStorage,
+    submission_token: &str,
+) -> Result<bool> {
+    // 只删除仍未被引用的记录，避免与附件引用并发时误删
+    let result = sqlx::query("DELETE FROM files WHERE submission_token = ? AND citation_count = 0")
+        .bind(submission_token)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("删除文件失败: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_file(storage: &MysqlStorage, submission_token: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM files WHERE submission_token = ?")
+        .bind(submission_token)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("删除文件失败: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn count_files_by_content_hash(
+    storage: &MysqlStorage,
+    content_hash: &str,
+) -> Result<i64> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM files WHERE content_hash = ?")
+        .bind(content_hash)
+        .fetch_one(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询文件失败: {e}")))?;
+
+    Ok(count)
+}
+
+pub async fn list_citation_mismatches(storage: &MysqlStorage) -> Result<Vec<String>> {
+    // 同一作业或提交中重复引用同一文件时按一次计
+    let tokens: Vec<String> = sqlx::query_scalar(
+        "SELECT f.submission_token FROM files f
+        LEFT JOIN (
+            SELECT token, COUNT(*) AS cited FROM (
+            SELECT DISTINCT h.id, j.token FROM homeworks h,
+                JSON_TABLE(h.attachments, '$[*]' COLUMNS (token VARCHAR(255) PATH '$')) j
+            UNION ALL
+            SELECT DISTINCT s.id, j.token FROM submissions s,
+                JSON_TABLE(s.attachments, '$[*]' COLUMNS (token VARCHAR(255) PATH '$')) j
+            ) a GROUP BY token
+        ) r ON r.token = f.submission_token COLLATE utf8mb4_bin
+        WHERE COALESCE(f.citation_count, 0) <> COALESCE(r.cited, 0)",
+    )
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询文件引用次数失败: {e}")))?;
+
+    Ok(tokens)
+}
+
+pub async fn begin_content_deletion(
+    storage: &MysqlStorage,
+    content_hash: &str,
+    stale_before: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    // 回收任务中途退出时遗留的标记过期后可以被接管
+    sqlx::query("DELETE FROM content_deletions WHERE content_hash = ? AND started_at < ?")
+        .bind(content_hash)
+        .bind(stale_before)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("删除内容删除标记失败: {e}")))?;
+
+    let result = sqlx::query(
+        "INSERT IGNORE INTO content_deletions (content_hash, started_at) VALUES (?, ?)",
+    )
+    .bind(content_hash)
+    .bind(chrono::Utc::now())
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("写入内容删除标记失败: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn finish_content_deletion(storage: &MysqlStorage, content_hash: &str) -> Result<()> {
+    sqlx::query("DELETE FROM content_deletions WHERE content_hash = ?")
+        .bind(content_hash)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("删除内容删除标记失败: {e}")))?;
+
+    Ok(())
+}
+
+pub async fn is_content_being_deleted(
+    storage: &MysqlStorage,
+    content_hash: &str,
+    stale_before: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM content_deletions WHERE content_hash = ? AND started_at >= ?",
+    )
+    .bind(content_hash)
+    .bind(stale_before)
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询内容删除标记失败: {e}")))?;
+
+    Ok(count > 0)
+}
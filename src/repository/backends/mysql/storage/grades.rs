@@ -0,0 +1,65 @@
+use super::{MysqlStorage, inserted_id};
+use crate::errors::{HWSystemError, Result};
+use crate::models::grades::{
+    entities::Grade, requests::CreateGradeRequest, responses::GradeListResponse,
+};
+
+pub async fn create_grade(
+    storage: &MysqlStorage,
+    submission_id: i64,
+    grader_id: i64,
+    grade: CreateGradeRequest,
+) -> Result<Grade> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "INSERT INTO grades (submission_id, grader_id, score, comment, graded_at)
+            VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(submission_id)
+    .bind(grader_id)
+    .bind(grade.score)
+    .bind(&grade.comment)
+    .bind(now)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create grade: {e}")))?;
+
+    let grade = sqlx::query_as::<sqlx::MySql, Grade>("SELECT * FROM grades WHERE id = ?")
+        .bind(inserted_id(&result))
+        .fetch_one(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Failed to create grade: {e}")))?;
+
+    Ok(grade)
+}
+
+pub async fn get_latest_grade_by_submission_id(
+    storage: &MysqlStorage,
+    submission_id: i64,
+) -> Result<Option<Grade>> {
+    let result = sqlx::query_as::<sqlx::MySql, Grade>(
+        "SELECT * FROM grades WHERE submission_id = ? ORDER BY graded_at DESC, id DESC LIMIT 1",
+    )
+    .bind(submission_id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query grade failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_grades_by_submission_id(
+    storage: &MysqlStorage,
+    submission_id: i64,
+) -> Result<GradeListResponse> {
+    let grades = sqlx::query_as::<sqlx::MySql, Grade>(
+        "SELECT * FROM grades WHERE submission_id = ? ORDER BY graded_at DESC, id DESC",
+    )
+    .bind(submission_id)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询评分历史失败: {e}")))?;
+
+    Ok(GradeListResponse { items: grades })
+}
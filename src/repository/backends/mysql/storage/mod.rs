@@ -0,0 +1,388 @@
+pub mod api_tokens;
+pub mod class_users;
+pub mod classes;
+pub mod file;
+pub mod grades;
+pub mod homeworks;
+pub mod mfa;
+pub mod refresh_tokens;
+pub mod sessions;
+pub mod storage_impl;
+pub mod submissions;
+pub mod upload_sessions;
+pub mod user;
+pub mod user_identities;
+
+use super::migrations::MysqlMigrationManager;
+use crate::errors::{HWSystemError, Result};
+use crate::system::app_config::AppConfig;
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions, MySqlQueryResult};
+use tracing::{error, warn};
+
+/// 取得插入语句生成的自增 ID
+///
+/// MySQL 不支持 RETURNING，插入后需要再查询一次才能拿到完整的行：
+/// 自增主键的表用此函数取得新行 ID 后按 ID 回查，其余表按插入时写入的主键或唯一键回查。
+fn inserted_id(result: &MySqlQueryResult) -> i64 {
+    result.last_insert_id() as i64
+}
+
+#[derive(Clone)]
+pub struct MysqlStorage {
+    pool: MySqlPool,
+}
+
+impl MysqlStorage {
+    pub async fn new_async() -> Result<Self> {
+        let config = AppConfig::get();
+        Self::connect(
+            &config.database.url,
+            config.database.pool_size,
+            config.database.timeout,
+        )
+        .await
+    }
+
+    async fn connect(url: &str, pool_size: u32, timeout: u64) -> Result<Self> {
+        // 创建连接池
+        let pool = MySqlPoolOptions::new()
+            .max_connections(pool_size)
+            .acquire_timeout(std::time::Duration::from_secs(timeout))
+            .connect(url)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_config(format!("Could not connect to database: {e}"))
+            })?;
+
+        // 测试连接
+        if let Err(e) = sqlx::query("SELECT 1").execute(&pool).await {
+            error!("Database connection test failed: {}", e);
+            return Err(HWSystemError::database_connection(format!(
+                "Database connection test failed: {e}"
+            )));
+        }
+
+        let storage = MysqlStorage { pool };
+
+        // 初始化迁移系统并运行迁移
+        let migration_manager = MysqlMigrationManager::new(storage.pool.clone());
+        migration_manager.init().await?;
+        migration_manager.migrate_up().await?;
+
+        warn!("MySQL database connection successful");
+
+        Ok(storage)
+    }
+}
+
+// 注册 MySQL 存储插件
+declare_storage_plugin!("mysql", MysqlStorage);
+
+#[cfg(test)]
+mod tests {
+    //! 需要可用的 MySQL/MariaDB 实例，通过 `HWSYSTEM_TEST_MYSQL_URL` 指定连接地址，
+    //! 未设置时跳过。各测试使用随机用户名与 ID，可在同一数据库上重复运行。
+
+    use super::super::migrations::get_all_migrations;
+    use super::*;
+    use crate::models::class_users::entities::ClassUserRole;
+    use crate::models::classes::requests::CreateClassRequest;
+    use crate::models::files::requests::CreateUploadSessionRequest;
+    use crate::models::grades::requests::CreateGradeRequest;
+    use crate::models::homeworks::requests::CreateHomeworkRequest;
+    use crate::models::submissions::requests::CreateSubmissionRequest;
+    use crate::models::users::entities::{User, UserProfile, UserRole};
+    use crate::models::users::requests::{CreateUserRequest, UpdateUserRequest};
+    use crate::repository::Storage;
+
+    async fn test_storage() -> Option<MysqlStorage> {
+        let url = std::env::var("HWSYSTEM_TEST_MYSQL_URL").ok()?;
+        Some(
+            MysqlStorage::connect(&url, 5, 10)
+                .await
+                .expect("Failed to connect to test MySQL database"),
+        )
+    }
+
+    async fn create_user(storage: &MysqlStorage, role: UserRole) -> User {
+        let name = format!("u{}", &uuid::Uuid::new_v4().simple().to_string()[..12]);
+        storage
+            .create_user(CreateUserRequest {
+                username: name.clone(),
+                email: format!("{name}@example.com"),
+                password: "hash".to_string(),
+                role,
+                status: None,
+                profile: UserProfile {
+                    profile_name: name,
+                    avatar_url: None,
+                },
+            })
+            .await
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn migrations_reach_latest_version_and_rerun_cleanly() {
+        let Some(storage) = test_storage().await else {
+            return;
+        };
+        let manager = MysqlMigrationManager::new(storage.pool.clone());
+        // 已是最新版本时再次运行不做任何修改
+        manager.init().await.unwrap();
+        manager.migrate_up().await.unwrap();
+
+        let latest = get_all_migrations().last().unwrap().version;
+        assert_eq!(manager.get_current_version().await.unwrap(), latest);
+    }
+
+    #[actix_web::test]
+    async fn users_round_trip() {
+        let Some(storage) = test_storage().await else {
+            return;
+        };
+        let user = create_user(&storage, UserRole::User).await;
+
+        let found = storage.get_user_by_id(user.id).await.unwrap().unwrap();
+        assert_eq!(found.username, user.username);
+        let found = storage
+            .get_user_by_username_or_email(&user.email)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, user.id);
+
+        let updated = storage
+            .update_user(
+                user.id,
+                UpdateUserRequest {
+                    email: None,
+                    password: None,
+                    role: Some(UserRole::Teacher),
+                    status: None,
+                    profile: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.role, UserRole::Teacher);
+
+        assert!(storage.delete_user(user.id).await.unwrap());
+        assert!(storage.get_user_by_id(user.id).await.unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn homework_submission_and_grades_round_trip() {
+        let Some(storage) = test_storage().await else {
+            return;
+        };
+        let teacher = create_user(&storage, UserRole::Teacher).await;
+        let student = create_user(&storage, UserRole::User).await;
+
+        let class = storage
+            .create_class(CreateClassRequest {
+                teacher_id: teacher.id,
+                class_name: "MySQL".to_string(),
+                description: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .get_class_by_id(class.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .teacher_id,
+            teacher.id
+        );
+        let member = storage
+            .join_class(student.id, class.id, ClassUserRole::Student)
+            .await
+            .unwrap();
+        assert_eq!(member.class_id, class.id);
+
+        let homework = storage
+            .create_homework(
+                class.id,
+                teacher.id,
+                CreateHomeworkRequest {
+                    title: "hw".to_string(),
+                    content: None,
+                    attachments: vec!["a".to_string()],
+                    max_score: 100.0,
+                    deadline: None,
+                    allow_late_submission: false,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(homework.attachments, vec!["a".to_string()]);
+
+        // 重复提交覆盖同一行
+        let first = storage
+            .create_submission(
+                homework.id,
+                student.id,
+                CreateSubmissionRequest {
+                    content: "v1".to_string(),
+                    attachments: vec![],
+                },
+                false,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let second = storage
+            .create_submission(
+                homework.id,
+                student.id,
+                CreateSubmissionRequest {
+                    content: "v2".to_string(),
+                    attachments: vec![],
+                },
+                true,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.content, "v2");
+        assert!(second.is_late);
+
+        for score in [60.0, 80.0] {
+            storage
+                .create_grade(
+                    second.id,
+                    teacher.id,
+                    CreateGradeRequest {
+                        score,
+                        comment: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+        let latest = storage
+            .get_latest_grade_by_submission_id(second.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.score, 80.0);
+        let history = storage
+            .list_grades_by_submission_id(second.id)
+            .await
+            .unwrap();
+        assert_eq!(history.items.len(), 2);
+
+        assert!(storage.delete_class(class.id).await.unwrap());
+    }
+
+    #[actix_web::test]
+    async fn sessions_and_tokens_round_trip() {
+        let Some(storage) = test_storage().await else {
+            return;
+        };
+        let user = create_user(&storage, UserRole::User).await;
+        let expires_at = chrono::Utc::now() + chrono::Duration::days(1);
+        let family = uuid::Uuid::new_v4().to_string();
+
+        let session = storage
+            .create_session(&family, user.id, Some("agent"), None, true, expires_at)
+            .await
+            .unwrap();
+        assert!(session.remember_me);
+        let jti = uuid::Uuid::new_v4().to_string();
+        storage
+            .create_refresh_token(&jti, &family, user.id, expires_at)
+            .await
+            .unwrap();
+        assert_eq!(storage.list_user_sessions(user.id).await.unwrap().len(), 1);
+
+        // 终止会话同时吊销其刷新令牌
+        assert!(storage.revoke_session(&family).await.unwrap());
+        assert!(!storage.revoke_session(&family).await.unwrap());
+        let token = storage.get_refresh_token(&jti).await.unwrap().unwrap();
+        assert!(token.revoked_at.is_some());
+
+        let hash = uuid::Uuid::new_v4().simple().to_string();
+        let api_token = storage
+            .create_api_token(user.id, "ci", "hwpat_ab", &hash, "read", None)
+            .await
+            .unwrap();
+        let found = storage.get_api_token_by_hash(&hash).await.unwrap().unwrap();
+        assert_eq!(found.id, api_token.id);
+        assert!(
+            storage
+                .revoke_api_token(user.id, api_token.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            storage
+                .list_user_api_tokens(user.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[actix_web::test]
+    async fn files_and_upload_sessions_round_trip() {
+        let Some(storage) = test_storage().await else {
+            return;
+        };
+        let user = create_user(&storage, UserRole::User).await;
+
+        let token = uuid::Uuid::new_v4().to_string();
+        let file = storage
+            .upload_file(&token, "a.txt", &3, "text/plain", "abc", user.id)
+            .await
+            .unwrap();
+        assert_eq!(file.content_hash.as_deref(), Some("abc"));
+        assert!(storage.get_file_by_token(&token).await.unwrap().is_some());
+        assert!(storage.delete_file(&token).await.unwrap());
+
+        let upload = CreateUploadSessionRequest {
+            file_name: "a.txt".to_string(),
+            file_type: "text/plain".to_string(),
+            size: 10,
+        };
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        let id = uuid::Uuid::new_v4().to_string();
+        let session = storage
+            .create_upload_session(&id, user.id, &upload, 5, 1, expires_at)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.upload_offset, 0);
+        // 达到上限后不再创建
+        let other = uuid::Uuid::new_v4().to_string();
+        assert!(
+            storage
+                .create_upload_session(&other, user.id, &upload, 5, 1, expires_at)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // 只有已接收字节数匹配时才推进
+        assert!(
+            storage
+                .advance_upload_session(&id, 0, 5, "c1", "state", expires_at)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !storage
+                .advance_upload_session(&id, 0, 5, "c2", "state", expires_at)
+                .await
+                .unwrap()
+        );
+        let session = storage.get_upload_session(&id).await.unwrap().unwrap();
+        assert_eq!(session.upload_offset, 5);
+        assert_eq!(session.chunk_ids, "c1");
+        assert!(storage.delete_upload_session(&id).await.unwrap());
+    }
+}
@@ -0,0 +1,145 @@
+use super::MysqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::Session;
+
+pub async fn create_session(
+    storage: &MysqlStorage,
+    id: &str,
+    user_id: i64,
+    user_agent: Option<&str>,
+    ip_address: Option<&str>,
+    remember_me: bool,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<Session> {
+    let now = chrono::Utc::now();
+
+    sqlx::query(
+        "INSERT INTO sessions (id, user_id, user_agent, ip_address, remember_me, created_at, last_used_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(user_agent)
+    .bind(ip_address)
+    .bind(remember_me)
+    .bind(now)
+    .bind(now)
+    .bind(expires_at)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create session: {e}")))?;
+
+    get_session_by_id(storage, id).await?.ok_or_else(|| {
+        HWSystemError::database_operation("Failed to create session: inserted row not found")
+    })
+}
+
+pub async fn get_session_by_id(storage: &MysqlStorage, id: &str) -> Result<Option<Session>> {
+    let result = sqlx::query_as::<sqlx::MySql, Session>("SELECT * FROM sessions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Query session failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_user_sessions(storage: &MysqlStorage, user_id: i64) -> Result<Vec<Session>> {
+    // 仅列出未终止且未过期的会话
+    let sessions = sqlx::query_as::<sqlx::MySql, Session>(
+        "SELECT * FROM sessions WHERE user_id = ? AND revoked_at IS NULL AND expires_at > ?
+            ORDER BY last_used_at DESC",
+    )
+    .bind(user_id)
+    .bind(chrono::Utc::now())
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询会话列表失败: {e}")))?;
+
+    Ok(sessions)
+}
+
+pub async fn touch_session(storage: &MysqlStorage, id: &str) -> Result<bool> {
+    let result =
+        sqlx::query("UPDATE sessions SET last_used_at = ? WHERE id = ? AND revoked_at IS NULL")
+            .bind(chrono::Utc::now())
+            .bind(id)
+            .execute(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to update session: {e}"))
+            })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn revoke_session(storage: &MysqlStorage, id: &str) -> Result<bool> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    // 终止会话的同时吊销会话下的全部刷新令牌
+    let result =
+        sqlx::query("UPDATE sessions SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL")
+            .bind(now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to revoke session: {e}"))
+            })?;
+
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = ? WHERE family_id = ? AND revoked_at IS NULL",
+    )
+    .bind(now)
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to revoke refresh tokens: {e}"))
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn revoke_user_sessions(storage: &MysqlStorage, user_id: i64) -> Result<u64> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    let result =
+        sqlx::query("UPDATE sessions SET revoked_at = ? WHERE user_id = ? AND revoked_at IS NULL")
+            .bind(now)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to revoke user sessions: {e}"))
+            })?;
+
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = ? WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(now)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to revoke refresh tokens: {e}"))
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(result.rows_affected())
+}
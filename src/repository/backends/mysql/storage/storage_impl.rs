@@ -0,0 +1,560 @@
+use super::MysqlStorage;
+use crate::{
+    models::{
+        auth::entities::{ApiToken, MfaRecoveryCode, RefreshToken, Session, UserIdentity, UserMfa},
+        class_users::{
+            entities::{ClassUser, ClassUserRole},
+            requests::{ClassUserQuery, UpdateClassUserRequest},
+            responses::ClassUserListResponse,
+        },
+        classes::{
+            entities::Class,
+            requests::{ClassListQuery, CreateClassRequest, UpdateClassRequest},
+            responses::ClassListResponse,
+        },
+        files::{
+            entities::{File, UploadSession},
+            requests::CreateUploadSessionRequest,
+        },
+        grades::{entities::Grade, requests::CreateGradeRequest, responses::GradeListResponse},
+        homeworks::{
+            entities::Homework,
+            requests::{CreateHomeworkRequest, HomeworkListQuery, UpdateHomeworkRequest},
+            responses::HomeworkListResponse,
+        },
+        submissions::{
+            entities::Submission,
+            requests::{CreateSubmissionRequest, SubmissionListQuery},
+            responses::SubmissionListResponse,
+        },
+        users::{
+            entities::User,
+            requests::{CreateUserRequest, UpdateUserRequest, UserListQuery},
+            responses::UserListResponse,
+        },
+    },
+    repository::backends::mysql::storage::class_users,
+};
+
+use super::{
+    api_tokens, classes, file, grades, homeworks, mfa, refresh_tokens, sessions, submissions,
+    upload_sessions, user, user_identities,
+};
+use crate::errors::Result;
+use crate::repository::Storage;
+use async_trait::async_trait;
+
+#[async_trait]
+impl Storage for MysqlStorage {
+    /// 用户模块
+    async fn create_user(&self, user: CreateUserRequest) -> Result<User> {
+        user::create_user(self, user).await
+    }
+
+    async fn create_users(&self, users: Vec<CreateUserRequest>) -> Result<Vec<User>> {
+        user::create_users(self, users).await
+    }
+
+    async fn get_user_by_id(&self, id: i64) -> Result<Option<User>> {
+        user::get_user_by_id(self, id).await
+    }
+
+    async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
+        user::get_user_by_username(self, username).await
+    }
+
+    async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        user::get_user_by_email(self, email).await
+    }
+
+    async fn get_user_by_username_or_email(&self, identifier: &str) -> Result<Option<User>> {
+        user::get_user_by_username_or_email(self, identifier).await
+    }
+
+    async fn list_users_with_pagination(&self, query: UserListQuery) -> Result<UserListResponse> {
+        user::list_users_with_pagination(self, query).await
+    }
+
+    async fn update_last_login(&self, id: i64) -> Result<bool> {
+        user::update_last_login(self, id).await
+    }
+
+    async fn mark_email_verified(&self, id: i64, email: &str) -> Result<bool> {
+        user::mark_email_verified(self, id, email).await
+    }
+
+    async fn update_user(&self, id: i64, update: UpdateUserRequest) -> Result<Option<User>> {
+        user::update_user(self, id, update).await
+    }
+
+    async fn delete_user(&self, id: i64) -> Result<bool> {
+        user::delete_user(self, id).await
+    }
+
+    /// 刷新令牌模块
+    async fn create_refresh_token(
+        &self,
+        jti: &str,
+        family_id: &str,
+        user_id: i64,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<RefreshToken> {
+        refresh_tokens::create_refresh_token(self, jti, family_id, user_id, expires_at).await
+    }
+
+    async fn get_refresh_token(&self, jti: &str) -> Result<Option<RefreshToken>> {
+        refresh_tokens::get_refresh_token(self, jti).await
+    }
+
+    async fn revoke_refresh_token(&self, jti: &str) -> Result<bool> {
+        refresh_tokens::revoke_refresh_token(self, jti).await
+    }
+
+    /// 登录会话模块
+    async fn create_session(
+        &self,
+        id: &str,
+        user_id: i64,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        remember_me: bool,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Session> {
+        sessions::create_session(
+            self,
+            id,
+            user_id,
+            user_agent,
+            ip_address,
+            remember_me,
+            expires_at,
+        )
+        .await
+    }
+
+    async fn get_session_by_id(&self, id: &str) -> Result<Option<Session>> {
+        sessions::get_session_by_id(self, id).await
+    }
+
+    async fn list_user_sessions(&self, user_id: i64) -> Result<Vec<Session>> {
+        sessions::list_user_sessions(self, user_id).await
+    }
+
+    async fn touch_session(&self, id: &str) -> Result<bool> {
+        sessions::touch_session(self, id).await
+    }
+
+    async fn revoke_session(&self, id: &str) -> Result<bool> {
+        sessions::revoke_session(self, id).await
+    }
+
+    async fn revoke_user_sessions(&self, user_id: i64) -> Result<u64> {
+        sessions::revoke_user_sessions(self, user_id).await
+    }
+
+    /// 两步验证模块
+    async fn get_user_mfa(&self, user_id: i64) -> Result<Option<UserMfa>> {
+        mfa::get_user_mfa(self, user_id).await
+    }
+
+    async fn save_user_mfa_secret(&self, user_id: i64, secret: &str) -> Result<UserMfa> {
+        mfa::save_user_mfa_secret(self, user_id, secret).await
+    }
+
+    async fn enable_user_mfa(&self, user_id: i64, recovery_code_hashes: &[String]) -> Result<bool> {
+        mfa::enable_user_mfa(self, user_id, recovery_code_hashes).await
+    }
+
+    async fn delete_user_mfa(&self, user_id: i64) -> Result<bool> {
+        mfa::delete_user_mfa(self, user_id).await
+    }
+
+    async fn update_mfa_last_used_step(&self, user_id: i64, step: i64) -> Result<bool> {
+        mfa::update_mfa_last_used_step(self, user_id, step).await
+    }
+
+    async fn list_unused_recovery_codes(&self, user_id: i64) -> Result<Vec<MfaRecoveryCode>> {
+        mfa::list_unused_recovery_codes(self, user_id).await
+    }
+
+    async fn use_recovery_code(&self, id: i64) -> Result<bool> {
+        mfa::use_recovery_code(self, id).await
+    }
+
+    async fn replace_recovery_codes(&self, user_id: i64, code_hashes: &[String]) -> Result<()> {
+        mfa::replace_recovery_codes(self, user_id, code_hashes).await
+    }
+
+    /// 个人访问令牌模块
+    async fn create_api_token(
+        &self,
+        user_id: i64,
+        name: &str,
+        token_prefix: &str,
+        token_hash: &str,
+        scopes: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<ApiToken> {
+        api_tokens::create_api_token(
+            self,
+            user_id,
+            name,
+            token_prefix,
+            token_hash,
+            scopes,
+            expires_at,
+        )
+        .await
+    }
+
+    async fn get_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>> {
+        api_tokens::get_api_token_by_hash(self, token_hash).await
+    }
+
+    async fn list_user_api_tokens(&self, user_id: i64) -> Result<Vec<ApiToken>> {
+        api_tokens::list_user_api_tokens(self, user_id).await
+    }
+
+    async fn revoke_api_token(&self, user_id: i64, id: i64) -> Result<bool> {
+        api_tokens::revoke_api_token(self, user_id, id).await
+    }
+
+    async fn touch_api_token(&self, id: i64) -> Result<bool> {
+        api_tokens::touch_api_token(self, id).await
+    }
+
+    async fn get_user_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>> {
+        user_identities::get_user_identity(self, provider, subject).await
+    }
+
+    async fn create_user_identity(
+        &self,
+        user_id: i64,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<UserIdentity> {
+        user_identities::create_user_identity(self, user_id, provider, subject, email).await
+    }
+
+    async fn touch_user_identity(&self, id: i64, email: Option<&str>) -> Result<bool> {
+        user_identities::touch_user_identity(self, id, email).await
+    }
+
+    async fn list_user_identities(&self, provider: &str) -> Result<Vec<UserIdentity>> {
+        user_identities::list_user_identities(self, provider).await
+    }
+
+    /// 作业模块
+    async fn create_homework(
+        &self,
+        class_id: i64,
+        created_by: i64,
+        homework: CreateHomeworkRequest,
+    ) -> Result<Homework> {
+        homeworks::create_homework(self, class_id, created_by, homework).await
+    }
+
+    async fn get_homework_by_id(&self, homework_id: i64) -> Result<Option<Homework>> {
+        homeworks::get_homework_by_id(self, homework_id).await
+    }
+
+    async fn list_homeworks_with_pagination(
+        &self,
+        query: HomeworkListQuery,
+    ) -> Result<HomeworkListResponse> {
+        homeworks::list_homeworks_with_pagination(self, query).await
+    }
+
+    async fn update_homework(
+        &self,
+        homework_id: i64,
+        update: UpdateHomeworkRequest,
+    ) -> Result<Option<Homework>> {
+        homeworks::update_homework(self, homework_id, update).await
+    }
+
+    async fn delete_homework(&self, homework_id: i64) -> Result<bool> {
+        homeworks::delete_homework(self, homework_id).await
+    }
+
+    async fn list_homeworks_by_attachment(&self, submission_token: &str) -> Result<Vec<Homework>> {
+        homeworks::list_homeworks_by_attachment(self, submission_token).await
+    }
+
+    /// 作业提交模块
+    async fn create_submission(
+        &self,
+        homework_id: i64,
+        creator_id: i64,
+        submission: CreateSubmissionRequest,
+        is_late: bool,
+    ) -> Result<Option<Submission>> {
+        submissions::create_submission(self, homework_id, creator_id, submission, is_late).await
+    }
+
+    async fn get_submission_by_id(&self, submission_id: i64) -> Result<Option<Submission>> {
+        submissions::get_submission_by_id(self, submission_id).await
+    }
+
+    async fn get_submission_by_homework_and_creator(
+        &self,
+        homework_id: i64,
+        creator_id: i64,
+    ) -> Result<Option<Submission>> {
+        submissions::get_submission_by_homework_and_creator(self, homework_id, creator_id).await
+    }
+
+    async fn list_submissions_with_pagination(
+        &self,
+        query: SubmissionListQuery,
+    ) -> Result<SubmissionListResponse> {
+        submissions::list_submissions_with_pagination(self, query).await
+    }
+
+    async fn list_submissions_by_attachment(
+        &self,
+        submission_token: &str,
+    ) -> Result<Vec<Submission>> {
+        submissions::list_submissions_by_attachment(self, submission_token).await
+    }
+
+    /// 作业评分模块
+    async fn create_grade(
+        &self,
+        submission_id: i64,
+        grader_id: i64,
+        grade: CreateGradeRequest,
+    ) -> Result<Grade> {
+        grades::create_grade(self, submission_id, grader_id, grade).await
+    }
+
+    async fn get_latest_grade_by_submission_id(&self, submission_id: i64) -> Result<Option<Grade>> {
+        grades::get_latest_grade_by_submission_id(self, submission_id).await
+    }
+
+    async fn list_grades_by_submission_id(&self, submission_id: i64) -> Result<GradeListResponse> {
+        grades::list_grades_by_submission_id(self, submission_id).await
+    }
+
+    /// 班级模块
+    async fn create_class(&self, class: CreateClassRequest) -> Result<Class> {
+        classes::create_class(self, class).await
+    }
+
+    async fn get_class_by_id(&self, class_id: i64) -> Result<Option<Class>> {
+        classes::get_class_by_id(self, class_id).await
+    }
+
+    async fn get_class_by_code(&self, invite_code: &str) -> Result<Option<Class>> {
+        classes::get_class_by_code(self, invite_code).await
+    }
+
+    async fn list_classes_with_pagination(
+        &self,
+        query: ClassListQuery,
+    ) -> Result<ClassListResponse> {
+        classes::list_classes_with_pagination(self, query).await
+    }
+
+    async fn update_class(
+        &self,
+        class_id: i64,
+        update: UpdateClassRequest,
+    ) -> Result<Option<Class>> {
+        classes::update_class(self, class_id, update).await
+    }
+
+    async fn delete_class(&self, class_id: i64) -> Result<bool> {
+        classes::delete_class(self, class_id).await
+    }
+
+    /// 班级学生管理方法
+    async fn join_class(
+        &self,
+        user_id: i64,
+        class_id: i64,
+        role: ClassUserRole,
+    ) -> Result<ClassUser> {
+        class_users::join_class(self, user_id, class_id, role).await
+    }
+
+    async fn leave_class(&self, user_id: i64, class_id: i64) -> Result<bool> {
+        class_users::leave_class(self, user_id, class_id).await
+    }
+
+    async fn update_class_user(
+        &self,
+        class_id: i64,
+        class_user_id: i64,
+        update_data: UpdateClassUserRequest,
+    ) -> Result<Option<ClassUser>> {
+        class_users::update_class_user(self, class_id, class_user_id, update_data).await
+    }
+
+    async fn list_class_users_with_pagination(
+        &self,
+        class_id: i64,
+        query: ClassUserQuery,
+    ) -> Result<ClassUserListResponse> {
+        class_users::list_class_users_with_pagination(self, class_id, query).await
+    }
+
+    async fn list_user_classes_with_pagination(
+        &self,
+        user_id: i64,
+        query: ClassListQuery,
+    ) -> Result<ClassListResponse> {
+        class_users::list_user_classes_with_pagination(self, user_id, query).await
+    }
+
+    async fn get_class_user_by_user_id_and_class_id(
+        &self,
+        user_id: i64,
+        class_id: i64,
+    ) -> Result<Option<ClassUser>> {
+        class_users::get_class_user_by_user_id_and_class_id(self, user_id, class_id).await
+    }
+
+    async fn get_class_and_class_user_by_class_id_and_code(
+        &self,
+        class_id: i64,
+        invite_code: &str,
+        user_id: i64,
+    ) -> Result<(Option<Class>, Option<ClassUser>)> {
+        class_users::get_class_and_class_user_by_class_id_and_code(
+            self,
+            class_id,
+            invite_code,
+            user_id,
+        )
+        .await
+    }
+
+    /// 文件模块
+    async fn upload_file(
+        &self,
+        submission_token: &str,
+        file_name: &str,
+        file_size: &i64,
+        file_type: &str,
+        content_hash: &str,
+        user_id: i64,
+    ) -> Result<File> {
+        // 文件上传逻辑
+        file::upload_file(
+            self,
+            submission_token,
+            file_name,
+            file_size,
+            file_type,
+            content_hash,
+            user_id,
+        )
+        .await
+    }
+
+    async fn get_file_by_token(&self, file_id: &str) -> Result<Option<File>> {
+        // 获取文件逻辑
+        file::get_file_by_token(self, file_id).await
+    }
+
+    async fn set_file_citation_count(&self, submission_token: &str, count: i32) -> Result<bool> {
+        file::set_file_citation_count(self, submission_token, count).await
+    }
+
+    async fn list_unreferenced_files(
+        &self,
+        uploaded_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<File>> {
+        file::list_unreferenced_files(self, uploaded_before, limit).await
+    }
+
+    async fn delete_unreferenced_file(&self, submission_token: &str) -> Result<bool> {
+        file::delete_unreferenced_file(self, submission_token).await
+    }
+
+    async fn delete_file(&self, submission_token: &str) -> Result<bool> {
+        file::delete_file(self, submission_token).await
+    }
+
+    async fn count_files_by_content_hash(&self, content_hash: &str) -> Result<i64> {
+        file::count_files_by_content_hash(self, content_hash).await
+    }
+
+    async fn list_citation_mismatches(&self) -> Result<Vec<String>> {
+        file::list_citation_mismatches(self).await
+    }
+
+    async fn begin_content_deletion(
+        &self,
+        content_hash: &str,
+        stale_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool> {
+        file::begin_content_deletion(self, content_hash, stale_before).await
+    }
+
+    async fn finish_content_deletion(&self, content_hash: &str) -> Result<()> {
+        file::finish_content_deletion(self, content_hash).await
+    }
+
+    async fn is_content_being_deleted(
+        &self,
+        content_hash: &str,
+        stale_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool> {
+        file::is_content_being_deleted(self, content_hash, stale_before).await
+    }
+
+    /// 可续传上传会话模块
+    async fn create_upload_session(
+        &self,
+        id: &str,
+        user_id: i64,
+        upload: &CreateUploadSessionRequest,
+        chunk_size: i64,
+        max_open: i64,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<UploadSession>> {
+        upload_sessions::create_upload_session(
+            self, id, user_id, upload, chunk_size, max_open, expires_at,
+        )
+        .await
+    }
+
+    async fn get_upload_session(&self, id: &str) -> Result<Option<UploadSession>> {
+        upload_sessions::get_upload_session(self, id).await
+    }
+
+    async fn advance_upload_session(
+        &self,
+        id: &str,
+        offset: i64,
+        new_offset: i64,
+        chunk_ids: &str,
+        hash_state: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool> {
+        upload_sessions::advance_upload_session(
+            self, id, offset, new_offset, chunk_ids, hash_state, expires_at,
+        )
+        .await
+    }
+
+    async fn delete_upload_session(&self, id: &str) -> Result<bool> {
+        upload_sessions::delete_upload_session(self, id).await
+    }
+
+    async fn list_expired_upload_sessions(
+        &self,
+        expired_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<UploadSession>> {
+        upload_sessions::list_expired_upload_sessions(self, expired_before, limit).await
+    }
+}
@@ -0,0 +1,143 @@
+use super::MysqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::files::{entities::UploadSession, requests::CreateUploadSessionRequest};
+
+pub async fn create_upload_session(
+    storage: &MysqlStorage,
+    id: &str,
+    user_id: i64,
+    upload: &CreateUploadSessionRequest,
+    chunk_size: i64,
+    max_open: i64,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<UploadSession>> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    // 锁定用户行，同一用户并发创建会话时依次计数，不会超出上限
+    sqlx::query("SELECT id FROM users WHERE id = ? FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to create upload session: {e}"))
+        })?;
+
+    let open: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM upload_sessions WHERE user_id = ? AND expires_at > ?",
+    )
+    .bind(user_id)
+    .bind(now)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query upload sessions failed: {e}")))?;
+    if open >= max_open {
+        return Ok(None);
+    }
+
+    sqlx::query(
+        "INSERT INTO upload_sessions (id, user_id, file_name, file_type, file_size, chunk_size, chunk_ids, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, '', ?, ?)",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(&upload.file_name)
+    .bind(&upload.file_type)
+    .bind(upload.size)
+    .bind(chunk_size)
+    .bind(now)
+    .bind(expires_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to create upload session: {e}"))
+    })?;
+
+    let result =
+        sqlx::query_as::<sqlx::MySql, UploadSession>("SELECT * FROM upload_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to create upload session: {e}"))
+            })?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(Some(result))
+}
+
+pub async fn get_upload_session(storage: &MysqlStorage, id: &str) -> Result<Option<UploadSession>> {
+    let result =
+        sqlx::query_as::<sqlx::MySql, UploadSession>("SELECT * FROM upload_sessions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query upload session failed: {e}"))
+            })?;
+
+    Ok(result)
+}
+
+pub async fn advance_upload_session(
+    storage: &MysqlStorage,
+    id: &str,
+    offset: i64,
+    new_offset: i64,
+    chunk_ids: &str,
+    hash_state: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE upload_sessions SET upload_offset = ?, chunk_ids = ?, hash_state = ?, expires_at = ?
+            WHERE id = ? AND upload_offset = ?",
+    )
+    .bind(new_offset)
+    .bind(chunk_ids)
+    .bind(hash_state)
+    .bind(expires_at)
+    .bind(id)
+    .bind(offset)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to update upload session: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_upload_session(storage: &MysqlStorage, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM upload_sessions WHERE id = ?")
+        .bind(id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete upload session: {e}"))
+        })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_expired_upload_sessions(
+    storage: &MysqlStorage,
+    expired_before: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<UploadSession>> {
+    let sessions = sqlx::query_as::<sqlx::MySql, UploadSession>(
+        "SELECT * FROM upload_sessions WHERE expires_at < ? ORDER BY expires_at LIMIT ?",
+    )
+    .bind(expired_before)
+    .bind(limit)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Query expired upload sessions failed: {e}"))
+    })?;
+
+    Ok(sessions)
+}
@@ -0,0 +1,344 @@
+use sqlx::Row;
+
+use super::{MysqlStorage, inserted_id};
+use crate::models::{
+    PaginationInfo,
+    users::{
+        entities::{User, UserStatus},
+        requests::{CreateUserRequest, UpdateUserRequest, UserListQuery},
+        responses::UserListResponse,
+    },
+};
+
+use crate::errors::{HWSystemError, Result};
+
+pub async fn create_user(storage: &MysqlStorage, user: CreateUserRequest) -> Result<User> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+        .bind(&user.username)
+        .bind(&user.email)
+        .bind(&user.password) // 密码哈希应该在 Service 层完成
+        .bind(user.role.to_string())
+        .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
+        .bind(user.profile.profile_name)
+        .bind(user.profile.avatar_url)
+        .bind(now)
+        .bind(now)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_write(e, "Failed to create user"))?;
+
+    get_user_by_id(storage, inserted_id(&result))
+        .await?
+        .ok_or_else(|| {
+            HWSystemError::database_operation("Failed to create user: inserted row not found")
+        })
+}
+
+pub async fn create_users(
+    storage: &MysqlStorage,
+    users: Vec<CreateUserRequest>,
+) -> Result<Vec<User>> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    let mut ids = Vec::with_capacity(users.len());
+    for user in users {
+        let result = sqlx::query(
+            "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&user.username)
+        .bind(&user.email)
+        .bind(&user.password)
+        .bind(user.role.to_string())
+        .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
+        .bind(user.profile.profile_name)
+        .bind(user.profile.avatar_url)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_write(e, &format!("Failed to create user {}", user.username))
+        })?;
+        ids.push(inserted_id(&result));
+    }
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    let mut created = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(user) = get_user_by_id(storage, id).await? {
+            created.push(user);
+        }
+    }
+    Ok(created)
+}
+
+pub async fn get_user_by_id(storage: &MysqlStorage, id: i64) -> Result<Option<User>> {
+    let result = sqlx::query_as::<sqlx::MySql, User>(
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users WHERE id = ?",
+    )
+        .bind(id)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Search user by ID failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_user_by_username(storage: &MysqlStorage, username: &str) -> Result<Option<User>> {
+    let result = sqlx::query_as::<sqlx::MySql, User>(
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users WHERE username = ?",
+    )
+        .bind(username)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Search user by username failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_user_by_email(storage: &MysqlStorage, email: &str) -> Result<Option<User>> {
+    let result = sqlx::query_as::<sqlx::MySql, User>(
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users WHERE email = ?",
+    )
+        .bind(email)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Search user by email failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_user_by_username_or_email(
+    storage: &MysqlStorage,
+    identifier: &str,
+) -> Result<Option<User>> {
+    let result = sqlx::query_as::<sqlx::MySql, User>(
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users WHERE username = ? OR email = ?",
+    )
+        .bind(identifier)
+        .bind(identifier)
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("根据用户名或邮箱查询用户失败: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_users_with_pagination(
+    storage: &MysqlStorage,
+    query: UserListQuery,
+) -> Result<UserListResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(10).clamp(1, 100);
+    let offset = (page - 1) * size;
+
+    // 构建基本查询
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+
+    // 搜索条件
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        conditions.push("(username LIKE ? OR email LIKE ? OR profile_name LIKE ?)");
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern.clone());
+        params.push(search_pattern.clone());
+        params.push(search_pattern);
+    }
+
+    // 角色筛选
+    if let Some(role) = &query.role {
+        conditions.push("role = ?");
+        params.push(role.to_string());
+    }
+
+    // 状态筛选
+    if let Some(status) = &query.status {
+        conditions.push("status = ?");
+        params.push(status.to_string());
+    }
+
+    let where_clause = if conditions.is_empty() {
+        "".to_string()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    // 查询总数
+    let count_sql = format!("SELECT COUNT(*) as total FROM users{where_clause}");
+    let mut count_query = sqlx::query(&count_sql);
+    for param in &params {
+        count_query = count_query.bind(param);
+    }
+
+    let total_row = count_query
+        .fetch_one(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询用户总数失败: {e}")))?;
+    let total: i64 = total_row.get("total");
+
+    // 查询数据
+    let data_sql = format!(
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users{where_clause} ORDER BY created_at DESC LIMIT ? OFFSET ?"
+    );
+
+    let mut data_query = sqlx::query_as::<sqlx::MySql, User>(&data_sql);
+
+    // 绑定筛选参数
+    for param in &params {
+        data_query = data_query.bind(param);
+    }
+
+    // LIMIT 和 OFFSET 参数绑定
+    data_query = data_query.bind(size).bind(offset);
+
+    let rows = data_query
+        .fetch_all(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询用户列表失败: {e}")))?;
+
+    let users = rows;
+
+    let pages = (total + size - 1) / size; // 向上取整
+
+    Ok(UserListResponse {
+        items: users,
+        pagination: PaginationInfo {
+            page,
+            size,
+            total,
+            pages,
+        },
+    })
+}
+
+pub async fn update_last_login(storage: &MysqlStorage, id: i64) -> Result<bool> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query("UPDATE users SET last_login = ? WHERE id = ?")
+        .bind(now)
+        .bind(id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to update last login time: {e}"))
+        })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn mark_email_verified(storage: &MysqlStorage, id: i64, email: &str) -> Result<bool> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "UPDATE users SET email_verified = TRUE, updated_at = ? WHERE id = ? AND email = ?",
+    )
+    .bind(now)
+    .bind(id)
+    .bind(email)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to mark email as verified: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn update_user(
+    storage: &MysqlStorage,
+    id: i64,
+    update: UpdateUserRequest,
+) -> Result<Option<User>> {
+    // 先检查用户是否存在
+    let Some(existing) = get_user_by_id(storage, id).await? else {
+        return Ok(None);
+    };
+
+    let now = chrono::Utc::now();
+    let mut updates = Vec::new();
+    let mut params = Vec::new();
+
+    if let Some(email) = &update.email {
+        updates.push("email = ?");
+        params.push(email.clone());
+        // 邮箱变更后需要重新验证
+        if *email != existing.email {
+            updates.push("email_verified = FALSE");
+        }
+    }
+
+    if let Some(password) = &update.password {
+        updates.push("password_hash = ?");
+        params.push(password.clone());
+    }
+
+    if let Some(role) = &update.role {
+        updates.push("role = ?");
+        params.push(role.to_string());
+    }
+
+    if let Some(status) = &update.status {
+        updates.push("status = ?");
+        params.push(status.to_string());
+    }
+
+    if let Some(profile) = &update.profile {
+        updates.push("profile_name = ?");
+        params.push(profile.profile_name.clone());
+
+        updates.push("avatar_url = ?");
+        params.push(profile.avatar_url.clone().unwrap_or_default());
+    }
+
+    if updates.is_empty() {
+        // 没有更新内容，直接返回当前用户
+        return get_user_by_id(storage, id).await;
+    }
+
+    updates.push("updated_at = ?");
+
+    let sql = format!("UPDATE users SET {} WHERE id = ?", updates.join(", "));
+
+    let mut query_builder = sqlx::query(&sql);
+    for param in params {
+        query_builder = query_builder.bind(param);
+    }
+    query_builder = query_builder.bind(now).bind(id);
+
+    query_builder
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Failed to update user: {e}")))?;
+
+    get_user_by_id(storage, id).await
+}
+
+pub async fn delete_user(storage: &MysqlStorage, id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Failed to delete user: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,90 @@
+use super::{MysqlStorage, inserted_id};
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::UserIdentity;
+
+pub async fn get_user_identity(
+    storage: &MysqlStorage,
+    provider: &str,
+    subject: &str,
+) -> Result<Option<UserIdentity>> {
+    let result = sqlx::query_as::<sqlx::MySql, UserIdentity>(
+        "SELECT * FROM user_identities WHERE provider = ? AND subject = ?",
+    )
+    .bind(provider)
+    .bind(subject)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query user identity failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn create_user_identity(
+    storage: &MysqlStorage,
+    user_id: i64,
+    provider: &str,
+    subject: &str,
+    email: Option<&str>,
+) -> Result<UserIdentity> {
+    let now = chrono::Utc::now();
+    let result = sqlx::query(
+        "INSERT INTO user_identities (user_id, provider, subject, email, created_at, last_login_at)
+            VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(user_id)
+    .bind(provider)
+    .bind(subject)
+    .bind(email)
+    .bind(now)
+    .bind(now)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to create user identity: {e}"))
+    })?;
+
+    sqlx::query_as::<sqlx::MySql, UserIdentity>("SELECT * FROM user_identities WHERE id = ?")
+        .bind(inserted_id(&result))
+        .fetch_optional(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("Query user identity failed: {e}")))?
+        .ok_or_else(|| {
+            HWSystemError::database_operation(
+                "Failed to create user identity: inserted row not found",
+            )
+        })
+}
+
+pub async fn touch_user_identity(
+    storage: &MysqlStorage,
+    id: i64,
+    email: Option<&str>,
+) -> Result<bool> {
+    let result =
+        sqlx::query("UPDATE user_identities SET last_login_at = ?, email = ? WHERE id = ?")
+            .bind(chrono::Utc::now())
+            .bind(email)
+            .bind(id)
+            .execute(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to update user identity: {e}"))
+            })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_user_identities(
+    storage: &MysqlStorage,
+    provider: &str,
+) -> Result<Vec<UserIdentity>> {
+    let result = sqlx::query_as::<sqlx::MySql, UserIdentity>(
+        "SELECT * FROM user_identities WHERE provider = ? ORDER BY id",
+    )
+    .bind(provider)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query user identities failed: {e}")))?;
+
+    Ok(result)
+}
@@ -64,7 +64,7 @@ impl PostgresqlMigrationManager {
             .map_err(|e| HWSystemError::database_operation(format!("开始迁移事务失败: {e}")))?;
 
         // 拆分多条语句按顺序执行
-        for stmt in migration.up_sql.split(';') {
+        for stmt in split_statements(&migration.up_sql) {
             let trimmed = stmt.trim();
             if !trimmed.is_empty() {
                 sqlx::query(trimmed).execute(&mut *tx).await.map_err(|e| {
@@ -128,6 +128,29 @@ impl PostgresqlMigrationManager {
 }
 
 /// 获取所有迁移定义
+// 按分号拆分迁移语句，`$$` 包围的过程体中的分号不作为语句结束
+fn split_statements(sql: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut in_body = false;
+    let bytes = sql.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if sql[index..].starts_with("$$") {
+            in_body = !in_body;
+            index += 2;
+            continue;
+        }
+        if bytes[index] == b';' && !in_body {
+            statements.push(&sql[start..index]);
+            start = index + 1;
+        }
+        index += 1;
+    }
+    statements.push(&sql[start..]);
+    statements
+}
+
 pub fn get_all_migrations() -> Vec<Migration> {
     vec![
         Migration {
@@ -237,5 +260,320 @@ pub fn get_all_migrations() -> Vec<Migration> {
                 CREATE INDEX IF NOT EXISTS idx_classes_invite_code ON classes(invite_code);
             ".to_string(),
         },
+        Migration {
+            version: 2,
+            name: "migrate_homework_schema".to_string(),
+            up_sql: "
+                -- 迁移不删除数据：存在无法迁移的记录时中止，由管理员处理后重新启动
+                DO $$
+                BEGIN
+                    IF EXISTS (SELECT 1 FROM classes WHERE teacher_id IS NULL) THEN
+                        RAISE EXCEPTION 'Some classes have no teacher (SELECT id, class_name FROM classes WHERE teacher_id IS NULL). Assign a teacher or delete them, then restart to continue the upgrade';
+                    END IF;
+                    IF EXISTS (SELECT 1 FROM files WHERE user_id IS NULL) THEN
+                        RAISE EXCEPTION 'Some files have no uploader (SELECT id, file_name FROM files WHERE user_id IS NULL). Assign an uploader or delete them, then restart to continue the upgrade';
+                    END IF;
+                    IF EXISTS (SELECT 1 FROM class_users GROUP BY class_id, user_id HAVING COUNT(*) > 1) THEN
+                        RAISE EXCEPTION 'Some users joined the same class more than once (SELECT class_id, user_id FROM class_users GROUP BY class_id, user_id HAVING COUNT(*) > 1). Keep one membership per user, then restart to continue the upgrade';
+                    END IF;
+                    IF EXISTS (SELECT 1 FROM submissions GROUP BY assignment_id, creator_id HAVING COUNT(*) > 1) THEN
+                        RAISE EXCEPTION 'Some students submitted the same assignment more than once (SELECT assignment_id, creator_id FROM submissions GROUP BY assignment_id, creator_id HAVING COUNT(*) > 1). Keep one submission per student, then restart to continue the upgrade';
+                    END IF;
+                END
+                $$;
+
+                -- 班级必须有教师，教师删除时班级随之删除
+                ALTER TABLE classes ALTER COLUMN teacher_id SET NOT NULL;
+                ALTER TABLE classes DROP CONSTRAINT IF EXISTS classes_teacher_id_fkey;
+                ALTER TABLE classes ADD CONSTRAINT classes_teacher_id_fkey
+                    FOREIGN KEY (teacher_id) REFERENCES users(id) ON DELETE CASCADE;
+
+                -- 班级成员增加班级角色，同一用户在同一班级只有一条记录
+                ALTER TABLE class_users ADD COLUMN IF NOT EXISTS role TEXT NOT NULL DEFAULT 'student';
+                ALTER TABLE class_users ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+                UPDATE class_users SET updated_at = joined_at WHERE updated_at IS NULL;
+                ALTER TABLE class_users ALTER COLUMN updated_at SET NOT NULL;
+                ALTER TABLE class_users ALTER COLUMN role DROP DEFAULT;
+                ALTER TABLE class_users ADD CONSTRAINT class_users_class_id_user_id_key UNIQUE (class_id, user_id);
+
+                -- 班级教师作为班级成员，以便按班级角色校验权限
+                UPDATE class_users SET role = 'teacher' FROM classes
+                    WHERE classes.id = class_users.class_id AND classes.teacher_id = class_users.user_id;
+                INSERT INTO class_users (class_id, user_id, role, joined_at, updated_at)
+                    SELECT id, teacher_id, 'teacher', created_at, created_at FROM classes
+                    ON CONFLICT (class_id, user_id) DO NOTHING;
+
+                -- 作业表由 assignments 改名为 homeworks
+                ALTER TABLE assignments RENAME TO homeworks;
+                ALTER TABLE homeworks RENAME COLUMN user_id TO created_by;
+                ALTER TABLE homeworks RENAME COLUMN description TO content;
+                ALTER TABLE homeworks ADD COLUMN attachments JSONB NOT NULL DEFAULT '[]';
+                ALTER TABLE homeworks ADD COLUMN max_score DOUBLE PRECISION NOT NULL DEFAULT 100;
+                ALTER TABLE homeworks ALTER COLUMN max_score DROP DEFAULT;
+                ALTER TABLE homeworks ADD COLUMN deadline TIMESTAMPTZ;
+                UPDATE homeworks SET deadline = TO_TIMESTAMP(due_date) WHERE due_date IS NOT NULL;
+                ALTER TABLE homeworks DROP COLUMN due_date;
+                ALTER TABLE homeworks ADD COLUMN allow_late_submission BOOLEAN NOT NULL DEFAULT FALSE;
+
+                -- 提交表关联作业，同一学生对同一作业只有一份提交
+                ALTER TABLE submissions RENAME COLUMN assignment_id TO homework_id;
+                ALTER TABLE submissions ADD COLUMN attachments JSONB NOT NULL DEFAULT '[]';
+                ALTER TABLE submissions ADD COLUMN is_late BOOLEAN NOT NULL DEFAULT FALSE;
+                ALTER TABLE submissions ALTER COLUMN submitted_at TYPE TIMESTAMPTZ USING TO_TIMESTAMP(submitted_at);
+                ALTER TABLE submissions ADD CONSTRAINT submissions_homework_id_creator_id_key UNIQUE (homework_id, creator_id);
+
+                -- 创建评分表
+                CREATE TABLE IF NOT EXISTS grades (
+                    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                    submission_id BIGINT NOT NULL,
+                    grader_id BIGINT NOT NULL,
+                    score DOUBLE PRECISION NOT NULL,
+                    comment TEXT,
+                    graded_at TIMESTAMPTZ NOT NULL,
+                    FOREIGN KEY (submission_id) REFERENCES submissions(id) ON DELETE CASCADE,
+                    FOREIGN KEY (grader_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                -- 文件表增加引用次数，文件随上传者删除
+                ALTER TABLE files ALTER COLUMN file_size TYPE BIGINT;
+                ALTER TABLE files ADD COLUMN citation_count INTEGER DEFAULT 0;
+                ALTER TABLE files ALTER COLUMN user_id SET NOT NULL;
+                ALTER TABLE files DROP CONSTRAINT IF EXISTS files_user_id_fkey;
+                ALTER TABLE files ADD CONSTRAINT files_user_id_fkey
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE;
+
+                -- 班级成员表索引
+                CREATE INDEX IF NOT EXISTS idx_class_users_class_id ON class_users(class_id);
+                CREATE INDEX IF NOT EXISTS idx_class_users_user_id ON class_users(user_id);
+                CREATE INDEX IF NOT EXISTS idx_class_users_role ON class_users(role);
+
+                -- 作业表索引
+                CREATE INDEX IF NOT EXISTS idx_homeworks_class_id ON homeworks(class_id);
+                CREATE INDEX IF NOT EXISTS idx_homeworks_deadline ON homeworks(deadline);
+
+                -- 提交表索引
+                CREATE INDEX IF NOT EXISTS idx_submissions_creator_id ON submissions(creator_id);
+
+                -- 评分表索引
+                CREATE INDEX IF NOT EXISTS idx_grades_submission_id ON grades(submission_id, graded_at);
+                CREATE INDEX IF NOT EXISTS idx_grades_grader_id ON grades(grader_id);
+
+                -- 文件表索引
+                CREATE INDEX IF NOT EXISTS idx_files_citation_count ON files(citation_count);
+                CREATE INDEX IF NOT EXISTS idx_files_user_id ON files(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 3,
+            name: "create_refresh_tokens".to_string(),
+            up_sql: "
+                -- 创建刷新令牌表，用于登出吊销与轮换重放检测
+                CREATE TABLE IF NOT EXISTS refresh_tokens (
+                    jti TEXT PRIMARY KEY,
+                    family_id TEXT NOT NULL,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    expires_at TIMESTAMPTZ NOT NULL,
+                    revoked_at TIMESTAMPTZ,
+                    created_at TIMESTAMPTZ NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id ON refresh_tokens(family_id);
+                CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 4,
+            name: "create_sessions".to_string(),
+            up_sql: "
+                -- 创建登录会话表，会话 ID 与刷新令牌族 ID 相同
+                CREATE TABLE IF NOT EXISTS sessions (
+                    id TEXT PRIMARY KEY,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    user_agent TEXT,
+                    ip_address TEXT,
+                    remember_me BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    last_used_at TIMESTAMPTZ NOT NULL,
+                    expires_at TIMESTAMPTZ NOT NULL,
+                    revoked_at TIMESTAMPTZ
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 5,
+            name: "add_users_email_verified".to_string(),
+            up_sql: "
+                -- 用户邮箱是否已验证
+                ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 6,
+            name: "create_user_mfa".to_string(),
+            up_sql: "
+                -- 两步验证设置，每个用户一条
+                CREATE TABLE IF NOT EXISTS user_mfa (
+                    user_id BIGINT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+                    secret TEXT NOT NULL,
+                    enabled BOOLEAN NOT NULL DEFAULT FALSE,
+                    last_used_step BIGINT,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    enabled_at TIMESTAMPTZ
+                );
+
+                -- 两步验证恢复码，仅保存哈希
+                CREATE TABLE IF NOT EXISTS mfa_recovery_codes (
+                    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    code_hash TEXT NOT NULL,
+                    used_at TIMESTAMPTZ,
+                    created_at TIMESTAMPTZ NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_mfa_recovery_codes_user_id ON mfa_recovery_codes(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 7,
+            name: "create_api_tokens".to_string(),
+            up_sql: "
+                -- 个人访问令牌，仅保存哈希
+                CREATE TABLE IF NOT EXISTS api_tokens (
+                    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    name TEXT NOT NULL,
+                    token_prefix TEXT NOT NULL,
+                    token_hash TEXT NOT NULL UNIQUE,
+                    scopes TEXT NOT NULL DEFAULT '',
+                    expires_at TIMESTAMPTZ,
+                    last_used_at TIMESTAMPTZ,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    revoked_at TIMESTAMPTZ
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 8,
+            name: "create_user_identities".to_string(),
+            up_sql: "
+                -- 关联到本地用户的外部身份 (OpenID Connect)
+                CREATE TABLE IF NOT EXISTS user_identities (
+                    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    provider TEXT NOT NULL,
+                    subject TEXT NOT NULL,
+                    email TEXT,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    last_login_at TIMESTAMPTZ,
+                    UNIQUE (provider, subject)
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_user_identities_user_id ON user_identities(user_id);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 9,
+            name: "add_file_content_hash".to_string(),
+            up_sql: "
+                -- 文件内容哈希，内容相同的文件共用同一份存储
+                ALTER TABLE files ADD COLUMN IF NOT EXISTS content_hash TEXT;
+
+                CREATE INDEX IF NOT EXISTS idx_files_content_hash ON files(content_hash);
+                CREATE INDEX IF NOT EXISTS idx_files_uploaded_at ON files(uploaded_at);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 10,
+            name: "create_upload_sessions".to_string(),
+            up_sql: "
+                -- 可续传上传会话，已接收的分块保存在文件存储中
+                CREATE TABLE IF NOT EXISTS upload_sessions (
+                    id TEXT PRIMARY KEY,
+                    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    file_name TEXT NOT NULL,
+                    file_type TEXT NOT NULL,
+                    file_size BIGINT NOT NULL,
+                    chunk_size BIGINT NOT NULL,
+                    upload_offset BIGINT NOT NULL DEFAULT 0,
+                    created_at TIMESTAMPTZ NOT NULL,
+                    expires_at TIMESTAMPTZ NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_upload_sessions_expires_at ON upload_sessions(expires_at);
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 11,
+            name: "create_content_deletions".to_string(),
+            up_sql: "
+                -- 正在删除的文件内容，回收任务删除内容期间上传相同内容时等待删除完成后再写入
+                CREATE TABLE IF NOT EXISTS content_deletions (
+                    content_hash TEXT PRIMARY KEY,
+                    started_at TIMESTAMPTZ NOT NULL
+                );
+            "
+            .to_string(),
+        },
+        Migration {
+            version: 12,
+            name: "add_upload_session_progress".to_string(),
+            up_sql: "
+                -- 已接收内容的哈希中间状态与分块 ID，合并时无需重新读取分块
+                ALTER TABLE upload_sessions ADD COLUMN IF NOT EXISTS hash_state TEXT NOT NULL DEFAULT '';
+                ALTER TABLE upload_sessions ADD COLUMN IF NOT EXISTS chunk_ids TEXT NOT NULL DEFAULT '';
+
+                CREATE INDEX IF NOT EXISTS idx_upload_sessions_user_id ON upload_sessions(user_id);
+            "
+            .to_string(),
+        },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_statements_keeps_procedure_bodies_together() {
+        let sql = "
+            DO $$ BEGIN IF TRUE THEN RAISE EXCEPTION 'a;b'; END IF; END $$;
+            ALTER TABLE t ADD COLUMN c TEXT;
+        ";
+        let statements: Vec<_> = split_statements(sql)
+            .into_iter()
+            .map(str::trim)
+            .filter(|stmt| !stmt.is_empty())
+            .collect();
+        assert_eq!(
+            statements,
+            [
+                "DO $$ BEGIN IF TRUE THEN RAISE EXCEPTION 'a;b'; END IF; END $$",
+                "ALTER TABLE t ADD COLUMN c TEXT",
+            ]
+        );
+    }
+
+    #[test]
+    fn migrations_never_delete_rows() {
+        for migration in get_all_migrations() {
+            assert!(
+                !migration.up_sql.contains("DELETE FROM"),
+                "migration v{} deletes rows",
+                migration.version
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use super::PostgresqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::grades::{
+    entities::Grade, requests::CreateGradeRequest, responses::GradeListResponse,
+};
+
+pub async fn create_grade(
+    storage: &PostgresqlStorage,
+    submission_id: i64,
+    grader_id: i64,
+    grade: CreateGradeRequest,
+) -> Result<Grade> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query_as::<sqlx::Postgres, Grade>(
+        "INSERT INTO grades (submission_id, grader_id, score, comment, graded_at)
+            VALUES ($1, $2, $3, $4, $5) RETURNING *",
+    )
+    .bind(submission_id)
+    .bind(grader_id)
+    .bind(grade.score)
+    .bind(&grade.comment)
+    .bind(now)
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create grade: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_latest_grade_by_submission_id(
+    storage: &PostgresqlStorage,
+    submission_id: i64,
+) -> Result<Option<Grade>> {
+    let result = sqlx::query_as::<sqlx::Postgres, Grade>(
+        "SELECT * FROM grades WHERE submission_id = $1 ORDER BY graded_at DESC, id DESC LIMIT 1",
+    )
+    .bind(submission_id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query grade failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_grades_by_submission_id(
+    storage: &PostgresqlStorage,
+    submission_id: i64,
+) -> Result<GradeListResponse> {
+    let grades = sqlx::query_as::<sqlx::Postgres, Grade>(
+        "SELECT * FROM grades WHERE submission_id = $1 ORDER BY graded_at DESC, id DESC",
+    )
+    .bind(submission_id)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("查询评分历史失败: {e}")))?;
+
+    Ok(GradeListResponse { items: grades })
+}
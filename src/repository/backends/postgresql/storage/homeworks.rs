@@ -1,17 +1,242 @@
+use sqlx::Row;
+use sqlx::types::Json;
+
 use super::PostgresqlStorage;
 use crate::errors::{HWSystemError, Result};
 use crate::models::common::pagination::PaginationInfo;
 use crate::models::homeworks::{
-    entities::Homework, requests::HomeworkListQuery, responses::HomeworkListResponse,
-    responses::HomeworkResponse,
+    entities::{Homework, HomeworkStatus},
+    requests::{CreateHomeworkRequest, HomeworkListQuery, UpdateHomeworkRequest},
+    responses::HomeworkListResponse,
 };
-use chrono::{TimeZone, Utc};
-use serde_json::json;
-use sqlx::Row;
+
+pub async fn create_homework(
+    storage: &PostgresqlStorage,
+    class_id: i64,
+    created_by: i64,
+    homework: CreateHomeworkRequest,
+) -> Result<Homework> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query_as::<sqlx::Postgres, Homework>(
+        "INSERT INTO homeworks (class_id, created_by, title, content, attachments, max_score, deadline, allow_late_submission, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *",
+    )
+    .bind(class_id)
+    .bind(created_by)
+    .bind(&homework.title)
+    .bind(&homework.content)
+    .bind(Json(&homework.attachments))
+    .bind(homework.max_score)
+    .bind(homework.deadline)
+    .bind(homework.allow_late_submission)
+    .bind(now)
+    .bind(now)
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create homework: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_homework_by_id(
+    storage: &PostgresqlStorage,
+    homework_id: i64,
+) -> Result<Option<Homework>> {
+    let result =
+        sqlx::query_as::<sqlx::Postgres, Homework>("SELECT * FROM homeworks WHERE id = $1")
+            .bind(homework_id)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query homework failed: {e}"))
+            })?;
+
+    Ok(result)
+}
 
 pub async fn list_homeworks_with_pagination(
     storage: &PostgresqlStorage,
     query: HomeworkListQuery,
 ) -> Result<HomeworkListResponse> {
-    unimplemented!()
-}
\ No newline at end of file
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(10).clamp(1, 100);
+    let offset = (page - 1) * size;
+
+    // 构建基本查询
+    let mut conditions = Vec::new();
+    let mut params = Vec::new();
+
+    // 班级筛选
+    if let Some(class_id) = query.class_id {
+        conditions.push(format!("class_id = {class_id}"));
+    }
+
+    // 只查询用户所在班级的作业
+    if let Some(user_id) = query.user_id {
+        conditions.push(format!(
+            "class_id IN (SELECT class_id FROM class_users WHERE user_id = {user_id})"
+        ));
+    }
+
+    // 状态筛选
+    match query.status {
+        Some(HomeworkStatus::Active) => {
+            conditions.push("(deadline IS NULL OR deadline >= NOW())".to_owned());
+        }
+        Some(HomeworkStatus::Expired) => {
+            conditions.push("deadline < NOW()".to_owned());
+        }
+        None => {}
+    }
+
+    // 搜索条件
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern);
+        let index = params.len();
+        conditions.push(format!("(title ILIKE ${index} OR content ILIKE ${index})"));
+    }
+
+    let where_clause = if conditions.is_empty() {
+        "".to_string()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    // 排序字段只允许白名单内的列，避免 SQL 注入
+    let order_by = match query.order_by.as_deref() {
+        Some("deadline") => "deadline",
+        Some("title") => "title",
+        Some("updated_at") => "updated_at",
+        _ => "created_at",
+    };
+    let order = match query.order.as_deref() {
+        Some(o) if o.eq_ignore_ascii_case("asc") => "ASC",
+        _ => "DESC",
+    };
+
+    // 统计总数
+    let count_sql = format!("SELECT COUNT(*) as total FROM homeworks{where_clause}");
+    let mut count_query = sqlx::query(&count_sql);
+    for param in &params {
+        count_query = count_query.bind(param);
+    }
+
+    let total_row = count_query.fetch_one(&storage.pool).await.map_err(|e| {
+        HWSystemError::database_operation(format!("Query homework total count failed: {e}"))
+    })?;
+    let total: i64 = total_row.get("total");
+
+    // 查询数据
+    let data_sql = format!(
+        "SELECT *
+            FROM homeworks{where_clause} ORDER BY {order_by} {order} LIMIT ${} OFFSET ${}",
+        params.len() + 1,
+        params.len() + 2
+    );
+
+    let mut data_query = sqlx::query_as::<sqlx::Postgres, Homework>(&data_sql);
+    for param in &params {
+        data_query = data_query.bind(param);
+    }
+    data_query = data_query.bind(size).bind(offset);
+
+    let homeworks = data_query
+        .fetch_all(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询作业列表失败: {e}")))?;
+
+    Ok(HomeworkListResponse {
+        items: homeworks,
+        pagination: PaginationInfo {
+            page,
+            size,
+            total,
+            pages: (total + size - 1) / size, // 向上取整
+        },
+    })
+}
+
+pub async fn update_homework(
+    storage: &PostgresqlStorage,
+    homework_id: i64,
+    update: UpdateHomeworkRequest,
+) -> Result<Option<Homework>> {
+    // 先检查作业是否存在，并在现有数据上合并更新内容
+    let mut homework = match get_homework_by_id(storage, homework_id).await? {
+        Some(homework) => homework,
+        None => return Ok(None),
+    };
+
+    if let Some(title) = update.title {
+        homework.title = title;
+    }
+    if let Some(content) = update.content {
+        homework.content = Some(content);
+    }
+    if let Some(attachments) = update.attachments {
+        homework.attachments = attachments;
+    }
+    if let Some(max_score) = update.max_score {
+        homework.max_score = max_score;
+    }
+    if let Some(deadline) = update.deadline {
+        homework.deadline = deadline;
+    }
+    if let Some(allow_late_submission) = update.allow_late_submission {
+        homework.allow_late_submission = allow_late_submission;
+    }
+
+    let now = chrono::Utc::now();
+
+    let updated_homework = sqlx::query_as::<sqlx::Postgres, Homework>(
+        "UPDATE homeworks SET title = $1, content = $2, attachments = $3, max_score = $4, deadline = $5, allow_late_submission = $6, updated_at = $7
+            WHERE id = $8 RETURNING *",
+    )
+    .bind(&homework.title)
+    .bind(&homework.content)
+    .bind(Json(&homework.attachments))
+    .bind(homework.max_score)
+    .bind(homework.deadline)
+    .bind(homework.allow_late_submission)
+    .bind(now)
+    .bind(homework_id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to update homework: {e}")))?;
+
+    Ok(updated_homework)
+}
+
+pub async fn delete_homework(storage: &PostgresqlStorage, homework_id: i64) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM homeworks WHERE id = $1")
+        .bind(homework_id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete homework: {e}"))
+        })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// 列出附件中包含指定文件的作业
+pub async fn list_homeworks_by_attachment(
+    storage: &PostgresqlStorage,
+    submission_token: &str,
+) -> Result<Vec<Homework>> {
+    let result = sqlx::query_as::<sqlx::Postgres, Homework>(
+        "SELECT * FROM homeworks WHERE attachments @> jsonb_build_array($1::text) ORDER BY id",
+    )
+    .bind(submission_token)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to list homeworks by attachment: {e}"))
+    })?;
+
+    Ok(result)
+}
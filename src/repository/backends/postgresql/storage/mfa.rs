@@ -0,0 +1,198 @@
+use super::PostgresqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::{MfaRecoveryCode, UserMfa};
+
+pub async fn get_user_mfa(storage: &PostgresqlStorage, user_id: i64) -> Result<Option<UserMfa>> {
+    let result =
+        sqlx::query_as::<sqlx::Postgres, UserMfa>("SELECT * FROM user_mfa WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query MFA settings failed: {e}"))
+            })?;
+
+    Ok(result)
+}
+
+pub async fn save_user_mfa_secret(
+    storage: &PostgresqlStorage,
+    user_id: i64,
+    secret: &str,
+) -> Result<UserMfa> {
+    let result = sqlx::query_as::<sqlx::Postgres, UserMfa>(
+        "INSERT INTO user_mfa (user_id, secret, enabled, last_used_step, created_at, enabled_at)
+            VALUES ($1, $2, FALSE, NULL, $3, NULL)
+            ON CONFLICT(user_id) DO UPDATE SET
+                secret = excluded.secret,
+                enabled = FALSE,
+                last_used_step = NULL,
+                created_at = excluded.created_at,
+                enabled_at = NULL
+            RETURNING *",
+    )
+    .bind(user_id)
+    .bind(secret)
+    .bind(chrono::Utc::now())
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to save MFA secret: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn enable_user_mfa(
+    storage: &PostgresqlStorage,
+    user_id: i64,
+    recovery_code_hashes: &[String],
+) -> Result<bool> {
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    let result =
+        sqlx::query("UPDATE user_mfa SET enabled = TRUE, enabled_at = $1 WHERE user_id = $2 AND enabled = FALSE")
+            .bind(chrono::Utc::now())
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| HWSystemError::database_operation(format!("Failed to enable MFA: {e}")))?;
+
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    replace_recovery_codes_in(&mut tx, user_id, recovery_code_hashes).await?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(true)
+}
+
+pub async fn delete_user_mfa(storage: &PostgresqlStorage, user_id: i64) -> Result<bool> {
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete recovery codes: {e}"))
+        })?;
+
+    let result = sqlx::query("DELETE FROM user_mfa WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete MFA settings: {e}"))
+        })?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn update_mfa_last_used_step(
+    storage: &PostgresqlStorage,
+    user_id: i64,
+    step: i64,
+) -> Result<bool> {
+    // 条件更新保证同一验证码并发提交时只有一次能通过
+    let result = sqlx::query(
+        "UPDATE user_mfa SET last_used_step = $1
+            WHERE user_id = $2 AND (last_used_step IS NULL OR last_used_step < $1)",
+    )
+    .bind(step)
+    .bind(user_id)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to update MFA step: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_unused_recovery_codes(
+    storage: &PostgresqlStorage,
+    user_id: i64,
+) -> Result<Vec<MfaRecoveryCode>> {
+    let codes = sqlx::query_as::<sqlx::Postgres, MfaRecoveryCode>(
+        "SELECT * FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL ORDER BY id",
+    )
+    .bind(user_id)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query recovery codes failed: {e}")))?;
+
+    Ok(codes)
+}
+
+pub async fn use_recovery_code(storage: &PostgresqlStorage, id: i64) -> Result<bool> {
+    let result =
+        sqlx::query("UPDATE mfa_recovery_codes SET used_at = $1 WHERE id = $2 AND used_at IS NULL")
+            .bind(chrono::Utc::now())
+            .bind(id)
+            .execute(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to use recovery code: {e}"))
+            })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn replace_recovery_codes(
+    storage: &PostgresqlStorage,
+    user_id: i64,
+    code_hashes: &[String],
+) -> Result<()> {
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    replace_recovery_codes_in(&mut tx, user_id, code_hashes).await?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(())
+}
+
+// 在事务中删除旧恢复码并写入新恢复码
+async fn replace_recovery_codes_in(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: i64,
+    code_hashes: &[String],
+) -> Result<()> {
+    sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete recovery codes: {e}"))
+        })?;
+
+    let now = chrono::Utc::now();
+    for code_hash in code_hashes {
+        sqlx::query(
+            "INSERT INTO mfa_recovery_codes (user_id, code_hash, created_at) VALUES ($1, $2, $3)",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(now)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to save recovery code: {e}"))
+        })?;
+    }
+
+    Ok(())
+}
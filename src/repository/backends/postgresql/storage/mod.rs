@@ -1,8 +1,17 @@
+pub mod api_tokens;
+pub mod class_users;
 pub mod classes;
 pub mod file;
+pub mod grades;
 pub mod homeworks;
+pub mod mfa;
+pub mod refresh_tokens;
+pub mod sessions;
 pub mod storage_impl;
+pub mod submissions;
+pub mod upload_sessions;
 pub mod user;
+pub mod user_identities;
 
 use super::migrations::PostgresqlMigrationManager;
 use crate::errors::{HWSystemError, Result};
@@ -0,0 +1,60 @@
+use super::PostgresqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::RefreshToken;
+
+pub async fn create_refresh_token(
+    storage: &PostgresqlStorage,
+    jti: &str,
+    family_id: &str,
+    user_id: i64,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<RefreshToken> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query_as::<sqlx::Postgres, RefreshToken>(
+        "INSERT INTO refresh_tokens (jti, family_id, user_id, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5) RETURNING *",
+    )
+    .bind(jti)
+    .bind(family_id)
+    .bind(user_id)
+    .bind(expires_at)
+    .bind(now)
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to create refresh token: {e}"))
+    })?;
+
+    Ok(result)
+}
+
+pub async fn get_refresh_token(
+    storage: &PostgresqlStorage,
+    jti: &str,
+) -> Result<Option<RefreshToken>> {
+    let result = sqlx::query_as::<sqlx::Postgres, RefreshToken>(
+        "SELECT * FROM refresh_tokens WHERE jti = $1",
+    )
+    .bind(jti)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query refresh token failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn revoke_refresh_token(storage: &PostgresqlStorage, jti: &str) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = $1 WHERE jti = $2 AND revoked_at IS NULL",
+    )
+    .bind(chrono::Utc::now())
+    .bind(jti)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to revoke refresh token: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
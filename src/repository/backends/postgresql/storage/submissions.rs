@@ -0,0 +1,153 @@
+use sqlx::Row;
+use sqlx::types::Json;
+
+use super::PostgresqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::common::pagination::PaginationInfo;
+use crate::models::submissions::{
+    entities::{Submission, SubmissionStatus},
+    requests::{CreateSubmissionRequest, SubmissionListQuery},
+    responses::SubmissionListResponse,
+};
+
+pub async fn create_submission(
+    storage: &PostgresqlStorage,
+    homework_id: i64,
+    creator_id: i64,
+    submission: CreateSubmissionRequest,
+    is_late: bool,
+) -> Result<Option<Submission>> {
+    let now = chrono::Utc::now();
+
+    // 同一学生对同一作业只保留一份提交，重复提交时覆盖内容并刷新提交时间
+    // 已评分的提交不再覆盖，否则分数会对应到评分后才提交的内容，此时不返回行
+    let result = sqlx::query_as::<sqlx::Postgres, Submission>(
+        "INSERT INTO submissions (homework_id, creator_id, content, attachments, is_late, submitted_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (homework_id, creator_id) DO UPDATE SET
+                content = EXCLUDED.content,
+                attachments = EXCLUDED.attachments,
+                is_late = EXCLUDED.is_late,
+                submitted_at = EXCLUDED.submitted_at
+            WHERE NOT EXISTS (SELECT 1 FROM grades WHERE grades.submission_id = submissions.id)
+            RETURNING *",
+    )
+    .bind(homework_id)
+    .bind(creator_id)
+    .bind(&submission.content)
+    .bind(Json(&submission.attachments))
+    .bind(is_late)
+    .bind(now)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create submission: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_submission_by_id(
+    storage: &PostgresqlStorage,
+    submission_id: i64,
+) -> Result<Option<Submission>> {
+    let result =
+        sqlx::query_as::<sqlx::Postgres, Submission>("SELECT * FROM submissions WHERE id = $1")
+            .bind(submission_id)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query submission failed: {e}"))
+            })?;
+
+    Ok(result)
+}
+
+pub async fn get_submission_by_homework_and_creator(
+    storage: &PostgresqlStorage,
+    homework_id: i64,
+    creator_id: i64,
+) -> Result<Option<Submission>> {
+    let result = sqlx::query_as::<sqlx::Postgres, Submission>(
+        "SELECT * FROM submissions WHERE homework_id = $1 AND creator_id = $2",
+    )
+    .bind(homework_id)
+    .bind(creator_id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query submission failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_submissions_with_pagination(
+    storage: &PostgresqlStorage,
+    query: SubmissionListQuery,
+) -> Result<SubmissionListResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(10).clamp(1, 100);
+    let offset = (page - 1) * size;
+
+    // 构建基本查询
+    let mut conditions = vec!["homework_id = $1".to_owned()];
+
+    // 状态筛选
+    match query.status {
+        Some(SubmissionStatus::OnTime) => conditions.push("is_late = FALSE".to_owned()),
+        Some(SubmissionStatus::Late) => conditions.push("is_late = TRUE".to_owned()),
+        None => {}
+    }
+
+    let where_clause = format!(" WHERE {}", conditions.join(" AND "));
+
+    // 统计总数
+    let count_sql = format!("SELECT COUNT(*) as total FROM submissions{where_clause}");
+    let total_row = sqlx::query(&count_sql)
+        .bind(query.homework_id)
+        .fetch_one(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Query submission total count failed: {e}"))
+        })?;
+    let total: i64 = total_row.get("total");
+
+    // 查询数据
+    let data_sql = format!(
+        "SELECT *
+            FROM submissions{where_clause} ORDER BY submitted_at DESC LIMIT $2 OFFSET $3"
+    );
+
+    let submissions = sqlx::query_as::<sqlx::Postgres, Submission>(&data_sql)
+        .bind(query.homework_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询提交列表失败: {e}")))?;
+
+    Ok(SubmissionListResponse {
+        items: submissions,
+        pagination: PaginationInfo {
+            page,
+            size,
+            total,
+            pages: (total + size - 1) / size, // 向上取整
+        },
+    })
+}
+
+// 列出附件中包含指定文件的提交
+pub async fn list_submissions_by_attachment(
+    storage: &PostgresqlStorage,
+    submission_token: &str,
+) -> Result<Vec<Submission>> {
+    let result = sqlx::query_as::<sqlx::Postgres, Submission>(
+        "SELECT * FROM submissions WHERE attachments @> jsonb_build_array($1::text) ORDER BY id",
+    )
+    .bind(submission_token)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to list submissions by attachment: {e}"))
+    })?;
+
+    Ok(result)
+}
@@ -0,0 +1,136 @@
+use super::PostgresqlStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::files::{entities::UploadSession, requests::CreateUploadSessionRequest};
+
+pub async fn create_upload_session(
+    storage: &PostgresqlStorage,
+    id: &str,
+    user_id: i64,
+    upload: &CreateUploadSessionRequest,
+    chunk_size: i64,
+    max_open: i64,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<Option<UploadSession>> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    // 锁定用户行，同一用户并发创建会话时依次计数，不会超出上限
+    sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to create upload session: {e}"))
+        })?;
+
+    let open: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM upload_sessions WHERE user_id = $1 AND expires_at > $2",
+    )
+    .bind(user_id)
+    .bind(now)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query upload sessions failed: {e}")))?;
+    if open >= max_open {
+        return Ok(None);
+    }
+
+    let result = sqlx::query_as::<sqlx::Postgres, UploadSession>(
+        "INSERT INTO upload_sessions (id, user_id, file_name, file_type, file_size, chunk_size, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(&upload.file_name)
+    .bind(&upload.file_type)
+    .bind(upload.size)
+    .bind(chunk_size)
+    .bind(now)
+    .bind(expires_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to create upload session: {e}"))
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(Some(result))
+}
+
+pub async fn get_upload_session(
+    storage: &PostgresqlStorage,
+    id: &str,
+) -> Result<Option<UploadSession>> {
+    let result = sqlx::query_as::<sqlx::Postgres, UploadSession>(
+        "SELECT * FROM upload_sessions WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query upload session failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn advance_upload_session(
+    storage: &PostgresqlStorage,
+    id: &str,
+    offset: i64,
+    new_offset: i64,
+    chunk_ids: &str,
+    hash_state: &str,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE upload_sessions SET upload_offset = $1, chunk_ids = $2, hash_state = $3, expires_at = $4
+            WHERE id = $5 AND upload_offset = $6",
+    )
+    .bind(new_offset)
+    .bind(chunk_ids)
+    .bind(hash_state)
+    .bind(expires_at)
+    .bind(id)
+    .bind(offset)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to update upload session: {e}")))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_upload_session(storage: &PostgresqlStorage, id: &str) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM upload_sessions WHERE id = $1")
+        .bind(id)
+        .execute(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Failed to delete upload session: {e}"))
+        })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_expired_upload_sessions(
+    storage: &PostgresqlStorage,
+    expired_before: chrono::DateTime<chrono::Utc>,
+    limit: i64,
+) -> Result<Vec<UploadSession>> {
+    let sessions = sqlx::query_as::<sqlx::Postgres, UploadSession>(
+        "SELECT * FROM upload_sessions WHERE expires_at < $1 ORDER BY expires_at LIMIT $2",
+    )
+    .bind(expired_before)
+    .bind(limit)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Query expired upload sessions failed: {e}"))
+    })?;
+
+    Ok(sessions)
+}
@@ -16,29 +16,70 @@ pub async fn create_user(storage: &PostgresqlStorage, user: CreateUserRequest) -
     let now = chrono::Utc::now();
 
     let result = sqlx::query_as::<sqlx::Postgres, User>(
-        "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at) 
+        "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-            RETURNING id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at",
+            RETURNING id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at",
     )
         .bind(&user.username)
         .bind(&user.email)
         .bind(&user.password) // 密码哈希应该在 Service 层完成
         .bind(user.role.to_string())
-        .bind(UserStatus::Active.to_string())
+        .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
         .bind(user.profile.profile_name)
         .bind(user.profile.avatar_url)
-        .bind(now.timestamp()) // 使用时间戳
-        .bind(now.timestamp()) // 使用时间戳
+        .bind(now)
+        .bind(now)
         .fetch_one(&storage.pool)
         .await
-        .map_err(|e| HWSystemError::database_operation(format!("Failed to create user: {e}")))?;
+        .map_err(|e| HWSystemError::database_write(e, "Failed to create user"))?;
 
     Ok(result)
 }
 
+pub async fn create_users(
+    storage: &PostgresqlStorage,
+    users: Vec<CreateUserRequest>,
+) -> Result<Vec<User>> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    let mut created = Vec::with_capacity(users.len());
+    for user in users {
+        let result = sqlx::query_as::<sqlx::Postgres, User>(
+            "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at",
+        )
+        .bind(&user.username)
+        .bind(&user.email)
+        .bind(&user.password)
+        .bind(user.role.to_string())
+        .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
+        .bind(user.profile.profile_name)
+        .bind(user.profile.avatar_url)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_write(e, &format!("Failed to create user {}", user.username))
+        })?;
+        created.push(result);
+    }
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(created)
+}
+
 pub async fn get_user_by_id(storage: &PostgresqlStorage, id: i64) -> Result<Option<User>> {
     let result = sqlx::query_as::<sqlx::Postgres, User>(
-        "SELECT id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
             FROM users WHERE id = $1",
     )
         .bind(id)
@@ -54,7 +95,7 @@ pub async fn get_user_by_username(
     username: &str,
 ) -> Result<Option<User>> {
     let result = sqlx::query_as::<sqlx::Postgres, User>(
-        "SELECT id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
             FROM users WHERE username = $1",
     )
         .bind(username)
@@ -67,7 +108,7 @@ pub async fn get_user_by_username(
 
 pub async fn get_user_by_email(storage: &PostgresqlStorage, email: &str) -> Result<Option<User>> {
     let result = sqlx::query_as::<sqlx::Postgres, User>(
-        "SELECT id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
             FROM users WHERE email = $1",
     )
         .bind(email)
@@ -83,7 +124,7 @@ pub async fn get_user_by_username_or_email(
     identifier: &str,
 ) -> Result<Option<User>> {
     let result = sqlx::query_as::<sqlx::Postgres, User>(
-        "SELECT id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
             FROM users WHERE username = $1 OR email = $2",
     )
         .bind(identifier)
@@ -103,55 +144,34 @@ pub async fn list_users_with_pagination(
     let size = query.size.unwrap_or(10).clamp(1, 100);
     let offset = (page - 1) * size;
 
-    // 构建基本查询
+    // 构建基本查询，PostgreSQL 占位符按参数顺序编号
     let mut conditions = Vec::new();
     let mut params: Vec<String> = Vec::new();
 
     // 搜索条件
-    if let Some(search) = &query.search {
-        if !search.trim().is_empty() {
-            conditions.push("(username ILIKE $1 OR email ILIKE $2 OR profile_name ILIKE $3)");
-            let search_pattern = format!("%{}%", search.trim());
-            params.push(search_pattern.clone());
-            params.push(search_pattern.clone());
-            params.push(search_pattern);
-        }
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern);
+        let index = params.len();
+        conditions.push(format!(
+            "(username ILIKE ${index} OR email ILIKE ${index} OR profile_name ILIKE ${index})"
+        ));
     }
 
     // 角色筛选
     if let Some(role) = &query.role {
-        conditions.push(if params.is_empty() {
-            "role = $1"
-        } else {
-            // 角色条件参数索引
-            // 索引为 params.len() + 1
-            // 由于search占用了1,2,3号参数，所以角色参数索引要加3
-            // 为避免逻辑复杂，下面重写为动态索引构造
-            // 这里简化用占位符，用 sqlx::QueryBuilder更好
-            // 但此处给个简化版
-            // 先跳过，改用 sqlx::QueryBuilder 建议
-            "role = $4"
-        });
         params.push(role.to_string());
+        conditions.push(format!("role = ${}", params.len()));
     }
 
     // 状态筛选
     if let Some(status) = &query.status {
-        conditions.push(if params.is_empty() {
-            "status = $1"
-        } else {
-            // 同理，状态参数索引依赖之前参数个数，略复杂
-            "status = $5"
-        });
         params.push(status.to_string());
+        conditions.push(format!("status = ${}", params.len()));
     }
 
-    // 如果多个条件，参数编号需要准确，建议用 sqlx::QueryBuilder 动态构造，或者这里简单版本如下：
-
-    // 下面的动态参数编号示例，假设只搜索，无角色和状态筛选
-    // 或者无搜索只有角色和状态等
-    // 实际建议用 sqlx::QueryBuilder
-
     let where_clause = if conditions.is_empty() {
         "".to_string()
     } else {
@@ -173,8 +193,10 @@ pub async fn list_users_with_pagination(
 
     // 查询数据
     let data_sql = format!(
-        "SELECT id, username, email, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
-            FROM users{where_clause} ORDER BY created_at DESC LIMIT $1 OFFSET $2"
+        "SELECT id, username, email, email_verified, password_hash, role, status, profile_name, avatar_url, last_login, created_at, updated_at
+            FROM users{where_clause} ORDER BY created_at DESC LIMIT ${} OFFSET ${}",
+        params.len() + 1,
+        params.len() + 2
     );
 
     let mut data_query = sqlx::query_as::<sqlx::Postgres, User>(&data_sql);
@@ -211,7 +233,7 @@ pub async fn update_last_login(storage: &PostgresqlStorage, id: i64) -> Result<b
     let now = chrono::Utc::now();
 
     let result = sqlx::query("UPDATE users SET last_login = $1 WHERE id = $2")
-        .bind(now.timestamp())
+        .bind(now)
         .bind(id)
         .execute(&storage.pool)
         .await
@@ -222,15 +244,37 @@ pub async fn update_last_login(storage: &PostgresqlStorage, id: i64) -> Result<b
     Ok(result.rows_affected() > 0)
 }
 
+pub async fn mark_email_verified(
+    storage: &PostgresqlStorage,
+    id: i64,
+    email: &str,
+) -> Result<bool> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "UPDATE users SET email_verified = TRUE, updated_at = $1 WHERE id = $2 AND email = $3",
+    )
+    .bind(now)
+    .bind(id)
+    .bind(email)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to mark email as verified: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn update_user(
     storage: &PostgresqlStorage,
     id: i64,
     update: UpdateUserRequest,
 ) -> Result<Option<User>> {
     // 先检查用户是否存在
-    if get_user_by_id(storage, id).await?.is_none() {
+    let Some(existing) = get_user_by_id(storage, id).await? else {
         return Ok(None);
-    }
+    };
 
     let now = chrono::Utc::now();
     let mut updates = Vec::new();
@@ -239,6 +283,10 @@ pub async fn update_user(
     if let Some(email) = &update.email {
         updates.push(format!("email = ${}", params.len() + 1));
         params.push(email.clone());
+        // 邮箱变更后需要重新验证
+        if *email != existing.email {
+            updates.push("email_verified = FALSE".to_string());
+        }
     }
 
     if let Some(password) = &update.password {
@@ -270,19 +318,18 @@ pub async fn update_user(
     }
 
     updates.push(format!("updated_at = ${}", params.len() + 1));
-    params.push(now.timestamp().to_string());
 
     let sql = format!(
         "UPDATE users SET {} WHERE id = ${}",
         updates.join(", "),
-        params.len() + 1
+        params.len() + 2
     );
-    params.push(id.to_string());
 
     let mut query_builder = sqlx::query(&sql);
     for param in params {
         query_builder = query_builder.bind(param);
     }
+    query_builder = query_builder.bind(now).bind(id);
 
     query_builder
         .execute(&storage.pool)
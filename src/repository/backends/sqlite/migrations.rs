@@ -298,5 +298,198 @@ pub fn get_all_migrations() -> Vec<Migration> {
                 -- 正式环境需要删除这些测试数据
                 ".to_string(),
         },
+        Migration {
+            version: 3,
+            name: "add_submission_late_flag".to_string(),
+            up_sql: "
+                -- 提交表增加迟交标记
+                ALTER TABLE submissions ADD COLUMN is_late BOOLEAN NOT NULL DEFAULT 0;
+
+                -- 提交表索引，同一学生对同一作业只保留一份提交
+                CREATE UNIQUE INDEX idx_submissions_homework_creator ON submissions(homework_id, creator_id);
+                CREATE INDEX idx_submissions_creator_id ON submissions(creator_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 4,
+            name: "add_grade_indexes".to_string(),
+            up_sql: "
+                -- 评分表索引，用于查询提交的评分历史
+                CREATE INDEX idx_grades_submission_id ON grades(submission_id, graded_at);
+                CREATE INDEX idx_grades_grader_id ON grades(grader_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 5,
+            name: "create_refresh_tokens".to_string(),
+            up_sql: "
+                -- 创建刷新令牌表，用于登出吊销与轮换重放检测
+                CREATE TABLE refresh_tokens (
+                    jti TEXT PRIMARY KEY,
+                    family_id TEXT NOT NULL,         -- 令牌族，同一次登录轮换产生的令牌共享
+                    user_id INTEGER NOT NULL,
+                    expires_at INTEGER NOT NULL,     -- 过期时间
+                    revoked_at INTEGER,              -- 吊销时间
+                    created_at INTEGER NOT NULL,     -- 签发时间
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_refresh_tokens_family_id ON refresh_tokens(family_id);
+                CREATE INDEX idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 6,
+            name: "create_sessions".to_string(),
+            up_sql: "
+                -- 创建登录会话表，会话 ID 与刷新令牌族 ID 相同
+                CREATE TABLE sessions (
+                    id TEXT PRIMARY KEY,
+                    user_id INTEGER NOT NULL,
+                    user_agent TEXT,                 -- 登录设备
+                    ip_address TEXT,                 -- 登录 IP
+                    remember_me BOOLEAN NOT NULL DEFAULT 0,
+                    created_at INTEGER NOT NULL,     -- 创建时间
+                    last_used_at INTEGER NOT NULL,   -- 最后使用时间
+                    expires_at INTEGER NOT NULL,     -- 过期时间
+                    revoked_at INTEGER,              -- 终止时间
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_sessions_user_id ON sessions(user_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 7,
+            name: "add_users_email_verified".to_string(),
+            up_sql: "
+                -- 用户邮箱是否已验证
+                ALTER TABLE users ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT 0;
+            ".to_string(),
+        },
+        Migration {
+            version: 8,
+            name: "create_user_mfa".to_string(),
+            up_sql: "
+                -- 两步验证设置，每个用户一条
+                CREATE TABLE user_mfa (
+                    user_id INTEGER PRIMARY KEY,
+                    secret TEXT NOT NULL,            -- Base32 编码的 TOTP 密钥
+                    enabled BOOLEAN NOT NULL DEFAULT 0,
+                    last_used_step INTEGER,          -- 最近一次验证通过的时间步
+                    created_at INTEGER NOT NULL,     -- 创建时间
+                    enabled_at INTEGER,              -- 启用时间
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                -- 两步验证恢复码，仅保存哈希
+                CREATE TABLE mfa_recovery_codes (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL,
+                    code_hash TEXT NOT NULL,
+                    used_at INTEGER,                 -- 使用时间
+                    created_at INTEGER NOT NULL,     -- 创建时间
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_mfa_recovery_codes_user_id ON mfa_recovery_codes(user_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 9,
+            name: "create_api_tokens".to_string(),
+            up_sql: "
+                -- 个人访问令牌，仅保存哈希
+                CREATE TABLE api_tokens (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL,
+                    name TEXT NOT NULL,
+                    token_prefix TEXT NOT NULL,      -- 令牌明文前缀，用于辨认令牌
+                    token_hash TEXT NOT NULL UNIQUE, -- 令牌 SHA-256 哈希
+                    scopes TEXT NOT NULL DEFAULT '', -- 权限范围，空格分隔
+                    expires_at INTEGER,              -- 过期时间
+                    last_used_at INTEGER,            -- 最后使用时间
+                    created_at INTEGER NOT NULL,     -- 创建时间
+                    revoked_at INTEGER,              -- 吊销时间
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 10,
+            name: "create_user_identities".to_string(),
+            up_sql: "
+                -- 关联到本地用户的外部身份 (OpenID Connect)
+                CREATE TABLE user_identities (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL,
+                    provider TEXT NOT NULL,          -- 身份提供方名称
+                    subject TEXT NOT NULL,           -- 身份提供方中的用户标识 (sub)
+                    email TEXT,                      -- 身份提供方返回的邮箱
+                    created_at INTEGER NOT NULL,     -- 关联时间
+                    last_login_at INTEGER,           -- 最近登录时间
+                    UNIQUE (provider, subject),
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_user_identities_user_id ON user_identities(user_id);
+            ".to_string(),
+        },
+        Migration {
+            version: 11,
+            name: "add_file_content_hash".to_string(),
+            up_sql: "
+                -- 文件内容哈希，内容相同的文件共用同一份存储
+                ALTER TABLE files ADD COLUMN content_hash TEXT;
+
+                CREATE INDEX idx_files_content_hash ON files(content_hash);
+                CREATE INDEX idx_files_uploaded_at ON files(uploaded_at);
+            ".to_string(),
+        },
+        Migration {
+            version: 12,
+            name: "create_upload_sessions".to_string(),
+            up_sql: "
+                -- 可续传上传会话，已接收的分块保存在文件存储中
+                CREATE TABLE upload_sessions (
+                    id TEXT PRIMARY KEY,
+                    user_id INTEGER NOT NULL,
+                    file_name TEXT NOT NULL,
+                    file_type TEXT NOT NULL,
+                    file_size INTEGER NOT NULL,      -- 文件总字节数
+                    chunk_size INTEGER NOT NULL,     -- 分块字节数
+                    upload_offset INTEGER NOT NULL DEFAULT 0, -- 已接收的字节数
+                    created_at INTEGER NOT NULL,     -- 创建时间
+                    expires_at INTEGER NOT NULL,     -- 过期时间，每接收一个分块顺延
+                    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+                );
+
+                CREATE INDEX idx_upload_sessions_expires_at ON upload_sessions(expires_at);
+            ".to_string(),
+        },
+        Migration {
+            version: 13,
+            name: "create_content_deletions".to_string(),
+            up_sql: "
+                -- 正在删除的文件内容，回收任务删除内容期间上传相同内容时等待删除完成后再写入
+                CREATE TABLE content_deletions (
+                    content_hash TEXT PRIMARY KEY,
+                    started_at INTEGER NOT NULL      -- 开始删除的时间
+                );
+            ".to_string(),
+        },
+        Migration {
+            version: 14,
+            name: "add_upload_session_progress".to_string(),
+            up_sql: "
+                -- 已接收内容的哈希中间状态与分块 ID，合并时无需重新读取分块
+                ALTER TABLE upload_sessions ADD COLUMN hash_state TEXT NOT NULL DEFAULT '';
+                ALTER TABLE upload_sessions ADD COLUMN chunk_ids TEXT NOT NULL DEFAULT '';
+
+                CREATE INDEX idx_upload_sessions_user_id ON upload_sessions(user_id);
+            ".to_string(),
+        },
     ]
 }
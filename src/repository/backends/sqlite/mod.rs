@@ -1,2 +1,5 @@
 mod migrations;
 mod storage;
+
+#[cfg(test)]
+pub use storage::SqliteStorage;
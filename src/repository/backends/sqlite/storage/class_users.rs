@@ -127,12 +127,12 @@ pub async fn list_class_users_with_pagination(
     conditions.push(class_id_query);
 
     // 搜索条件
-    if let Some(search) = &query.search {
-        if !search.trim().is_empty() {
-            conditions.push("u.profile_name LIKE ?".to_owned());
-            let search_pattern = format!("%{}%", search.trim());
-            params.push(search_pattern);
-        }
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        conditions.push("u.profile_name LIKE ?".to_owned());
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern);
     }
 
     let where_clause = if conditions.is_empty() {
@@ -142,13 +142,13 @@ pub async fn list_classes_with_pagination(
     }
 
     // 搜索条件
-    if let Some(search) = &query.search {
-        if !search.trim().is_empty() {
-            conditions.push("(class_name LIKE ? OR description LIKE ?)".to_owned());
-            let search_pattern = format!("%{}%", search.trim());
-            params.push(search_pattern.clone());
-            params.push(search_pattern);
-        }
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        conditions.push("(class_name LIKE ? OR description LIKE ?)".to_owned());
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern.clone());
+        params.push(search_pattern);
     }
 
     let where_clause = if conditions.is_empty() {
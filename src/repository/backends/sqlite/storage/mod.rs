@@ -1,9 +1,17 @@
+pub mod api_tokens;
 pub mod class_users;
 pub mod classes;
 pub mod file;
+pub mod grades;
 pub mod homeworks;
+pub mod mfa;
+pub mod refresh_tokens;
+pub mod sessions;
 pub mod storage_impl;
+pub mod submissions;
+pub mod upload_sessions;
 pub mod user;
+pub mod user_identities;
 
 use super::migrations::SqliteMigrationManager;
 use crate::errors::{HWSystemError, Result};
@@ -50,6 +58,26 @@ impl SqliteStorage {
 
         Ok(storage)
     }
+
+    /// 创建内存数据库并运行迁移，仅用于测试
+    ///
+    /// 每个内存数据库只存在于单个连接中，连接池固定为一个连接且不回收
+    #[cfg(test)]
+    pub async fn new_in_memory() -> Result<Self> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(SqliteConnectOptions::new().filename(":memory:"))
+            .await
+            .map_err(|e| HWSystemError::database_connection(format!("无法连接到数据库: {e}")))?;
+
+        let storage = SqliteStorage { pool };
+        let migration_manager = SqliteMigrationManager::new(storage.pool.clone());
+        migration_manager.init().await?;
+        migration_manager.migrate_up().await?;
+        Ok(storage)
+    }
 }
 
 // 注册 SQLite 存储插件
@@ -0,0 +1,153 @@
+use sqlx::Row;
+use sqlx::types::Json;
+
+use super::SqliteStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::common::pagination::PaginationInfo;
+use crate::models::submissions::{
+    entities::{Submission, SubmissionStatus},
+    requests::{CreateSubmissionRequest, SubmissionListQuery},
+    responses::SubmissionListResponse,
+};
+
+pub async fn create_submission(
+    storage: &SqliteStorage,
+    homework_id: i64,
+    creator_id: i64,
+    submission: CreateSubmissionRequest,
+    is_late: bool,
+) -> Result<Option<Submission>> {
+    let now = chrono::Utc::now();
+
+    // 同一学生对同一作业只保留一份提交，重复提交时覆盖内容并刷新提交时间
+    // 已评分的提交不再覆盖，否则分数会对应到评分后才提交的内容，此时不返回行
+    let result = sqlx::query_as::<sqlx::Sqlite, Submission>(
+        "INSERT INTO submissions (homework_id, creator_id, content, attachments, is_late, submitted_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (homework_id, creator_id) DO UPDATE SET
+                content = excluded.content,
+                attachments = excluded.attachments,
+                is_late = excluded.is_late,
+                submitted_at = excluded.submitted_at
+            WHERE NOT EXISTS (SELECT 1 FROM grades WHERE grades.submission_id = submissions.id)
+            RETURNING *",
+    )
+    .bind(homework_id)
+    .bind(creator_id)
+    .bind(&submission.content)
+    .bind(Json(&submission.attachments))
+    .bind(is_late)
+    .bind(now.timestamp()) // 使用时间戳
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Failed to create submission: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn get_submission_by_id(
+    storage: &SqliteStorage,
+    submission_id: i64,
+) -> Result<Option<Submission>> {
+    let result =
+        sqlx::query_as::<sqlx::Sqlite, Submission>("SELECT * FROM submissions WHERE id = ?")
+            .bind(submission_id)
+            .fetch_optional(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Query submission failed: {e}"))
+            })?;
+
+    Ok(result)
+}
+
+pub async fn get_submission_by_homework_and_creator(
+    storage: &SqliteStorage,
+    homework_id: i64,
+    creator_id: i64,
+) -> Result<Option<Submission>> {
+    let result = sqlx::query_as::<sqlx::Sqlite, Submission>(
+        "SELECT * FROM submissions WHERE homework_id = ? AND creator_id = ?",
+    )
+    .bind(homework_id)
+    .bind(creator_id)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query submission failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn list_submissions_with_pagination(
+    storage: &SqliteStorage,
+    query: SubmissionListQuery,
+) -> Result<SubmissionListResponse> {
+    let page = query.page.unwrap_or(1).max(1);
+    let size = query.size.unwrap_or(10).clamp(1, 100);
+    let offset = (page - 1) * size;
+
+    // 构建基本查询
+    let mut conditions = vec!["homework_id = ?".to_owned()];
+
+    // 状态筛选
+    match query.status {
+        Some(SubmissionStatus::OnTime) => conditions.push("is_late = 0".to_owned()),
+        Some(SubmissionStatus::Late) => conditions.push("is_late = 1".to_owned()),
+        None => {}
+    }
+
+    let where_clause = format!(" WHERE {}", conditions.join(" AND "));
+
+    // 统计总数
+    let count_sql = format!("SELECT COUNT(*) as total FROM submissions{where_clause}");
+    let total_row = sqlx::query(&count_sql)
+        .bind(query.homework_id)
+        .fetch_one(&storage.pool)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_operation(format!("Query submission total count failed: {e}"))
+        })?;
+    let total: i64 = total_row.get("total");
+
+    // 查询数据
+    let data_sql = format!(
+        "SELECT *
+            FROM submissions{where_clause} ORDER BY submitted_at DESC LIMIT ? OFFSET ?"
+    );
+
+    let submissions = sqlx::query_as::<sqlx::Sqlite, Submission>(&data_sql)
+        .bind(query.homework_id)
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&storage.pool)
+        .await
+        .map_err(|e| HWSystemError::database_operation(format!("查询提交列表失败: {e}")))?;
+
+    Ok(SubmissionListResponse {
+        items: submissions,
+        pagination: PaginationInfo {
+            page,
+            size,
+            total,
+            pages: (total + size - 1) / size, // 向上取整
+        },
+    })
+}
+
+// 列出附件中包含指定文件的提交
+pub async fn list_submissions_by_attachment(
+    storage: &SqliteStorage,
+    submission_token: &str,
+) -> Result<Vec<Submission>> {
+    let result = sqlx::query_as::<sqlx::Sqlite, Submission>(
+        "SELECT * FROM submissions WHERE EXISTS (SELECT 1 FROM json_each(submissions.attachments) WHERE value = ?) ORDER BY id",
+    )
+    .bind(submission_token)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to list submissions by attachment: {e}"))
+    })?;
+
+    Ok(result)
+}
@@ -23,18 +23,58 @@ pub async fn create_user(storage: &SqliteStorage, user: CreateUserRequest) -> Re
     .bind(&user.email)
     .bind(&user.password) // 密码哈希应该在 Service 层完成
     .bind(user.role.to_string())
-    .bind(UserStatus::Active.to_string())
+    .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
     .bind(user.profile.profile_name)
     .bind(user.profile.avatar_url)
     .bind(now.timestamp()) // 使用时间戳
     .bind(now.timestamp()) // 使用时间戳
     .fetch_one(&storage.pool)
     .await
-    .map_err(|e| HWSystemError::database_operation(format!("Failed to create user: {e}")))?;
+    .map_err(|e| HWSystemError::database_write(e, "Failed to create user"))?;
 
     Ok(result)
 }
 
+pub async fn create_users(
+    storage: &SqliteStorage,
+    users: Vec<CreateUserRequest>,
+) -> Result<Vec<User>> {
+    let now = chrono::Utc::now();
+
+    let mut tx = storage.pool.begin().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to begin transaction: {e}"))
+    })?;
+
+    let mut created = Vec::with_capacity(users.len());
+    for user in users {
+        let result = sqlx::query_as::<sqlx::Sqlite, User>(
+            "INSERT INTO users (username, email, password_hash, role, status, profile_name, avatar_url, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(&user.username)
+        .bind(&user.email)
+        .bind(&user.password)
+        .bind(user.role.to_string())
+        .bind(user.status.as_ref().unwrap_or(&UserStatus::Active).to_string())
+        .bind(user.profile.profile_name)
+        .bind(user.profile.avatar_url)
+        .bind(now.timestamp())
+        .bind(now.timestamp())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| {
+            HWSystemError::database_write(e, &format!("Failed to create user {}", user.username))
+        })?;
+        created.push(result);
+    }
+
+    tx.commit().await.map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to commit transaction: {e}"))
+    })?;
+
+    Ok(created)
+}
+
 pub async fn get_user_by_id(storage: &SqliteStorage, id: i64) -> Result<Option<User>> {
     let result = sqlx::query_as::<sqlx::Sqlite, User>("SELECT * FROM users WHERE id = ?")
         .bind(id)
@@ -111,14 +151,14 @@ pub async fn list_users_with_pagination(
     let mut params = Vec::new();
 
     // 搜索条件
-    if let Some(search) = &query.search {
-        if !search.trim().is_empty() {
-            conditions.push("(username LIKE ? OR email LIKE ? OR profile_name LIKE ?)");
-            let search_pattern = format!("%{}%", search.trim());
-            params.push(search_pattern.clone());
-            params.push(search_pattern.clone());
-            params.push(search_pattern);
-        }
+    if let Some(search) = &query.search
+        && !search.trim().is_empty()
+    {
+        conditions.push("(username LIKE ? OR email LIKE ? OR profile_name LIKE ?)");
+        let search_pattern = format!("%{}%", search.trim());
+        params.push(search_pattern.clone());
+        params.push(search_pattern.clone());
+        params.push(search_pattern);
     }
 
     // 角色筛选
@@ -197,15 +237,33 @@ pub async fn update_last_login(storage: &SqliteStorage, id: i64) -> Result<bool>
     Ok(result.rows_affected() > 0)
 }
 
+pub async fn mark_email_verified(storage: &SqliteStorage, id: i64, email: &str) -> Result<bool> {
+    let now = chrono::Utc::now();
+
+    let result = sqlx::query(
+        "UPDATE users SET email_verified = 1, updated_at = ? WHERE id = ? AND email = ?",
+    )
+    .bind(now.timestamp())
+    .bind(id)
+    .bind(email)
+    .execute(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to mark email as verified: {e}"))
+    })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn update_user(
     storage: &SqliteStorage,
     id: i64,
     update: UpdateUserRequest,
 ) -> Result<Option<User>> {
     // 先检查用户是否存在
-    if get_user_by_id(storage, id).await?.is_none() {
+    let Some(existing) = get_user_by_id(storage, id).await? else {
         return Ok(None);
-    }
+    };
 
     let now = chrono::Utc::now();
     let mut updates = Vec::new();
@@ -214,6 +272,10 @@ pub async fn update_user(
     if let Some(email) = &update.email {
         updates.push("email = ?");
         params.push(email.clone());
+        // 邮箱变更后需要重新验证
+        if *email != existing.email {
+            updates.push("email_verified = 0");
+        }
     }
 
     if let Some(password) = &update.password {
@@ -0,0 +1,81 @@
+use super::SqliteStorage;
+use crate::errors::{HWSystemError, Result};
+use crate::models::auth::entities::UserIdentity;
+
+pub async fn get_user_identity(
+    storage: &SqliteStorage,
+    provider: &str,
+    subject: &str,
+) -> Result<Option<UserIdentity>> {
+    let result = sqlx::query_as::<sqlx::Sqlite, UserIdentity>(
+        "SELECT * FROM user_identities WHERE provider = ? AND subject = ?",
+    )
+    .bind(provider)
+    .bind(subject)
+    .fetch_optional(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query user identity failed: {e}")))?;
+
+    Ok(result)
+}
+
+pub async fn create_user_identity(
+    storage: &SqliteStorage,
+    user_id: i64,
+    provider: &str,
+    subject: &str,
+    email: Option<&str>,
+) -> Result<UserIdentity> {
+    let now = chrono::Utc::now().timestamp();
+    let result = sqlx::query_as::<sqlx::Sqlite, UserIdentity>(
+        "INSERT INTO user_identities (user_id, provider, subject, email, created_at, last_login_at)
+            VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
+    )
+    .bind(user_id)
+    .bind(provider)
+    .bind(subject)
+    .bind(email)
+    .bind(now)
+    .bind(now)
+    .fetch_one(&storage.pool)
+    .await
+    .map_err(|e| {
+        HWSystemError::database_operation(format!("Failed to create user identity: {e}"))
+    })?;
+
+    Ok(result)
+}
+
+pub async fn touch_user_identity(
+    storage: &SqliteStorage,
+    id: i64,
+    email: Option<&str>,
+) -> Result<bool> {
+    let result =
+        sqlx::query("UPDATE user_identities SET last_login_at = ?, email = ? WHERE id = ?")
+            .bind(chrono::Utc::now().timestamp())
+            .bind(email)
+            .bind(id)
+            .execute(&storage.pool)
+            .await
+            .map_err(|e| {
+                HWSystemError::database_operation(format!("Failed to update user identity: {e}"))
+            })?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_user_identities(
+    storage: &SqliteStorage,
+    provider: &str,
+) -> Result<Vec<UserIdentity>> {
+    let result = sqlx::query_as::<sqlx::Sqlite, UserIdentity>(
+        "SELECT * FROM user_identities WHERE provider = ? ORDER BY id",
+    )
+    .bind(provider)
+    .fetch_all(&storage.pool)
+    .await
+    .map_err(|e| HWSystemError::database_operation(format!("Query user identities failed: {e}")))?;
+
+    Ok(result)
+}
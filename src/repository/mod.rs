@@ -5,6 +5,7 @@ use std::sync::Arc;
 use tracing::error;
 
 use crate::models::{
+    auth::entities::{ApiToken, MfaRecoveryCode, RefreshToken, Session, UserIdentity, UserMfa},
     class_users::{
         entities::{ClassUser, ClassUserRole},
         requests::{ClassUserQuery, UpdateClassUserRequest},
@@ -15,8 +16,21 @@ use crate::models::{
         requests::{ClassListQuery, CreateClassRequest, UpdateClassRequest},
         responses::ClassListResponse,
     },
-    files::entities::File,
-    homeworks::{requests::HomeworkListQuery, responses::HomeworkListResponse},
+    files::{
+        entities::{File, UploadSession},
+        requests::CreateUploadSessionRequest,
+    },
+    grades::{entities::Grade, requests::CreateGradeRequest, responses::GradeListResponse},
+    homeworks::{
+        entities::Homework,
+        requests::{CreateHomeworkRequest, HomeworkListQuery, UpdateHomeworkRequest},
+        responses::HomeworkListResponse,
+    },
+    submissions::{
+        entities::Submission,
+        requests::{CreateSubmissionRequest, SubmissionListQuery},
+        responses::SubmissionListResponse,
+    },
     users::{
         entities::User,
         requests::{CreateUserRequest, UpdateUserRequest, UserListQuery},
@@ -36,6 +50,8 @@ pub trait Storage: Send + Sync {
     /// 用户管理方法
     // 创建用户
     async fn create_user(&self, user: CreateUserRequest) -> Result<User>;
+    // 在同一事务中批量创建用户，任一用户创建失败时全部回滚
+    async fn create_users(&self, users: Vec<CreateUserRequest>) -> Result<Vec<User>>;
     // 通过ID获取用户信息
     async fn get_user_by_id(&self, id: i64) -> Result<Option<User>>;
     // 通过用户名获取用户信息
@@ -52,6 +68,102 @@ pub trait Storage: Send + Sync {
     async fn delete_user(&self, id: i64) -> Result<bool>;
     // 更新用户最后登录时间
     async fn update_last_login(&self, id: i64) -> Result<bool>;
+    // 标记用户邮箱已验证，邮箱已变更时不生效
+    async fn mark_email_verified(&self, id: i64, email: &str) -> Result<bool>;
+
+    /// 刷新令牌管理方法
+    // 记录新签发的刷新令牌
+    async fn create_refresh_token(
+        &self,
+        jti: &str,
+        family_id: &str,
+        user_id: i64,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<RefreshToken>;
+    // 通过 jti 获取刷新令牌
+    async fn get_refresh_token(&self, jti: &str) -> Result<Option<RefreshToken>>;
+    // 吊销单个刷新令牌，仅当令牌此前未被吊销时返回 true
+    async fn revoke_refresh_token(&self, jti: &str) -> Result<bool>;
+
+    /// 登录会话管理方法
+    // 创建登录会话，会话 ID 即刷新令牌族 ID
+    async fn create_session(
+        &self,
+        id: &str,
+        user_id: i64,
+        user_agent: Option<&str>,
+        ip_address: Option<&str>,
+        remember_me: bool,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Session>;
+    // 通过ID获取会话
+    async fn get_session_by_id(&self, id: &str) -> Result<Option<Session>>;
+    // 列出用户未终止且未过期的会话
+    async fn list_user_sessions(&self, user_id: i64) -> Result<Vec<Session>>;
+    // 更新会话最后使用时间
+    async fn touch_session(&self, id: &str) -> Result<bool>;
+    // 终止会话并吊销其全部刷新令牌，仅当会话此前未被终止时返回 true
+    async fn revoke_session(&self, id: &str) -> Result<bool>;
+    // 终止用户的全部会话并吊销其全部刷新令牌，返回终止的会话数量
+    async fn revoke_user_sessions(&self, user_id: i64) -> Result<u64>;
+
+    /// 两步验证管理方法
+    // 获取用户的两步验证设置
+    async fn get_user_mfa(&self, user_id: i64) -> Result<Option<UserMfa>>;
+    // 保存新的 TOTP 密钥，覆盖尚未启用的设置，启用状态重置为未启用
+    async fn save_user_mfa_secret(&self, user_id: i64, secret: &str) -> Result<UserMfa>;
+    // 启用两步验证并替换全部恢复码，仅当此前未启用时返回 true
+    async fn enable_user_mfa(&self, user_id: i64, recovery_code_hashes: &[String]) -> Result<bool>;
+    // 关闭两步验证并删除密钥与恢复码
+    async fn delete_user_mfa(&self, user_id: i64) -> Result<bool>;
+    // 记录验证通过的时间步，仅当时间步大于上次记录时返回 true，防止验证码重放
+    async fn update_mfa_last_used_step(&self, user_id: i64, step: i64) -> Result<bool>;
+    // 列出用户未使用的恢复码
+    async fn list_unused_recovery_codes(&self, user_id: i64) -> Result<Vec<MfaRecoveryCode>>;
+    // 标记恢复码已使用，仅当此前未使用时返回 true
+    async fn use_recovery_code(&self, id: i64) -> Result<bool>;
+    // 替换用户的全部恢复码
+    async fn replace_recovery_codes(&self, user_id: i64, code_hashes: &[String]) -> Result<()>;
+
+    /// 个人访问令牌管理方法
+    // 创建个人访问令牌，只保存令牌哈希
+    async fn create_api_token(
+        &self,
+        user_id: i64,
+        name: &str,
+        token_prefix: &str,
+        token_hash: &str,
+        scopes: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Result<ApiToken>;
+    // 通过令牌哈希获取个人访问令牌
+    async fn get_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>>;
+    // 列出用户未吊销的个人访问令牌
+    async fn list_user_api_tokens(&self, user_id: i64) -> Result<Vec<ApiToken>>;
+    // 吊销属于指定用户的个人访问令牌，仅当此前未吊销时返回 true
+    async fn revoke_api_token(&self, user_id: i64, id: i64) -> Result<bool>;
+    // 更新个人访问令牌最后使用时间
+    async fn touch_api_token(&self, id: i64) -> Result<bool>;
+
+    /// 外部身份管理方法
+    // 通过身份提供方与用户标识获取外部身份
+    async fn get_user_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<UserIdentity>>;
+    // 将外部身份关联到用户
+    async fn create_user_identity(
+        &self,
+        user_id: i64,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<UserIdentity>;
+    // 记录通过外部身份登录，同时更新身份提供方返回的邮箱
+    async fn touch_user_identity(&self, id: i64, email: Option<&str>) -> Result<bool>;
+    // 列出指定身份提供方的全部外部身份
+    async fn list_user_identities(&self, provider: &str) -> Result<Vec<UserIdentity>>;
 
     /// 文件管理方法
     // 上传文件
@@ -61,10 +173,74 @@ pub trait Storage: Send + Sync {
         file_name: &str,
         file_size: &i64,
         file_type: &str,
+        content_hash: &str,
         user_id: i64,
     ) -> Result<File>;
     // 通过唯一 token 获取文件信息
     async fn get_file_by_token(&self, file_id: &str) -> Result<Option<File>>;
+    // 设置文件被作业与提交引用的次数
+    async fn set_file_citation_count(&self, submission_token: &str, count: i32) -> Result<bool>;
+    // 列出指定时间前上传且未被引用的文件
+    async fn list_unreferenced_files(
+        &self,
+        uploaded_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<File>>;
+    // 删除未被引用的文件记录，文件已被引用时不删除
+    async fn delete_unreferenced_file(&self, submission_token: &str) -> Result<bool>;
+    // 删除文件记录
+    async fn delete_file(&self, submission_token: &str) -> Result<bool>;
+    // 统计内容哈希相同的文件记录数
+    async fn count_files_by_content_hash(&self, content_hash: &str) -> Result<i64>;
+    // 列出引用次数与作业、提交中实际引用数不符的文件
+    async fn list_citation_mismatches(&self) -> Result<Vec<String>>;
+    // 标记开始删除内容，已有其他回收任务在删除且标记未过期时返回 false
+    async fn begin_content_deletion(
+        &self,
+        content_hash: &str,
+        stale_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool>;
+    // 清除内容删除标记
+    async fn finish_content_deletion(&self, content_hash: &str) -> Result<()>;
+    // 内容是否正在被删除，忽略过期的标记
+    async fn is_content_being_deleted(
+        &self,
+        content_hash: &str,
+        stale_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool>;
+
+    /// 可续传上传会话管理方法
+    // 创建上传会话，用户未过期的会话已有 max_open 个时不创建并返回 None
+    async fn create_upload_session(
+        &self,
+        id: &str,
+        user_id: i64,
+        upload: &CreateUploadSessionRequest,
+        chunk_size: i64,
+        max_open: i64,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<UploadSession>>;
+    // 通过ID获取上传会话
+    async fn get_upload_session(&self, id: &str) -> Result<Option<UploadSession>>;
+    // 已接收字节数仍为 offset 时更新为 new_offset，同时写入分块 ID 与哈希状态并顺延过期时间，
+    // 并发写入同一位置时只有一个成功
+    async fn advance_upload_session(
+        &self,
+        id: &str,
+        offset: i64,
+        new_offset: i64,
+        chunk_ids: &str,
+        hash_state: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool>;
+    // 删除上传会话
+    async fn delete_upload_session(&self, id: &str) -> Result<bool>;
+    // 列出指定时间前过期的上传会话
+    async fn list_expired_upload_sessions(
+        &self,
+        expired_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> Result<Vec<UploadSession>>;
 
     /// 班级管理方法
     // 创建班级
@@ -105,7 +281,7 @@ pub trait Storage: Send + Sync {
         update_data: UpdateClassUserRequest,
     ) -> Result<Option<ClassUser>>;
     // 列出班级用户
-    async fn list_class_users_with_pagination( 
+    async fn list_class_users_with_pagination(
         &self,
         class_id: i64,
         query: ClassUserQuery,
@@ -130,11 +306,72 @@ pub trait Storage: Send + Sync {
         user_id: i64,
     ) -> Result<(Option<Class>, Option<ClassUser>)>;
 
-    // 作业管理方法
+    /// 作业管理方法
+    // 创建作业
+    async fn create_homework(
+        &self,
+        class_id: i64,
+        created_by: i64,
+        homework: CreateHomeworkRequest,
+    ) -> Result<Homework>;
+    // 通过ID获取作业信息
+    async fn get_homework_by_id(&self, homework_id: i64) -> Result<Option<Homework>>;
+    // 列出作业
     async fn list_homeworks_with_pagination(
         &self,
         query: HomeworkListQuery,
     ) -> Result<HomeworkListResponse>;
+    // 更新作业信息
+    async fn update_homework(
+        &self,
+        homework_id: i64,
+        update: UpdateHomeworkRequest,
+    ) -> Result<Option<Homework>>;
+    // 删除作业
+    async fn delete_homework(&self, homework_id: i64) -> Result<bool>;
+    // 列出附件中包含指定文件的作业
+    async fn list_homeworks_by_attachment(&self, submission_token: &str) -> Result<Vec<Homework>>;
+
+    /// 作业提交管理方法
+    // 提交作业，同一学生重复提交时覆盖之前的提交；之前的提交已评分时不覆盖并返回 None
+    async fn create_submission(
+        &self,
+        homework_id: i64,
+        creator_id: i64,
+        submission: CreateSubmissionRequest,
+        is_late: bool,
+    ) -> Result<Option<Submission>>;
+    // 通过ID获取提交
+    async fn get_submission_by_id(&self, submission_id: i64) -> Result<Option<Submission>>;
+    // 获取学生对某作业的提交
+    async fn get_submission_by_homework_and_creator(
+        &self,
+        homework_id: i64,
+        creator_id: i64,
+    ) -> Result<Option<Submission>>;
+    // 列出作业的提交
+    async fn list_submissions_with_pagination(
+        &self,
+        query: SubmissionListQuery,
+    ) -> Result<SubmissionListResponse>;
+    // 列出附件中包含指定文件的提交
+    async fn list_submissions_by_attachment(
+        &self,
+        submission_token: &str,
+    ) -> Result<Vec<Submission>>;
+
+    /// 作业评分管理方法
+    // 为提交评分，每次评分都会新增记录以保留历史
+    async fn create_grade(
+        &self,
+        submission_id: i64,
+        grader_id: i64,
+        grade: CreateGradeRequest,
+    ) -> Result<Grade>;
+    // 获取提交的最新评分
+    async fn get_latest_grade_by_submission_id(&self, submission_id: i64) -> Result<Option<Grade>>;
+    // 列出提交的评分历史
+    async fn list_grades_by_submission_id(&self, submission_id: i64) -> Result<GradeListResponse>;
 }
 
 pub struct StorageFactory;
@@ -3,8 +3,13 @@ use once_cell::sync::Lazy;
 
 use crate::domain::AuthService;
 use crate::middlewares;
-use crate::models::auth::requests::LoginRequest;
-use crate::models::users::requests::CreateUserRequest;
+use crate::models::auth::requests::{
+    ChangePasswordRequest, CreateApiTokenRequest, DisableMfaRequest, ForgotPasswordRequest,
+    LoginRequest, MfaCodeRequest, MfaLoginRequest, MfaLoginSetupRequest, OidcCallbackQuery,
+    OidcLoginQuery, RegisterRequest, ResetPasswordRequest, UpdateProfileRequest,
+    VerifyEmailRequest,
+};
+use crate::utils::SafeIDI64;
 
 // 懒加载的全局 AuthService 实例
 static AUTH_SERVICE: Lazy<AuthService> = Lazy::new(AuthService::new_lazy);
@@ -16,17 +21,182 @@ pub async fn login(
     AUTH_SERVICE.login(user_data.into_inner(), &req).await
 }
 
+pub async fn mfa_login(
+    req: HttpRequest,
+    login_data: web::Json<MfaLoginRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.mfa_login(login_data.into_inner(), &req).await
+}
+
+pub async fn mfa_login_setup(
+    req: HttpRequest,
+    setup_data: web::Json<MfaLoginSetupRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .mfa_login_setup(setup_data.into_inner(), &req)
+        .await
+}
+
+pub async fn list_oidc_providers() -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.list_oidc_providers().await
+}
+
+pub async fn oidc_login(
+    req: HttpRequest,
+    provider: web::Path<String>,
+    query: web::Query<OidcLoginQuery>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .oidc_login(&req, &provider, query.into_inner())
+        .await
+}
+
+pub async fn oidc_callback(
+    req: HttpRequest,
+    provider: web::Path<String>,
+    query: web::Query<OidcCallbackQuery>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .oidc_callback(&req, &provider, query.into_inner())
+        .await
+}
+
 pub async fn refresh_token(request: HttpRequest) -> ActixResult<HttpResponse> {
     AUTH_SERVICE.refresh_token(&request).await
 }
 
 pub async fn register(
     req: HttpRequest,
-    user_data: web::Json<CreateUserRequest>,
+    user_data: web::Json<RegisterRequest>,
 ) -> ActixResult<HttpResponse> {
     AUTH_SERVICE.register(user_data.into_inner(), &req).await
 }
 
+pub async fn verify_email(
+    req: HttpRequest,
+    verify_data: web::Json<VerifyEmailRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .verify_email(verify_data.into_inner(), &req)
+        .await
+}
+
+pub async fn resend_verification_email(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.resend_verification_email(&request).await
+}
+
+pub async fn forgot_password(
+    req: HttpRequest,
+    forgot_data: web::Json<ForgotPasswordRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .forgot_password(forgot_data.into_inner(), &req)
+        .await
+}
+
+pub async fn reset_password(
+    req: HttpRequest,
+    reset_data: web::Json<ResetPasswordRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .reset_password(reset_data.into_inner(), &req)
+        .await
+}
+
+pub async fn update_profile(
+    req: HttpRequest,
+    profile_data: web::Json<UpdateProfileRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .update_profile(profile_data.into_inner(), &req)
+        .await
+}
+
+pub async fn change_password(
+    req: HttpRequest,
+    password_data: web::Json<ChangePasswordRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .change_password(password_data.into_inner(), &req)
+        .await
+}
+
+pub async fn mfa_status(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.mfa_status(&request).await
+}
+
+pub async fn mfa_setup(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.mfa_setup(&request).await
+}
+
+pub async fn mfa_enable(
+    req: HttpRequest,
+    code_data: web::Json<MfaCodeRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.mfa_enable(code_data.into_inner(), &req).await
+}
+
+pub async fn mfa_disable(
+    req: HttpRequest,
+    disable_data: web::Json<DisableMfaRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .mfa_disable(disable_data.into_inner(), &req)
+        .await
+}
+
+pub async fn regenerate_recovery_codes(
+    req: HttpRequest,
+    code_data: web::Json<MfaCodeRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .regenerate_recovery_codes(code_data.into_inner(), &req)
+        .await
+}
+
+pub async fn list_api_tokens(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.list_api_tokens(&request).await
+}
+
+pub async fn create_api_token(
+    req: HttpRequest,
+    token_data: web::Json<CreateApiTokenRequest>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE
+        .create_api_token(token_data.into_inner(), &req)
+        .await
+}
+
+pub async fn revoke_api_token(req: HttpRequest, token_id: SafeIDI64) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.revoke_api_token(&req, token_id.0).await
+}
+
+pub async fn upload_avatar(
+    req: HttpRequest,
+    payload: actix_multipart::Multipart,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.upload_avatar(&req, payload).await
+}
+
+pub async fn logout(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.logout(&request).await
+}
+
+pub async fn logout_all(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.logout_all(&request).await
+}
+
+pub async fn list_sessions(request: HttpRequest) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.list_sessions(&request).await
+}
+
+pub async fn revoke_session(
+    request: HttpRequest,
+    session_id: web::Path<String>,
+) -> ActixResult<HttpResponse> {
+    AUTH_SERVICE.revoke_session(&request, &session_id).await
+}
+
 pub async fn verify_token(request: HttpRequest) -> ActixResult<HttpResponse> {
     AUTH_SERVICE.verify_token(&request).await
 }
@@ -38,14 +208,674 @@ pub async fn get_user(request: HttpRequest) -> ActixResult<HttpResponse> {
 pub fn configure_auth_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/auth")
+            .wrap(middlewares::RateLimit::new("auth"))
             .route("/login", web::post().to(login))
+            .route("/login/mfa", web::post().to(mfa_login))
+            .route("/login/mfa/setup", web::post().to(mfa_login_setup))
+            .route("/oidc/providers", web::get().to(list_oidc_providers))
+            .route("/oidc/{provider}/login", web::get().to(oidc_login))
+            .route("/oidc/{provider}/callback", web::get().to(oidc_callback))
             .route("/register", web::post().to(register))
             .route("/refresh", web::post().to(refresh_token))
+            .route("/verify-email", web::post().to(verify_email))
+            .route("/forgot-password", web::post().to(forgot_password))
+            .route("/reset-password", web::post().to(reset_password))
             .service(
                 web::scope("")
                     .wrap(middlewares::RequireJWT)
+                    .route(
+                        "/verify-email/resend",
+                        web::post().to(resend_verification_email),
+                    )
+                    .route("/logout", web::post().to(logout))
+                    .route("/logout-all", web::post().to(logout_all))
+                    .route("/sessions", web::get().to(list_sessions))
+                    .route("/sessions/{session_id}", web::delete().to(revoke_session))
                     .route("/verify-token", web::get().to(verify_token))
-                    .route("/me", web::get().to(get_user)),
+                    .route("/me", web::get().to(get_user))
+                    .route("/me", web::patch().to(update_profile))
+                    .route("/me/password", web::post().to(change_password))
+                    .route("/me/avatar", web::post().to(upload_avatar))
+                    .route("/mfa", web::get().to(mfa_status))
+                    .route("/mfa/setup", web::post().to(mfa_setup))
+                    .route("/mfa/enable", web::post().to(mfa_enable))
+                    .route("/mfa/disable", web::post().to(mfa_disable))
+                    .route(
+                        "/mfa/recovery-codes",
+                        web::post().to(regenerate_recovery_codes),
+                    )
+                    .route("/tokens", web::get().to(list_api_tokens))
+                    .route("/tokens", web::post().to(create_api_token))
+                    .route("/tokens/{id}", web::delete().to(revoke_api_token)),
             ),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{Value, json};
+
+    use crate::models::ErrorCode;
+    use crate::models::users::entities::{User, UserRole};
+    use crate::models::users::requests::UpdateUserRequest;
+    use crate::system::app_config::AppConfig;
+    use crate::test_utils::{TEST_PASSWORD, TestContext, bearer};
+    use crate::utils::api_token;
+    use crate::utils::email_token::{EmailTokenPurpose, EmailTokenUtils};
+
+    fn login_request(username: &str, password: &str, spoofed_ip: &str) -> actix_http::Request {
+        let peer: SocketAddr = "203.0.113.9:40000".parse().unwrap();
+        test::TestRequest::post()
+            .uri("/api/v1/auth/login")
+            .peer_addr(peer)
+            .insert_header(("X-Forwarded-For", spoofed_ip))
+            .set_json(json!({ "username": username, "password": password }))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn account_is_locked_after_repeated_failures() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        ctx.create_user("alice", UserRole::User).await;
+        let max_attempts = AppConfig::get().auth.max_failed_attempts;
+
+        for _ in 0..max_attempts {
+            let resp = test::call_service(&app, login_request("alice", "wrong", "1.1.1.1")).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        // 锁定期内即使密码正确也拒绝
+        let resp = test::call_service(&app, login_request("alice", TEST_PASSWORD, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::LOCKED);
+    }
+
+    #[actix_web::test]
+    async fn forwarded_header_from_untrusted_peer_does_not_reset_ip_limit() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let max_attempts = AppConfig::get().auth.max_failed_attempts_per_ip;
+
+        // 每次伪造不同的 X-Forwarded-For，仍按直接连接的对端地址计数
+        for i in 0..max_attempts {
+            let spoofed_ip = format!("198.51.100.{i}");
+            let resp =
+                test::call_service(&app, login_request("nobody", "wrong", &spoofed_ip)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let resp =
+            test::call_service(&app, login_request("nobody", "wrong", "198.51.100.250")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    fn post_json(uri: &str, body: Value) -> actix_http::Request {
+        test::TestRequest::post()
+            .uri(uri)
+            .set_json(body)
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn registration_email_verification_flow() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/register",
+                json!({
+                    "username": "david",
+                    "email": "david@example.com",
+                    "password": TEST_PASSWORD,
+                    "profile": { "profile_name": "David" }
+                }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(ctx.mailer.sent().len(), 1);
+        let token = ctx.mailer.last_token_for("david@example.com").unwrap();
+
+        let user = ctx
+            .storage
+            .get_user_by_username("david")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!user.email_verified);
+
+        // 过期的令牌被拒绝
+        let expired = EmailTokenUtils::generate(
+            &user,
+            EmailTokenPurpose::VerifyEmail,
+            chrono::Duration::minutes(-10),
+        )
+        .unwrap();
+        let resp = test::call_service(
+            &app,
+            post_json("/api/v1/auth/verify-email", json!({ "token": expired })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // 密码重置令牌不能用于邮箱验证
+        let wrong_purpose = EmailTokenUtils::generate(
+            &user,
+            EmailTokenPurpose::ResetPassword,
+            chrono::Duration::minutes(10),
+        )
+        .unwrap();
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/verify-email",
+                json!({ "token": wrong_purpose }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = test::call_service(
+            &app,
+            post_json("/api/v1/auth/verify-email", json!({ "token": token })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let user = ctx.storage.get_user_by_id(user.id).await.unwrap().unwrap();
+        assert!(user.email_verified);
+
+        // 同一个令牌不能再次使用
+        let resp = test::call_service(
+            &app,
+            post_json("/api/v1/auth/verify-email", json!({ "token": token })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        let resp = test::call_service(&app, login_request("david", TEST_PASSWORD, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn password_reset_flow() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("erin", UserRole::User).await;
+        let new_password = "NewPassword456!";
+
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/forgot-password",
+                json!({ "email": "erin@example.com" }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 重置邮件在后台发送
+        let mut token = None;
+        for _ in 0..50 {
+            token = ctx.mailer.last_token_for("erin@example.com");
+            if token.is_some() {
+                break;
+            }
+            actix_web::rt::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let token = token.expect("password reset email was not sent");
+
+        let expired = EmailTokenUtils::generate(
+            &user,
+            EmailTokenPurpose::ResetPassword,
+            chrono::Duration::minutes(-10),
+        )
+        .unwrap();
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/reset-password",
+                json!({ "token": expired, "new_password": new_password }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/reset-password",
+                json!({ "token": token, "new_password": new_password }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 密码已修改，原令牌失效
+        let resp = test::call_service(
+            &app,
+            post_json(
+                "/api/v1/auth/reset-password",
+                json!({ "token": token, "new_password": "AnotherPassword789!" }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = test::call_service(&app, login_request("erin", TEST_PASSWORD, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, login_request("erin", new_password, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // 登录成功，返回 access token 与 refresh token cookie
+    async fn login(
+        app: &impl actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+            Error = actix_web::Error,
+        >,
+        username: &str,
+    ) -> (String, String) {
+        let resp = test::call_service(app, login_request(username, TEST_PASSWORD, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let refresh_token = refresh_cookie(&resp).unwrap();
+        let body: Value = test::read_body_json(resp).await;
+        let access_token = body["data"]["access_token"].as_str().unwrap().to_string();
+        (access_token, refresh_token)
+    }
+
+    fn refresh_cookie<B>(resp: &actix_web::dev::ServiceResponse<B>) -> Option<String> {
+        resp.response()
+            .cookies()
+            .find(|cookie| cookie.name() == "refresh_token")
+            .map(|cookie| cookie.value().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn refresh_request(refresh_token: &str) -> actix_http::Request {
+        test::TestRequest::post()
+            .uri("/api/v1/auth/refresh")
+            .cookie(actix_web::cookie::Cookie::new(
+                "refresh_token",
+                refresh_token,
+            ))
+            .to_request()
+    }
+
+    fn me_request(access_token: &str) -> actix_http::Request {
+        test::TestRequest::get()
+            .uri("/api/v1/auth/me")
+            .insert_header(("Authorization", format!("Bearer {access_token}")))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn refresh_token_rotation_and_reuse_detection() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        ctx.create_user("frank", UserRole::User).await;
+        let (access_token, first) = login(&app, "frank").await;
+
+        // 刷新后签发新的 refresh token，旧令牌随即吊销
+        let resp = test::call_service(&app, refresh_request(&first)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let second = refresh_cookie(&resp).unwrap();
+        assert_ne!(first, second);
+        let body: Value = test::read_body_json(resp).await;
+        let refreshed_access_token = body["data"]["access_token"].as_str().unwrap().to_string();
+        let resp = test::call_service(&app, me_request(&refreshed_access_token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 旧令牌再次出现视为被盗用，整个令牌族被吊销
+        let resp = test::call_service(&app, refresh_request(&first)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, refresh_request(&second)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // 该会话签发的 access token 同样失效
+        for token in [&access_token, &refreshed_access_token] {
+            let resp = test::call_service(&app, me_request(token)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[actix_web::test]
+    async fn logout_all_survives_cache_eviction() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        ctx.create_user("grace", UserRole::User).await;
+        let (first, first_refresh) = login(&app, "grace").await;
+        let (second, _) = login(&app, "grace").await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/auth/logout-all")
+            .insert_header(("Authorization", format!("Bearer {first}")))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 缓存中的吊销记录丢失后，仍按存储中的会话状态拒绝
+        ctx.cache.invalidate_all().await;
+        for token in [&first, &second] {
+            let resp = test::call_service(&app, me_request(token)).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+        let resp = test::call_service(&app, refresh_request(&first_refresh)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // 同一秒内重新登录得到的令牌不受影响
+        let (access_token, _) = login(&app, "grace").await;
+        let resp = test::call_service(&app, me_request(&access_token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    fn sessions_request(access_token: &str) -> actix_http::Request {
+        test::TestRequest::get()
+            .uri("/api/v1/auth/sessions")
+            .insert_header(("Authorization", format!("Bearer {access_token}")))
+            .to_request()
+    }
+
+    fn revoke_session_request(access_token: &str, session_id: &str) -> actix_http::Request {
+        test::TestRequest::delete()
+            .uri(&format!("/api/v1/auth/sessions/{session_id}"))
+            .insert_header(("Authorization", format!("Bearer {access_token}")))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn sessions_are_listed_and_revoked_individually() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        ctx.create_user("olivia", UserRole::User).await;
+        let other = ctx.create_user("peggy", UserRole::User).await;
+        let (first, _) = login(&app, "olivia").await;
+        let (second, second_refresh) = login(&app, "olivia").await;
+
+        // 两个会话都列出，只有发起请求的会话标记为当前会话
+        let resp = test::call_service(&app, sessions_request(&first)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let items = body["data"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items.iter().filter(|s| s["is_current"] == true).count(), 1);
+        let second_session = items.iter().find(|s| s["is_current"] == false).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        // 其他用户不能终止不属于自己的会话
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/auth/sessions/{second_session}"))
+            .insert_header(bearer(&other))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = test::call_service(&app, revoke_session_request(&first, &second_session)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, revoke_session_request(&first, &second_session)).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        // 被终止会话的 access token 与 refresh token 立即失效，其他会话不受影响
+        let resp = test::call_service(&app, me_request(&second)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, refresh_request(&second_refresh)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, sessions_request(&first)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let items = body["data"]["items"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["is_current"], true);
+    }
+
+    #[actix_web::test]
+    async fn wrong_current_password_counts_towards_lockout() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("heidi", UserRole::User).await;
+        let max_attempts = AppConfig::get().auth.max_failed_attempts;
+        let change_password = |current: &str| {
+            test::TestRequest::post()
+                .uri("/api/v1/auth/me/password")
+                .insert_header(bearer(&user))
+                .set_json(json!({
+                    "current_password": current,
+                    "new_password": "NewPassword456!",
+                }))
+                .to_request()
+        };
+
+        for _ in 0..max_attempts {
+            let resp = test::call_service(&app, change_password("wrong")).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
+        // 达到上限后账号被锁定，修改密码与登录都被拒绝
+        let resp = test::call_service(&app, change_password(TEST_PASSWORD)).await;
+        assert_eq!(resp.status(), StatusCode::LOCKED);
+        let resp = test::call_service(&app, login_request("heidi", TEST_PASSWORD, "1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::LOCKED);
+    }
+
+    #[actix_web::test]
+    async fn profile_update_ignores_avatar_url() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("ivan", UserRole::User).await;
+
+        let req = test::TestRequest::patch()
+            .uri("/api/v1/auth/me")
+            .insert_header(bearer(&user))
+            .set_json(json!({
+                "profile_name": "Ivan",
+                "avatar_url": "https://evil.example.com/track.png",
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let updated = ctx.storage.get_user_by_id(user.id).await.unwrap().unwrap();
+        assert_eq!(updated.profile.profile_name, "Ivan");
+        assert_eq!(updated.profile.avatar_url.unwrap_or_default(), "");
+    }
+
+    fn avatar_request(user: &User) -> actix_http::Request {
+        let mut png = Vec::new();
+        image::RgbImage::new(8, 8)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let boundary = "avatar-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(&png);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        test::TestRequest::post()
+            .uri("/api/v1/auth/me/avatar")
+            .insert_header(bearer(user))
+            .insert_header((
+                "Content-Type",
+                format!("multipart/form-data; boundary={boundary}"),
+            ))
+            .set_payload(body)
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn avatar_upload_only_replaces_avatar_url() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("nina", UserRole::User).await;
+
+        // 先发起一次请求让用户信息进入缓存，再直接修改存储中的资料
+        let resp = test::call_service(
+            &app,
+            test::TestRequest::get()
+                .uri("/api/v1/auth/me")
+                .insert_header(bearer(&user))
+                .to_request(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let mut profile = user.profile.clone();
+        profile.profile_name = "Nina Renamed".to_string();
+        ctx.storage
+            .update_user(
+                user.id,
+                UpdateUserRequest {
+                    email: None,
+                    password: None,
+                    role: None,
+                    status: None,
+                    profile: Some(profile),
+                },
+            )
+            .await
+            .unwrap();
+
+        let resp = test::call_service(&app, avatar_request(&user)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let updated = ctx.storage.get_user_by_id(user.id).await.unwrap().unwrap();
+        assert_eq!(updated.profile.profile_name, "Nina Renamed");
+        let avatar_url = updated.profile.avatar_url.unwrap();
+        assert!(avatar_url.starts_with(crate::utils::avatar::AVATAR_URL_PREFIX));
+
+        let resp =
+            test::call_service(&app, test::TestRequest::get().uri(&avatar_url).to_request()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // 使用登录获得的 JWT 创建个人访问令牌，返回令牌 ID 与明文
+    async fn create_api_token(
+        app: &impl actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+            Error = actix_web::Error,
+        >,
+        user: &User,
+        scopes: &[&str],
+    ) -> (i64, String) {
+        let req = test::TestRequest::post()
+            .uri("/api/v1/auth/tokens")
+            .insert_header(bearer(user))
+            .set_json(json!({ "name": "script", "scopes": scopes }))
+            .to_request();
+        let resp = test::call_service(app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let id = body["data"]["id"].as_i64().unwrap();
+        let token = body["data"]["token"].as_str().unwrap().to_string();
+        (id, token)
+    }
+
+    fn rename_request(token: &str) -> actix_http::Request {
+        test::TestRequest::patch()
+            .uri("/api/v1/auth/me")
+            .insert_header(("Authorization", format!("Bearer {token}")))
+            .set_json(json!({ "profile_name": "Renamed" }))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn api_token_is_recognised_by_prefix() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("judy", UserRole::User).await;
+        let (_, token) = create_api_token(&app, &user, &[]).await;
+        assert!(token.starts_with(api_token::API_TOKEN_PREFIX));
+
+        let resp = test::call_service(&app, me_request(&token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 去掉前缀后按 JWT 校验，不会被当作个人访问令牌
+        let unprefixed = token.trim_start_matches(api_token::API_TOKEN_PREFIX);
+        let resp = test::call_service(&app, me_request(unprefixed)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = test::call_service(&app, me_request("hwpat_unknown")).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn revoked_and_expired_api_tokens_are_rejected() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("kevin", UserRole::User).await;
+
+        let (id, token) = create_api_token(&app, &user, &[]).await;
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/auth/tokens/{id}"))
+            .insert_header(bearer(&user))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, me_request(&token)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let (token, prefix, hash) = api_token::generate();
+        let expired_at = chrono::Utc::now() - chrono::Duration::minutes(1);
+        ctx.storage
+            .create_api_token(user.id, "expired", &prefix, &hash, "", Some(expired_at))
+            .await
+            .unwrap();
+        let resp = test::call_service(&app, me_request(&token)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn read_only_api_token_cannot_write() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("laura", UserRole::User).await;
+
+        let (_, read_token) = create_api_token(&app, &user, &["read"]).await;
+        let resp = test::call_service(&app, me_request(&read_token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, rename_request(&read_token)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["code"], ErrorCode::ApiTokenScopeDenied as i32);
+
+        let (_, write_token) = create_api_token(&app, &user, &["write"]).await;
+        let resp = test::call_service(&app, rename_request(&write_token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn api_tokens_cannot_manage_tokens() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("mallory", UserRole::User).await;
+        let (id, token) = create_api_token(&app, &user, &["write"]).await;
+        let authorization = ("Authorization", format!("Bearer {token}"));
+
+        let requests = [
+            test::TestRequest::get().uri("/api/v1/auth/tokens"),
+            test::TestRequest::post()
+                .uri("/api/v1/auth/tokens")
+                .set_json(json!({ "name": "renewed" })),
+            test::TestRequest::delete().uri(&format!("/api/v1/auth/tokens/{id}")),
+        ];
+        for req in requests {
+            let req = req.insert_header(authorization.clone()).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            let body: Value = test::read_body_json(resp).await;
+            assert_eq!(body["code"], ErrorCode::ApiTokenNotAllowed as i32);
+        }
+
+        // 令牌未被吊销，仍可正常使用
+        let resp = test::call_service(&app, me_request(&token)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -3,6 +3,9 @@ use once_cell::sync::Lazy;
 
 use crate::domain::FileService;
 use crate::middlewares;
+use crate::models::files::requests::{
+    CreateUploadSessionRequest, SignedDownloadQuery, UploadChunkQuery,
+};
 
 // 懒加载的全局 FileService 实例
 static FILE_SERVICE: Lazy<FileService> = Lazy::new(FileService::new_lazy);
@@ -22,13 +25,557 @@ pub async fn handle_download(
         .handle_download(&request, file_token.into_inner())
         .await
 }
+
+pub async fn handle_signed_download(
+    request: HttpRequest,
+    file_token: web::Path<String>,
+    query: web::Query<SignedDownloadQuery>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .handle_signed_download(&request, file_token.into_inner(), query.into_inner().token)
+        .await
+}
+
+pub async fn create_download_url(
+    request: HttpRequest,
+    file_token: web::Path<String>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .create_download_url(&request, file_token.into_inner())
+        .await
+}
+
+pub async fn create_upload_session(
+    request: HttpRequest,
+    upload: web::Json<CreateUploadSessionRequest>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .create_upload_session(&request, upload.into_inner())
+        .await
+}
+
+pub async fn get_upload_session(
+    request: HttpRequest,
+    upload_id: web::Path<String>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .get_upload_session(&request, upload_id.into_inner())
+        .await
+}
+
+pub async fn upload_chunk(
+    request: HttpRequest,
+    upload_id: web::Path<String>,
+    query: web::Query<UploadChunkQuery>,
+    payload: web::Payload,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .upload_chunk(
+            &request,
+            upload_id.into_inner(),
+            query.into_inner().offset,
+            payload,
+        )
+        .await
+}
+
+pub async fn complete_upload(
+    request: HttpRequest,
+    upload_id: web::Path<String>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .complete_upload(&request, upload_id.into_inner())
+        .await
+}
+
+pub async fn cancel_upload(
+    request: HttpRequest,
+    upload_id: web::Path<String>,
+) -> ActixResult<HttpResponse> {
+    FILE_SERVICE
+        .cancel_upload(&request, upload_id.into_inner())
+        .await
+}
+
+pub async fn handle_avatar(file_name: web::Path<String>) -> ActixResult<HttpResponse> {
+    FILE_SERVICE.handle_avatar(file_name.into_inner()).await
+}
+
 // 配置路由
 pub fn configure_file_routes(cfg: &mut web::ServiceConfig) {
+    // 签名链接由令牌授权，需注册在要求登录的 /api/v1/files 之前
+    cfg.service(
+        web::scope("/api/v1/files/signed")
+            .wrap(middlewares::RateLimit::new("files"))
+            .wrap(middleware::Compress::default())
+            .route("/{file_token}", web::get().to(handle_signed_download)),
+    );
     cfg.service(
         web::scope("/api/v1/files")
+            .wrap(middlewares::RateLimit::new("files"))
             .wrap(middlewares::RequireJWT)
             .wrap(middleware::Compress::default())
             .route("/upload", web::post().to(handle_upload))
-            .route("/download/{file_token}", web::get().to(handle_download)),
+            // 可续传上传，需注册在 /{file_token}/download-url 之前
+            .route("/uploads", web::post().to(create_upload_session))
+            .service(
+                web::resource("/uploads/{upload_id}")
+                    .route(web::get().to(get_upload_session))
+                    .route(web::patch().to(upload_chunk))
+                    .route(web::delete().to(cancel_upload)),
+            )
+            .route(
+                "/uploads/{upload_id}/complete",
+                web::post().to(complete_upload),
+            )
+            .route("/download/{file_token}", web::get().to(handle_download))
+            .route(
+                "/{file_token}/download-url",
+                web::post().to(create_download_url),
+            ),
     );
+    // 头像公开访问，无需登录
+    cfg.service(web::scope("/api/v1/avatars").route("/{file_name}", web::get().to(handle_avatar)));
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use futures_util::StreamExt;
+    use serde_json::{Value, json};
+    use sha2::{Digest, Sha256};
+
+    use crate::blob_store::MIN_COMPOSE_PART_SIZE;
+    use crate::models::ErrorCode;
+
+    use crate::models::class_users::entities::ClassUserRole;
+    use crate::models::files::entities::File;
+    use crate::models::homeworks::requests::CreateHomeworkRequest;
+    use crate::models::submissions::requests::CreateSubmissionRequest;
+    use crate::models::users::entities::{User, UserRole};
+    use crate::system::app_config::AppConfig;
+    use crate::test_utils::{TestContext, bearer};
+    use crate::utils::download_token::{DownloadTokenData, DownloadTokenUtils};
+    use crate::utils::purpose_token::{PurposeToken, TokenPurpose};
+
+    async fn upload(ctx: &TestContext, owner: &User, token: &str) -> File {
+        upload_typed(ctx, owner, token, "text/plain").await
+    }
+
+    async fn upload_typed(ctx: &TestContext, owner: &User, token: &str, file_type: &str) -> File {
+        let content_hash = uuid::Uuid::new_v4().simple().to_string();
+        ctx.blob_store
+            .put(&content_hash, "content".into())
+            .await
+            .unwrap();
+        ctx.storage
+            .upload_file(token, "a.txt", &7, file_type, &content_hash, owner.id)
+            .await
+            .unwrap()
+    }
+
+    fn homework(attachments: Vec<String>) -> CreateHomeworkRequest {
+        CreateHomeworkRequest {
+            title: "Homework 1".to_string(),
+            content: None,
+            attachments,
+            max_score: 100.0,
+            deadline: None,
+            allow_late_submission: false,
+        }
+    }
+
+    #[actix_web::test]
+    async fn attachments_are_readable_by_uploader_admin_class_and_graders() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let teacher = ctx.create_user("teacher", UserRole::Teacher).await;
+        let student = ctx.create_user("student", UserRole::User).await;
+        let classmate = ctx.create_user("classmate", UserRole::User).await;
+        let representative = ctx.create_user("representative", UserRole::User).await;
+        let outsider = ctx.create_user("outsider", UserRole::User).await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let class = ctx.create_class(&teacher, &[&student, &classmate]).await;
+        ctx.storage
+            .join_class(
+                representative.id,
+                class.id,
+                ClassUserRole::ClassRepresentative,
+            )
+            .await
+            .unwrap();
+
+        let handout = upload(&ctx, &teacher, "file-handout").await;
+        let answer = upload(&ctx, &student, "file-answer").await;
+        let homework = ctx
+            .storage
+            .create_homework(
+                class.id,
+                teacher.id,
+                homework(vec![handout.submission_token.clone()]),
+            )
+            .await
+            .unwrap();
+        ctx.storage
+            .create_submission(
+                homework.id,
+                student.id,
+                CreateSubmissionRequest {
+                    content: "answer".to_string(),
+                    attachments: vec![answer.submission_token.clone()],
+                },
+                false,
+            )
+            .await
+            .unwrap();
+
+        let download = |file: &File, user: &User| {
+            test::TestRequest::get()
+                .uri(&format!("/api/v1/files/download/{}", file.submission_token))
+                .insert_header(bearer(user))
+                .to_request()
+        };
+        let cases = [
+            // 作业附件：班级成员可见
+            (&handout, &classmate, StatusCode::OK),
+            (&handout, &outsider, StatusCode::FORBIDDEN),
+            // 提交附件：上传者、管理员与可以评分的用户可见，其他同学不可见
+            (&answer, &student, StatusCode::OK),
+            (&answer, &admin, StatusCode::OK),
+            (&answer, &teacher, StatusCode::OK),
+            (&answer, &representative, StatusCode::OK),
+            (&answer, &classmate, StatusCode::FORBIDDEN),
+            (&answer, &outsider, StatusCode::FORBIDDEN),
+        ];
+        for (file, user, expected) in cases {
+            let resp = test::call_service(&app, download(file, user)).await;
+            assert_eq!(
+                resp.status(),
+                expected,
+                "{} reading {}",
+                user.username,
+                file.submission_token
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn signed_links_expire_and_only_open_their_own_file() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let owner = ctx.create_user("owner", UserRole::User).await;
+        let file = upload(&ctx, &owner, "file-signed").await;
+        let other = upload(&ctx, &owner, "file-other").await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/api/v1/files/{}/download-url",
+                file.submission_token
+            ))
+            .insert_header(bearer(&owner))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        let url = body["data"]["url"].as_str().unwrap().to_string();
+
+        let get = |uri: String| test::TestRequest::get().uri(&uri).to_request();
+
+        // 签发的链接无需登录即可下载
+        let resp = test::call_service(&app, get(url.clone())).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(test::read_body(resp).await, "content");
+
+        // 链接只能下载签发时指定的文件
+        let token = url.split("token=").nth(1).unwrap();
+        let resp = test::call_service(
+            &app,
+            get(format!(
+                "/api/v1/files/signed/{}?token={token}",
+                other.submission_token
+            )),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // 过期的链接
+        let expired = DownloadTokenUtils::generate(
+            owner.id,
+            &file.submission_token,
+            chrono::Duration::seconds(-60),
+        )
+        .unwrap();
+        let resp = test::call_service(
+            &app,
+            get(format!(
+                "/api/v1/files/signed/{}?token={expired}",
+                file.submission_token
+            )),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // 其他用途（aud）的令牌即使内容相同也不能用于下载
+        let foreign = PurposeToken::generate(
+            TokenPurpose::ResetPassword,
+            owner.id,
+            DownloadTokenData {
+                file: file.submission_token.clone(),
+            },
+            chrono::Duration::minutes(5),
+        )
+        .unwrap();
+        let resp = test::call_service(
+            &app,
+            get(format!(
+                "/api/v1/files/signed/{}?token={foreign}",
+                file.submission_token
+            )),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn pdf_and_images_open_inline_other_types_download() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let owner = ctx.create_user("viewer", UserRole::User).await;
+
+        for (token, file_type, disposition) in [
+            ("file-pdf", "application/pdf", "inline"),
+            ("file-png", "image/png", "inline"),
+            ("file-html", "text/html", "attachment"),
+            ("file-svg", "image/svg+xml", "attachment"),
+            ("file-zip", "application/zip", "attachment"),
+        ] {
+            upload_typed(&ctx, &owner, token, file_type).await;
+            let req = test::TestRequest::get()
+                .uri(&format!("/api/v1/files/download/{token}"))
+                .insert_header(bearer(&owner))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let headers = resp.headers();
+            assert!(
+                headers
+                    .get("content-disposition")
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .starts_with(disposition),
+                "{file_type}"
+            );
+            assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+        }
+    }
+
+    async fn call(
+        app: &impl actix_web::dev::Service<
+            actix_http::Request,
+            Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+            Error = actix_web::Error,
+        >,
+        req: test::TestRequest,
+        user: &User,
+    ) -> (StatusCode, Value) {
+        let resp = test::call_service(app, req.insert_header(bearer(user)).to_request()).await;
+        let status = resp.status();
+        let body = test::read_body(resp).await;
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn create_session(size: usize) -> test::TestRequest {
+        test::TestRequest::post()
+            .uri("/api/v1/files/uploads")
+            .set_json(json!({
+                "file_name": "report.pdf",
+                "file_type": "application/pdf",
+                "size": size,
+            }))
+    }
+
+    fn send_chunk(upload_id: &str, offset: usize, data: &[u8]) -> test::TestRequest {
+        test::TestRequest::patch()
+            .uri(&format!(
+                "/api/v1/files/uploads/{upload_id}?offset={offset}"
+            ))
+            .set_payload(data.to_vec())
+    }
+
+    fn complete(upload_id: &str) -> test::TestRequest {
+        test::TestRequest::post().uri(&format!("/api/v1/files/uploads/{upload_id}/complete"))
+    }
+
+    // 合并后的文件内容与记录的内容哈希一致
+    async fn assert_stored(ctx: &TestContext, submission_token: &str, data: &[u8]) {
+        let file = ctx
+            .storage
+            .get_file_by_token(submission_token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            file.content_hash.as_deref(),
+            Some(format!("{:x}", Sha256::digest(data)).as_str())
+        );
+        let mut stream = ctx
+            .blob_store
+            .get(file.blob_key(), None)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut stored = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            stored.extend_from_slice(&chunk.unwrap());
+        }
+        assert_eq!(stored, data);
+    }
+
+    #[actix_web::test]
+    async fn resumable_upload_checks_offsets_and_chunk_sizes() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("uploader", UserRole::User).await;
+
+        let (status, _) = call(&app, create_session(0), &user).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = call(&app, create_session(10), &user).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let chunk_size = AppConfig::get()
+            .upload
+            .chunk_size
+            .max(MIN_COMPOSE_PART_SIZE);
+        let data: Vec<u8> = (0..chunk_size + 100).map(|i| (i % 251) as u8).collect();
+        let (status, body) = call(&app, create_session(data.len()), &user).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["data"]["chunk_size"], chunk_size);
+        let upload_id = body["data"]["upload_id"].as_str().unwrap().to_string();
+
+        // 起始位置必须等于已接收的字节数
+        let (status, body) = call(
+            &app,
+            send_chunk(&upload_id, 5, &data[5..chunk_size + 5]),
+            &user,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], ErrorCode::UploadOffsetMismatch as i32);
+        assert_eq!(body["data"]["offset"], 0);
+
+        // 除最后一块外大小必须恰好为 chunk_size
+        let (status, body) = call(&app, send_chunk(&upload_id, 0, &data[..10]), &user).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], ErrorCode::UploadChunkInvalid as i32);
+
+        let (status, body) = call(&app, complete(&upload_id), &user).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], ErrorCode::UploadIncomplete as i32);
+
+        let (status, body) =
+            call(&app, send_chunk(&upload_id, 0, &data[..chunk_size]), &user).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["offset"], chunk_size);
+        let (status, _) = call(
+            &app,
+            send_chunk(&upload_id, chunk_size, &data[chunk_size..]),
+            &user,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = call(&app, complete(&upload_id), &user).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_stored(
+            &ctx,
+            body["data"]["submission_token"].as_str().unwrap(),
+            &data,
+        )
+        .await;
+
+        // 完成后会话随即删除
+        let req = test::TestRequest::get().uri(&format!("/api/v1/files/uploads/{upload_id}"));
+        let (status, _) = call(&app, req, &user).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn concurrent_chunks_at_same_offset_advance_once() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("racer", UserRole::User).await;
+
+        let (_, body) = call(&app, create_session(100), &user).await;
+        let upload_id = body["data"]["upload_id"].as_str().unwrap().to_string();
+
+        // 存储层按已接收字节数比较并更新，同一位置只能前进一次
+        assert!(
+            ctx.storage
+                .advance_upload_session(&upload_id, 0, 100, "a", "", chrono::Utc::now())
+                .await
+                .unwrap()
+        );
+        assert!(
+            !ctx.storage
+                .advance_upload_session(&upload_id, 0, 100, "b", "", chrono::Utc::now())
+                .await
+                .unwrap()
+        );
+
+        // 两个请求同时发送不同内容的同一分块，只有一个生效，合并后的内容与哈希来自同一个请求
+        let (_, body) = call(&app, create_session(100), &user).await;
+        let upload_id = body["data"]["upload_id"].as_str().unwrap().to_string();
+        let (first, second) = (vec![1u8; 100], vec![2u8; 100]);
+        let ((first_status, _), (second_status, _)) = futures_util::join!(
+            call(&app, send_chunk(&upload_id, 0, &first), &user),
+            call(&app, send_chunk(&upload_id, 0, &second), &user),
+        );
+        let mut statuses = [first_status, second_status];
+        statuses.sort();
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::CONFLICT]);
+
+        let (status, body) = call(&app, complete(&upload_id), &user).await;
+        assert_eq!(status, StatusCode::OK);
+        let winner = if first_status == StatusCode::OK {
+            &first
+        } else {
+            &second
+        };
+        assert_stored(
+            &ctx,
+            body["data"]["submission_token"].as_str().unwrap(),
+            winner,
+        )
+        .await;
+    }
+
+    #[actix_web::test]
+    async fn open_upload_sessions_are_limited_per_user() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let user = ctx.create_user("hoarder", UserRole::User).await;
+        let other = ctx.create_user("neighbour", UserRole::User).await;
+
+        let mut upload_ids = Vec::new();
+        for _ in 0..AppConfig::get().upload.max_open_sessions {
+            let (status, body) = call(&app, create_session(100), &user).await;
+            assert_eq!(status, StatusCode::CREATED);
+            upload_ids.push(body["data"]["upload_id"].as_str().unwrap().to_string());
+        }
+        let (status, body) = call(&app, create_session(100), &user).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["code"], ErrorCode::UploadSessionLimitExceeded as i32);
+
+        // 上限按用户计算，取消一个会话后可以重新创建
+        let (status, _) = call(&app, create_session(100), &other).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let req =
+            test::TestRequest::delete().uri(&format!("/api/v1/files/uploads/{}", upload_ids[0]));
+        let (status, _) = call(&app, req, &user).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, create_session(100), &user).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
 }
@@ -0,0 +1,165 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use once_cell::sync::Lazy;
+
+use crate::domain::GradeService;
+use crate::middlewares;
+use crate::models::grades::requests::CreateGradeRequest;
+use crate::utils::SafeSubmissionIdI64;
+
+// 懒加载的全局 GradeService 实例
+static GRADE_SERVICE: Lazy<GradeService> = Lazy::new(GradeService::new_lazy);
+
+// HTTP处理程序
+pub async fn list_grades(req: HttpRequest, path: SafeSubmissionIdI64) -> ActixResult<HttpResponse> {
+    GRADE_SERVICE.list_grades(&req, path.0).await
+}
+
+pub async fn create_grade(
+    req: HttpRequest,
+    path: SafeSubmissionIdI64,
+    grade_data: web::Json<CreateGradeRequest>,
+) -> ActixResult<HttpResponse> {
+    GRADE_SERVICE
+        .create_grade(&req, path.0, grade_data.into_inner())
+        .await
+}
+
+pub async fn get_latest_grade(
+    req: HttpRequest,
+    path: SafeSubmissionIdI64,
+) -> ActixResult<HttpResponse> {
+    GRADE_SERVICE.get_latest_grade(&req, path.0).await
+}
+
+// 配置路由
+pub fn configure_grades_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/submissions/{submission_id}/grades")
+            .wrap(middlewares::RequireJWT)
+            // 获取评分历史，提交者本人或评分人可查看
+            .route("", web::get().to(list_grades))
+            // 评分（重新评分会保留历史），班级教师、管理员或允许评分的课代表
+            .route("", web::post().to(create_grade))
+            // 获取最新评分及评语
+            .route("/latest", web::get().to(get_latest_grade)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{Value, json};
+
+    use crate::models::homeworks::requests::CreateHomeworkRequest;
+    use crate::models::submissions::requests::CreateSubmissionRequest;
+    use crate::models::users::entities::{User, UserRole};
+    use crate::test_utils::{TestContext, bearer};
+
+    fn grade_request(submission_id: i64, grader: &User, body: Value) -> actix_http::Request {
+        test::TestRequest::post()
+            .uri(&format!("/api/v1/submissions/{submission_id}/grades"))
+            .insert_header(bearer(grader))
+            .set_json(body)
+            .to_request()
+    }
+
+    fn get_request(uri: &str, user: &User) -> actix_http::Request {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(bearer(user))
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn regrading_keeps_history() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let teacher = ctx.create_user("teacher", UserRole::Teacher).await;
+        let student = ctx.create_user("student", UserRole::User).await;
+        let classmate = ctx.create_user("classmate", UserRole::User).await;
+        let class = ctx.create_class(&teacher, &[&student, &classmate]).await;
+        let homework = ctx
+            .storage
+            .create_homework(
+                class.id,
+                teacher.id,
+                CreateHomeworkRequest {
+                    title: "Homework 1".to_string(),
+                    content: None,
+                    attachments: vec![],
+                    max_score: 100.0,
+                    deadline: None,
+                    allow_late_submission: false,
+                },
+            )
+            .await
+            .unwrap();
+        let submission = ctx
+            .storage
+            .create_submission(
+                homework.id,
+                student.id,
+                CreateSubmissionRequest {
+                    content: "answer".to_string(),
+                    attachments: vec![],
+                },
+                false,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let history_uri = format!("/api/v1/submissions/{}/grades", submission.id);
+
+        // 超出作业最高分的评分被拒绝，不写入历史
+        let resp = test::call_service(
+            &app,
+            grade_request(submission.id, &teacher, json!({ "score": 101.0 })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = test::call_service(
+            &app,
+            grade_request(submission.id, &teacher, json!({ "score": 70.0 })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let resp = test::call_service(
+            &app,
+            grade_request(
+                submission.id,
+                &teacher,
+                json!({ "score": 85.0, "comment": "Regraded after appeal" }),
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        // 最新评分为重新评分的结果，历史按时间倒序保留全部评分
+        let resp = test::call_service(
+            &app,
+            get_request(&format!("{history_uri}/latest"), &student),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["score"], 85.0);
+        assert_eq!(body["data"]["comment"], "Regraded after appeal");
+
+        let resp = test::call_service(&app, get_request(&history_uri, &student)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        let scores: Vec<f64> = body["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|grade| grade["score"].as_f64().unwrap())
+            .collect();
+        assert_eq!(scores, vec![85.0, 70.0]);
+
+        // 同班其他学生不能查看评分历史
+        let resp = test::call_service(&app, get_request(&history_uri, &classmate)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -3,7 +3,11 @@ use once_cell::sync::Lazy;
 
 use crate::domain::HomeworkService;
 use crate::middlewares;
-use crate::models::homeworks::requests::HomeworkListQuery;
+use crate::models::class_users::entities::ClassUserRole;
+use crate::models::homeworks::requests::{
+    CreateHomeworkRequest, HomeworkListParams, UpdateHomeworkRequest,
+};
+use crate::utils::{SafeClassIdI64, SafeHomeworkIdI64};
 
 // 懒加载的全局 HomeworkService 实例
 static HOMEWORK_SERVICE: Lazy<HomeworkService> = Lazy::new(HomeworkService::new_lazy);
@@ -11,18 +15,253 @@ static HOMEWORK_SERVICE: Lazy<HomeworkService> = Lazy::new(HomeworkService::new_
 // HTTP处理程序
 pub async fn list_homeworks(
     req: HttpRequest,
-    query: web::Query<HomeworkListQuery>,
+    query: web::Query<HomeworkListParams>,
 ) -> ActixResult<HttpResponse> {
     HOMEWORK_SERVICE
         .list_homeworks(&req, query.into_inner())
         .await
 }
 
+pub async fn list_class_homeworks(
+    req: HttpRequest,
+    path: SafeClassIdI64,
+    query: web::Query<HomeworkListParams>,
+) -> ActixResult<HttpResponse> {
+    HOMEWORK_SERVICE
+        .list_class_homeworks(&req, path.0, query.into_inner())
+        .await
+}
+
+pub async fn create_homework(
+    req: HttpRequest,
+    path: SafeClassIdI64,
+    homework_data: web::Json<CreateHomeworkRequest>,
+) -> ActixResult<HttpResponse> {
+    HOMEWORK_SERVICE
+        .create_homework(&req, path.0, homework_data.into_inner())
+        .await
+}
+
+pub async fn get_homework(
+    req: HttpRequest,
+    path: web::Path<(SafeClassIdI64, SafeHomeworkIdI64)>,
+) -> ActixResult<HttpResponse> {
+    let class_id = path.0.0;
+    let homework_id = path.1.0;
+    HOMEWORK_SERVICE
+        .get_homework(&req, class_id, homework_id)
+        .await
+}
+
+pub async fn update_homework(
+    req: HttpRequest,
+    path: web::Path<(SafeClassIdI64, SafeHomeworkIdI64)>,
+    update_data: web::Json<UpdateHomeworkRequest>,
+) -> ActixResult<HttpResponse> {
+    let class_id = path.0.0;
+    let homework_id = path.1.0;
+    HOMEWORK_SERVICE
+        .update_homework(&req, class_id, homework_id, update_data.into_inner())
+        .await
+}
+
+pub async fn delete_homework(
+    req: HttpRequest,
+    path: web::Path<(SafeClassIdI64, SafeHomeworkIdI64)>,
+) -> ActixResult<HttpResponse> {
+    let class_id = path.0.0;
+    let homework_id = path.1.0;
+    HOMEWORK_SERVICE
+        .delete_homework(&req, class_id, homework_id)
+        .await
+}
+
 // 配置路由
 pub fn configure_homeworks_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/homeworks")
             .wrap(middlewares::RequireJWT)
+            // 列出当前用户所在班级的作业，管理员可以查询所有作业
             .route("", web::get().to(list_homeworks)),
     );
+    cfg.service(
+        web::scope("/api/v1/classes/{class_id}/homeworks")
+            .wrap(middlewares::RequireJWT)
+            .service(
+                web::resource("")
+                    .route(
+                        web::get()
+                            .to(list_class_homeworks)
+                            // 列出班级作业，班级成员均可查看
+                            .wrap(middlewares::RequireClassRole::new_any(
+                                ClassUserRole::all_roles(),
+                            )),
+                    )
+                    .route(
+                        web::post()
+                            .to(create_homework)
+                            // 发布作业，仅班级教师（或管理员）
+                            .wrap(middlewares::RequireClassRole::new_any(
+                                ClassUserRole::class_teacher_roles(),
+                            )),
+                    ),
+            )
+            .service(
+                web::resource("/{homework_id}")
+                    .route(
+                        web::get()
+                            .to(get_homework)
+                            // 获取作业详情，班级成员均可查看
+                            .wrap(middlewares::RequireClassRole::new_any(
+                                ClassUserRole::all_roles(),
+                            )),
+                    )
+                    .route(
+                        web::put()
+                            .to(update_homework)
+                            // 更新作业，仅班级教师（或管理员）
+                            .wrap(middlewares::RequireClassRole::new_any(
+                                ClassUserRole::class_teacher_roles(),
+                            )),
+                    )
+                    .route(
+                        web::delete()
+                            .to(delete_homework)
+                            // 删除作业，仅班级教师（或管理员）
+                            .wrap(middlewares::RequireClassRole::new_any(
+                                ClassUserRole::class_teacher_roles(),
+                            )),
+                    ),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{Value, json};
+
+    use crate::models::classes::entities::Class;
+    use crate::models::users::entities::{User, UserRole};
+    use crate::test_utils::{TestContext, bearer};
+
+    async fn setup(ctx: &TestContext) -> (Class, User, User) {
+        let teacher = ctx.create_user("teacher", UserRole::Teacher).await;
+        let student = ctx.create_user("student", UserRole::User).await;
+        let class = ctx.create_class(&teacher, &[&student]).await;
+        (class, teacher, student)
+    }
+
+    #[actix_web::test]
+    async fn teacher_can_create_get_update_and_delete_homework() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let (class, teacher, student) = setup(&ctx).await;
+        let base = format!("/api/v1/classes/{}/homeworks", class.id);
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(bearer(&teacher))
+            .set_json(
+                json!({ "title": "Homework 1", "content": "Read chapter 1", "max_score": 100.0 }),
+            )
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let homework_id = body["data"]["id"].as_i64().unwrap();
+        let item = format!("{base}/{homework_id}");
+
+        // 班级成员均可查看
+        let req = test::TestRequest::get()
+            .uri(&item)
+            .insert_header(bearer(&student))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body: Value = test::read_body_json(resp).await;
+        assert_eq!(body["data"]["title"], "Homework 1");
+
+        let req = test::TestRequest::put()
+            .uri(&item)
+            .insert_header(bearer(&teacher))
+            .set_json(json!({ "title": "Homework 1 (revised)", "max_score": 50.0 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let homework = ctx
+            .storage
+            .get_homework_by_id(homework_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(homework.title, "Homework 1 (revised)");
+        assert_eq!(homework.max_score, 50.0);
+
+        let req = test::TestRequest::delete()
+            .uri(&item)
+            .insert_header(bearer(&teacher))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+            .uri(&item)
+            .insert_header(bearer(&teacher))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn non_teacher_cannot_modify_homework() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let (class, teacher, student) = setup(&ctx).await;
+        let outsider = ctx.create_user("outsider", UserRole::Teacher).await;
+        let base = format!("/api/v1/classes/{}/homeworks", class.id);
+
+        let payload = json!({ "title": "Homework 1", "max_score": 100.0 });
+        for user in [&student, &outsider] {
+            let req = test::TestRequest::post()
+                .uri(&base)
+                .insert_header(bearer(user))
+                .set_json(&payload)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        let req = test::TestRequest::post()
+            .uri(&base)
+            .insert_header(bearer(&teacher))
+            .set_json(&payload)
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let item = format!("{base}/{}", body["data"]["id"].as_i64().unwrap());
+
+        let req = test::TestRequest::put()
+            .uri(&item)
+            .insert_header(bearer(&student))
+            .set_json(json!({ "title": "Changed" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::delete()
+            .uri(&item)
+            .insert_header(bearer(&student))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        // 非班级成员也不能查看
+        let req = test::TestRequest::get()
+            .uri(&item)
+            .insert_header(bearer(&outsider))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
 }
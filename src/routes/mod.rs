@@ -8,14 +8,20 @@ pub mod class_users;
 
 pub mod files;
 
+pub mod grades;
+
 pub mod homeworks;
 
+pub mod submissions;
+
 pub mod system;
 
 pub use auth::configure_auth_routes;
 pub use class_users::configure_class_users_routes;
 pub use classes::configure_classes_routes;
 pub use files::configure_file_routes;
+pub use grades::configure_grades_routes;
 pub use homeworks::configure_homeworks_routes;
+pub use submissions::configure_submissions_routes;
 pub use system::configure_system_routes;
 pub use users::configure_user_routes;
@@ -0,0 +1,124 @@
+use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
+use once_cell::sync::Lazy;
+
+use crate::domain::SubmissionService;
+use crate::middlewares;
+use crate::models::submissions::requests::{CreateSubmissionRequest, SubmissionListParams};
+use crate::utils::SafeHomeworkIdI64;
+
+// 懒加载的全局 SubmissionService 实例
+static SUBMISSION_SERVICE: Lazy<SubmissionService> = Lazy::new(SubmissionService::new_lazy);
+
+// HTTP处理程序
+pub async fn list_submissions(
+    req: HttpRequest,
+    path: SafeHomeworkIdI64,
+    query: web::Query<SubmissionListParams>,
+) -> ActixResult<HttpResponse> {
+    SUBMISSION_SERVICE
+        .list_submissions(&req, path.0, query.into_inner())
+        .await
+}
+
+pub async fn create_submission(
+    req: HttpRequest,
+    path: SafeHomeworkIdI64,
+    submission_data: web::Json<CreateSubmissionRequest>,
+) -> ActixResult<HttpResponse> {
+    SUBMISSION_SERVICE
+        .create_submission(&req, path.0, submission_data.into_inner())
+        .await
+}
+
+pub async fn get_my_submission(
+    req: HttpRequest,
+    path: SafeHomeworkIdI64,
+) -> ActixResult<HttpResponse> {
+    SUBMISSION_SERVICE.get_my_submission(&req, path.0).await
+}
+
+// 配置路由
+pub fn configure_submissions_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/homeworks/{homework_id}/submissions")
+            .wrap(middlewares::RequireJWT)
+            // 列出作业的全部提交，仅班级教师（或管理员）
+            .route("", web::get().to(list_submissions))
+            // 提交作业，仅班级学生和课代表
+            .route("", web::post().to(create_submission))
+            // 获取当前用户的提交
+            .route("/me", web::get().to(get_my_submission)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{Value, json};
+
+    use crate::models::homeworks::requests::CreateHomeworkRequest;
+    use crate::models::users::entities::UserRole;
+    use crate::test_utils::{TestContext, bearer};
+
+    #[actix_web::test]
+    async fn graded_submission_cannot_be_resubmitted() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let teacher = ctx.create_user("teacher", UserRole::Teacher).await;
+        let student = ctx.create_user("student", UserRole::User).await;
+        let class = ctx.create_class(&teacher, &[&student]).await;
+        let homework = ctx
+            .storage
+            .create_homework(
+                class.id,
+                teacher.id,
+                CreateHomeworkRequest {
+                    title: "Homework 1".to_string(),
+                    content: None,
+                    attachments: vec![],
+                    max_score: 100.0,
+                    deadline: None,
+                    allow_late_submission: false,
+                },
+            )
+            .await
+            .unwrap();
+        let uri = format!("/api/v1/homeworks/{}/submissions", homework.id);
+
+        let submit = |content: &str| {
+            test::TestRequest::post()
+                .uri(&uri)
+                .insert_header(bearer(&student))
+                .set_json(json!({ "content": content }))
+                .to_request()
+        };
+
+        // 评分前可以重新提交，覆盖之前的内容
+        let resp = test::call_service(&app, submit("first")).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let resp = test::call_service(&app, submit("second")).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body: Value = test::read_body_json(resp).await;
+        let submission_id = body["data"]["id"].as_i64().unwrap();
+        assert_eq!(body["data"]["content"], "second");
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/api/v1/submissions/{submission_id}/grades"))
+            .insert_header(bearer(&teacher))
+            .set_json(json!({ "score": 90.0 }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        let resp = test::call_service(&app, submit("third")).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        let submission = ctx
+            .storage
+            .get_submission_by_id(submission_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(submission.content, "second");
+    }
+}
@@ -12,8 +12,14 @@ pub async fn get_settings(request: HttpRequest) -> ActixResult<HttpResponse> {
     SYSTEM_SERVICE.get_settings(&request).await
 }
 
+pub async fn get_jwks(request: HttpRequest) -> ActixResult<HttpResponse> {
+    SYSTEM_SERVICE.get_jwks(&request).await
+}
+
 // 配置路由
 pub fn configure_system_routes(cfg: &mut web::ServiceConfig) {
+    // 公开的验证公钥集合，供其他服务验证本系统签发的令牌
+    cfg.route("/.well-known/jwks.json", web::get().to(get_jwks));
     cfg.service(
         web::scope("/api/v1/system")
             .wrap(middleware::Compress::default())
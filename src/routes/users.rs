@@ -1,10 +1,14 @@
+use actix_multipart::Multipart;
 use actix_web::{HttpRequest, HttpResponse, Result as ActixResult, web};
 use once_cell::sync::Lazy;
 
 use crate::domain::UserService;
 use crate::middlewares;
+use crate::models::common::PaginationQuery;
 use crate::models::users::entities::UserRole;
-use crate::models::users::requests::{CreateUserRequest, UpdateUserRequest, UserListParams};
+use crate::models::users::requests::{
+    CreateUserRequest, UpdateUserRequest, UserExportParams, UserImportQuery, UserListParams,
+};
 use crate::utils::SafeIDI64;
 
 // 懒加载的全局 UserService 实例
@@ -25,6 +29,23 @@ pub async fn create_user(
     USER_SERVICE.create_user(user_data.into_inner(), &req).await
 }
 
+pub async fn import_users(
+    req: HttpRequest,
+    query: web::Query<UserImportQuery>,
+    payload: Multipart,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE
+        .import_users(query.into_inner(), payload, &req)
+        .await
+}
+
+pub async fn export_users(
+    req: HttpRequest,
+    params: web::Query<UserExportParams>,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE.export_users(params.into_inner(), &req).await
+}
+
 pub async fn get_user(req: HttpRequest, user_id: SafeIDI64) -> ActixResult<HttpResponse> {
     USER_SERVICE.get_user(user_id.0, &req).await
 }
@@ -43,6 +64,58 @@ pub async fn delete_user(req: HttpRequest, user_id: SafeIDI64) -> ActixResult<Ht
     USER_SERVICE.delete_user(user_id.0, &req).await
 }
 
+pub async fn list_user_sessions(req: HttpRequest, user_id: SafeIDI64) -> ActixResult<HttpResponse> {
+    USER_SERVICE.list_user_sessions(user_id.0, &req).await
+}
+
+pub async fn revoke_user_session(
+    req: HttpRequest,
+    path: web::Path<(SafeIDI64, String)>,
+) -> ActixResult<HttpResponse> {
+    let (user_id, session_id) = path.into_inner();
+    USER_SERVICE
+        .revoke_user_session(user_id.0, &session_id, &req)
+        .await
+}
+
+pub async fn revoke_all_user_sessions(
+    req: HttpRequest,
+    user_id: SafeIDI64,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE.revoke_all_user_sessions(user_id.0, &req).await
+}
+
+pub async fn unlock_user(req: HttpRequest, user_id: SafeIDI64) -> ActixResult<HttpResponse> {
+    USER_SERVICE.unlock_user(user_id.0, &req).await
+}
+
+pub async fn reset_user_mfa(req: HttpRequest, user_id: SafeIDI64) -> ActixResult<HttpResponse> {
+    USER_SERVICE.reset_user_mfa(user_id.0, &req).await
+}
+
+pub async fn list_pending_registrations(
+    req: HttpRequest,
+    query: web::Query<PaginationQuery>,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE
+        .list_pending_registrations(query.into_inner(), &req)
+        .await
+}
+
+pub async fn approve_registration(
+    req: HttpRequest,
+    user_id: SafeIDI64,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE.approve_registration(user_id.0, &req).await
+}
+
+pub async fn reject_registration(
+    req: HttpRequest,
+    user_id: SafeIDI64,
+) -> ActixResult<HttpResponse> {
+    USER_SERVICE.reject_registration(user_id.0, &req).await
+}
+
 // 配置路由
 pub fn configure_user_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -53,9 +126,267 @@ pub fn configure_user_routes(cfg: &mut web::ServiceConfig) {
                     .wrap(middlewares::RequireRole::new_any(UserRole::admin_roles()))
                     .route("", web::get().to(list_users))
                     .route("", web::post().to(create_user))
+                    // 导入导出与注册审核路由需在 /{id} 之前注册
+                    .route("/import", web::post().to(import_users))
+                    .route("/export", web::get().to(export_users))
+                    .route("/registrations", web::get().to(list_pending_registrations))
+                    .route(
+                        "/registrations/{id}/approve",
+                        web::post().to(approve_registration),
+                    )
+                    .route(
+                        "/registrations/{id}/reject",
+                        web::post().to(reject_registration),
+                    )
                     .route("/{id}", web::get().to(get_user))
                     .route("/{id}", web::put().to(update_user))
-                    .route("/{id}", web::delete().to(delete_user)),
+                    .route("/{id}", web::delete().to(delete_user))
+                    .route("/{id}/unlock", web::post().to(unlock_user))
+                    .route("/{id}/mfa", web::delete().to(reset_user_mfa))
+                    .route("/{id}/sessions", web::get().to(list_user_sessions))
+                    .route("/{id}/sessions", web::delete().to(revoke_all_user_sessions))
+                    .route(
+                        "/{id}/sessions/{session_id}",
+                        web::delete().to(revoke_user_session),
+                    ),
             ),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{Value, json};
+
+    use crate::models::users::entities::{User, UserRole, UserStatus};
+    use crate::models::users::requests::UpdateUserRequest;
+    use crate::test_utils::{TestContext, bearer};
+
+    async fn create_user_with_status(
+        ctx: &TestContext,
+        username: &str,
+        status: UserStatus,
+    ) -> User {
+        let user = ctx.create_user(username, UserRole::User).await;
+        ctx.storage
+            .update_user(
+                user.id,
+                UpdateUserRequest {
+                    email: None,
+                    password: None,
+                    role: None,
+                    status: Some(status),
+                    profile: None,
+                },
+            )
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn registration_queue_only_contains_pending_users() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let pending = create_user_with_status(&ctx, "pending", UserStatus::Pending).await;
+        let inactive = create_user_with_status(&ctx, "inactive", UserStatus::Inactive).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/registrations")
+            .insert_header(bearer(&admin))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        let ids: Vec<i64> = body["data"]["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![pending.id]);
+
+        // 被停用的普通用户不是注册申请，不能被拒绝删除
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/api/v1/users/registrations/{}/reject",
+                inactive.id
+            ))
+            .insert_header(bearer(&admin))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+        assert!(
+            ctx.storage
+                .get_user_by_id(inactive.id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/api/v1/users/registrations/{}/approve",
+                pending.id
+            ))
+            .insert_header(bearer(&admin))
+            .to_request();
+        let body: Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(body["data"]["user"]["status"], "active");
+    }
+
+    #[actix_web::test]
+    async fn admin_cannot_set_pending_status() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let user = ctx.create_user("bob", UserRole::User).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users")
+            .insert_header(bearer(&admin))
+            .set_json(json!({
+                "username": "carol",
+                "email": "carol@example.com",
+                "password": "Password123!",
+                "role": "user",
+                "status": "pending",
+                "profile": { "profile_name": "carol" }
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/api/v1/users/{}", user.id))
+            .insert_header(bearer(&admin))
+            .set_json(json!({ "status": "pending" }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn duplicate_username_is_a_conflict() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        ctx.create_user("carol", UserRole::User).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/v1/users")
+            .insert_header(bearer(&admin))
+            .set_json(json!({
+                "username": "carol",
+                "email": "another@example.com",
+                "password": "Password123!",
+                "role": "user",
+                "profile": { "profile_name": "carol" }
+            }))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+    }
+
+    #[actix_web::test]
+    async fn xlsx_export_can_be_read_back() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        ctx.create_user("carol", UserRole::User).await;
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/users/export?format=xlsx&search=carol")
+            .insert_header(bearer(&admin))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        let rows = crate::utils::xlsx::read_rows(&body).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][1..3], ["username", "email"]);
+        assert_eq!(rows[1][1..3], ["carol", "carol@example.com"]);
+    }
+
+    fn get_request(uri: &str, user: &User) -> actix_http::Request {
+        test::TestRequest::get()
+            .uri(uri)
+            .insert_header(bearer(user))
+            .to_request()
+    }
+
+    fn update_request(admin: &User, user_id: i64, body: Value) -> actix_http::Request {
+        test::TestRequest::put()
+            .uri(&format!("/api/v1/users/{user_id}"))
+            .insert_header(bearer(admin))
+            .set_json(body)
+            .to_request()
+    }
+
+    #[actix_web::test]
+    async fn suspended_user_is_rejected_on_next_request() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let user = ctx.create_user("trent", UserRole::User).await;
+
+        // 先发起一次请求让用户信息进入缓存
+        let resp = test::call_service(&app, get_request("/api/v1/auth/me", &user)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            update_request(&admin, user.id, json!({ "status": "suspended" })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 令牌仍未过期，但缓存已失效，按存储中的状态拒绝
+        let resp = test::call_service(&app, get_request("/api/v1/auth/me", &user)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn demoted_admin_loses_access_on_next_request() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let deputy = ctx.create_user("deputy", UserRole::Admin).await;
+
+        let resp = test::call_service(&app, get_request("/api/v1/users", &deputy)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(
+            &app,
+            update_request(&admin, deputy.id, json!({ "role": "user" })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 令牌中的角色仍为管理员，权限按最新的用户信息判断
+        let resp = test::call_service(&app, get_request("/api/v1/users", &deputy)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = test::call_service(&app, get_request("/api/v1/auth/me", &deputy)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn deleted_user_is_rejected_on_next_request() {
+        let ctx = TestContext::new().await;
+        let app = ctx.app().await;
+        let admin = ctx.create_user("root", UserRole::Admin).await;
+        let user = ctx.create_user("victor", UserRole::User).await;
+
+        let resp = test::call_service(&app, get_request("/api/v1/auth/me", &user)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/api/v1/users/{}", user.id))
+            .insert_header(bearer(&admin))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::call_service(&app, get_request("/api/v1/auth/me", &user)).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
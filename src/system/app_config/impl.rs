@@ -1,3 +1,5 @@
+#[cfg(test)]
+use config::FileFormat;
 use config::{Config, ConfigError, Environment, File};
 use std::sync::OnceLock;
 
@@ -74,6 +76,40 @@ impl AppConfig {
         Ok(())
     }
 
+    /// 以默认配置文件初始化全局配置，仅用于测试
+    ///
+    /// 使用内存缓存、本地发件箱与临时上传目录，不读取配置文件和环境变量
+    #[cfg(test)]
+    pub fn init_for_test() -> &'static AppConfig {
+        APP_CONFIG.get_or_init(|| {
+            let upload_dir =
+                std::env::temp_dir().join(format!("hwsystem-test-{}", std::process::id()));
+            Config::builder()
+                .add_source(File::from_str(
+                    include_str!("../../../config.toml"),
+                    FileFormat::Toml,
+                ))
+                .set_override("database.url", ":memory:")
+                .and_then(|b| b.set_override("cache.type", "moka"))
+                .and_then(|b| b.set_override("mail.transport", "outbox"))
+                .and_then(|b| {
+                    b.set_override(
+                        "mail.outbox.dir",
+                        upload_dir.join("outbox").to_string_lossy().as_ref(),
+                    )
+                })
+                .and_then(|b| b.set_override("upload.dir", upload_dir.to_string_lossy().as_ref()))
+                .and_then(|b| b.set_override("upload.gc_interval", 0))
+                .and_then(|b| b.set_override("rate_limit.enabled", false))
+                .and_then(|b| b.set_override("homework.class_representative_can_grade", true))
+                .and_then(|b| b.set_override("auth.failure_delay_base_ms", 0))
+                .and_then(|b| b.set_override("auth.failure_delay_max_ms", 0))
+                .and_then(|b| b.build())
+                .and_then(|c| c.try_deserialize())
+                .expect("Failed to load test configuration")
+        })
+    }
+
     /// 检查是否为生产环境
     pub fn is_production(&self) -> bool {
         self.app.environment == "production"
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::users::entities::UserRole;
 
 /// 应用配置结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +13,28 @@ pub struct AppConfig {
     pub cache: CacheConfig,
     pub cors: CorsConfig,
     pub upload: UploadConfig,
+    #[serde(default)]
+    pub homework: HomeworkConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub registration: RegistrationConfig,
+    #[serde(default)]
+    pub mail: MailConfig,
+    #[serde(default)]
+    pub avatar: AvatarConfig,
+    #[serde(default)]
+    pub mfa: MfaConfig,
+    #[serde(default)]
+    pub api_token: ApiTokenConfig,
+    #[serde(default)]
+    pub oidc: OidcConfig,
+    #[serde(default)]
+    pub ldap: LdapConfig,
+    #[serde(default)]
+    pub user_import: UserImportConfig,
 }
 
 /// 应用设置
@@ -30,6 +55,8 @@ pub struct ServerConfig {
     pub max_workers: usize,
     pub timeouts: TimeoutConfig,
     pub limits: LimitConfig,
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>, // 受信任的反向代理地址或网段，只采用其转发的客户端 IP
 }
 
 /// 超时配置
@@ -54,6 +81,22 @@ pub struct JwtConfig {
     pub access_token_expiry: i64,
     pub refresh_token_expiry: i64,
     pub refresh_token_remember_me_expiry: i64,
+    #[serde(default)]
+    pub signing_key_id: String, // 用于签发令牌的非对称密钥 kid，为空时使用 secret 以 HS256 签名
+    #[serde(default)]
+    pub require_kid: bool, // 只接受带 kid 的非对称签名令牌，拒绝 HS256 令牌
+    #[serde(default)]
+    pub keys: Vec<JwtKeyConfig>, // 非对称密钥列表，轮换后旧密钥保留公钥用于验证
+}
+
+/// JWT 非对称密钥配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtKeyConfig {
+    pub kid: String,             // 密钥 ID，写入令牌头部的 kid
+    pub algorithm: String,       // 签名算法: RS256 / EdDSA
+    pub public_key_file: String, // PEM 格式公钥文件
+    #[serde(default)]
+    pub private_key_file: String, // PEM 格式私钥文件，仅签名密钥需要
 }
 
 /// 数据库配置
@@ -100,8 +143,429 @@ pub struct CorsConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UploadConfig {
     pub dir: String,                // 上传目录
     pub max_size: usize,            // 单文件最大字节数
     pub allowed_types: Vec<String>, // 允许的MIME类型或扩展名
+    pub backend: String,            // 文件存储后端 (fs/s3)
+    pub download_url_expiry: i64,   // 下载链接有效期(秒)
+    pub gc_interval: u64,           // 未引用文件回收间隔(秒)，0 表示不回收
+    pub gc_grace_period: i64,       // 未引用文件的保留期(秒)
+    pub resumable_max_size: usize,  // 可续传上传的单文件最大字节数
+    pub chunk_size: usize,          // 可续传上传的分块字节数
+    pub session_expiry: i64,        // 可续传上传会话无新分块时的有效期(秒)
+    pub max_open_sessions: i64,     // 每个用户同时进行的可续传上传会话数量上限
+    pub s3: S3Config,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            dir: "uploads".to_string(),
+            max_size: 10 * 1024 * 1024,
+            allowed_types: vec![
+                "image/png".to_string(),
+                "image/jpeg".to_string(),
+                "application/pdf".to_string(),
+            ],
+            backend: "fs".to_string(),
+            download_url_expiry: 300,
+            gc_interval: 3600,
+            gc_grace_period: 86400,
+            resumable_max_size: 1024 * 1024 * 1024,
+            chunk_size: 8 * 1024 * 1024,
+            session_expiry: 86400,
+            max_open_sessions: 5,
+            s3: S3Config::default(),
+        }
+    }
+}
+
+/// S3 兼容对象存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    pub endpoint: String,   // 服务地址，例如 https://s3.us-east-1.amazonaws.com
+    pub region: String,     // 签名使用的区域
+    pub bucket: String,     // 存储桶名称
+    pub access_key: String, // 访问密钥 ID
+    pub secret_key: String, // 访问密钥
+    pub prefix: String,     // 对象键前缀
+    pub path_style: bool,   // 使用路径风格地址 (endpoint/bucket/key)，关闭时使用虚拟主机风格
+    pub timeout: u64,       // 单个请求超时(秒)
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:9000".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: String::new(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            prefix: String::new(),
+            path_style: true,
+            timeout: 60,
+        }
+    }
+}
+
+/// 作业配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HomeworkConfig {
+    #[serde(default)]
+    pub class_representative_can_grade: bool, // 是否允许课代表评分
+}
+
+/// 认证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub max_failed_attempts: u32,        // 账号连续登录失败多少次后锁定
+    pub max_failed_attempts_per_ip: u32, // 同一 IP 登录失败多少次后锁定
+    pub failure_window: i64,             // 失败次数统计窗口 (秒)
+    pub lockout_duration: i64,           // 锁定时长 (秒)
+    pub failure_delay_base_ms: u64,      // 失败后拒绝再次登录的基础时长 (毫秒)，按失败次数翻倍
+    pub failure_delay_max_ms: u64,       // 失败后拒绝再次登录的最大时长 (毫秒)
+    pub email_verification_expiry: i64,  // 邮箱验证链接有效期 (分钟)
+    pub password_reset_expiry: i64,      // 密码重置链接有效期 (分钟)
+    pub authenticators: Vec<String>,     // 用户名密码登录依次尝试的认证方式: local, ldap
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            max_failed_attempts_per_ip: 20,
+            failure_window: 900,
+            lockout_duration: 900,
+            failure_delay_base_ms: 200,
+            failure_delay_max_ms: 3000,
+            email_verification_expiry: 1440,
+            password_reset_expiry: 30,
+            authenticators: vec!["local".to_string()],
+        }
+    }
+}
+
+/// 限流配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,                            // 是否启用限流
+    pub default: RateLimitPolicy,                 // 未单独配置的路由作用域使用的限流策略
+    pub scopes: HashMap<String, RateLimitPolicy>, // 按路由作用域单独配置的限流策略
+}
+
+impl RateLimitConfig {
+    /// 获取路由作用域对应的限流策略，未启用限流时返回 None
+    pub fn policy(&self, scope: &str) -> Option<RateLimitPolicy> {
+        if !self.enabled {
+            return None;
+        }
+        Some(self.scopes.get(scope).unwrap_or(&self.default).clone())
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default: RateLimitPolicy::default(),
+            scopes: HashMap::from([(
+                "auth".to_string(),
+                RateLimitPolicy {
+                    capacity: 20,
+                    refill_per_minute: 10,
+                },
+            )]),
+        }
+    }
+}
+
+/// 限流策略，计数窗口长度为补满容量所需的时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitPolicy {
+    pub capacity: u32,          // 每个计数窗口内允许的请求数，即允许的突发请求数
+    pub refill_per_minute: u32, // 每分钟允许的平均请求数
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: 120,
+            refill_per_minute: 120,
+        }
+    }
+}
+
+/// 自助注册配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegistrationConfig {
+    pub mode: RegistrationMode,    // 注册模式
+    pub invite_codes: Vec<String>, // 仅限邀请模式下可用的邀请码
+}
+
+/// 自助注册模式
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistrationMode {
+    #[default]
+    Open, // 开放注册
+    InviteOnly, // 凭邀请码注册
+    Approval,   // 注册后需管理员审核
+    Closed,     // 关闭注册
+}
+
+/// 邮件配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailConfig {
+    pub transport: String,     // 邮件发送方式 (smtp/outbox)
+    pub from: String,          // 发件人
+    pub link_base_url: String, // 邮件中链接指向的前端地址
+    pub smtp: SmtpConfig,
+    pub outbox: OutboxConfig,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            transport: "outbox".to_string(),
+            from: "HWSystem <noreply@example.com>".to_string(),
+            link_base_url: "http://localhost:3000".to_string(),
+            smtp: SmtpConfig::default(),
+            outbox: OutboxConfig::default(),
+        }
+    }
+}
+
+/// SMTP 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub tls: String, // 加密方式 (starttls/tls/none)
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 587,
+            username: String::new(),
+            password: String::new(),
+            tls: "starttls".to_string(),
+        }
+    }
+}
+
+/// 邮件发件箱配置，邮件写入本地目录而不实际发送，用于开发和测试
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboxConfig {
+    pub dir: String, // 邮件保存目录
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            dir: "./outbox".to_string(),
+        }
+    }
+}
+
+/// 头像配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AvatarConfig {
+    pub max_size: usize,    // 上传文件最大字节数
+    pub max_dimension: u32, // 原图允许的最大宽高(像素)
+    pub sizes: Vec<u32>,    // 生成的正方形尺寸(像素)，最大尺寸作为 avatar_url
+}
+
+impl Default for AvatarConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 5 * 1024 * 1024,
+            max_dimension: 4096,
+            sizes: vec![64, 256],
+        }
+    }
+}
+
+/// 两步验证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MfaConfig {
+    pub issuer: String,                // 验证器 App 中显示的签发方名称
+    pub required_roles: Vec<UserRole>, // 必须启用两步验证的角色
+    pub pending_token_expiry: i64,     // 登录第二步令牌有效期 (分钟)
+    pub allowed_skew: u64,             // 允许的时间偏差 (时间步，每步 30 秒)
+    pub recovery_code_count: usize,    // 生成的恢复码数量
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "HWSystem".to_string(),
+            required_roles: Vec::new(),
+            pending_token_expiry: 5,
+            allowed_skew: 1,
+            recovery_code_count: 10,
+        }
+    }
+}
+
+/// 个人访问令牌配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiTokenConfig {
+    pub max_per_user: usize,  // 每个用户最多持有的有效令牌数量
+    pub max_expiry_days: i64, // 令牌最长有效天数，0 表示允许永不过期的令牌
+}
+
+impl Default for ApiTokenConfig {
+    fn default() -> Self {
+        Self {
+            max_per_user: 20,
+            max_expiry_days: 0,
+        }
+    }
+}
+
+/// OpenID Connect 单点登录配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OidcConfig {
+    pub redirect_base_url: String, // 本服务的对外访问地址，用于拼接回调地址
+    pub frontend_redirect_url: String, // 登录完成后跳转的前端地址，为空时回调直接返回 JSON
+    pub state_expiry: i64,         // 登录请求 (state) 有效期 (秒)
+    pub providers: HashMap<String, OidcProviderConfig>, // 身份提供方，键为路由中的 provider 名称
+}
+
+impl Default for OidcConfig {
+    fn default() -> Self {
+        Self {
+            redirect_base_url: "http://localhost:8080".to_string(),
+            frontend_redirect_url: String::new(),
+            state_expiry: 600,
+            providers: HashMap::new(),
+        }
+    }
+}
+
+/// OpenID Connect 身份提供方配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OidcProviderConfig {
+    pub display_name: String, // 登录页显示的名称
+    pub issuer: String, // 签发方标识，未配置端点时从 {issuer}/.well-known/openid-configuration 发现
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub client_secret: String, // 为空时作为公开客户端，仅依赖 PKCE
+    pub scopes: Vec<String>,
+    pub authorization_endpoint: String, // 授权端点，为空时使用发现结果
+    pub token_endpoint: String,         // 令牌端点，为空时使用发现结果
+    pub jwks_uri: String,               // 公钥集合地址，为空时使用发现结果
+    pub default_role: UserRole,         // 自动创建的用户的角色
+    pub auto_provision: bool,           // 未关联的外部账号首次登录时自动创建用户
+    pub link_by_email: bool,            // 按已验证的邮箱关联已有用户
+    pub username_claim: String,         // 自动创建用户时作为用户名的声明
+}
+
+impl Default for OidcProviderConfig {
+    fn default() -> Self {
+        Self {
+            display_name: String::new(),
+            issuer: String::new(),
+            client_id: String::new(),
+            client_secret: String::new(),
+            scopes: vec![
+                "openid".to_string(),
+                "email".to_string(),
+                "profile".to_string(),
+            ],
+            authorization_endpoint: String::new(),
+            token_endpoint: String::new(),
+            jwks_uri: String::new(),
+            default_role: UserRole::User,
+            auto_provision: true,
+            link_by_email: true,
+            username_claim: "preferred_username".to_string(),
+        }
+    }
+}
+
+/// LDAP 认证配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LdapConfig {
+    pub url: String,         // 服务器地址，支持 ldap:// 与 ldaps://
+    pub tls_ca_file: String, // 验证 ldaps 服务器证书的 CA 文件 (PEM)，为空时使用系统根证书
+    pub bind_dn: String,     // 搜索用户时使用的服务账号，为空时匿名搜索
+    #[serde(skip_serializing)]
+    pub bind_password: String,
+    pub base_dn: String,                        // 搜索用户的起点
+    pub user_filter: String, // 搜索用户的过滤器，{username} 会被替换为转义后的登录名
+    pub id_attribute: String, // 唯一标识用户的属性，例如 entryUUID，为空时使用 DN
+    pub username_attribute: String, // 映射到 username 的属性
+    pub email_attribute: String, // 映射到 email 的属性
+    pub profile_name_attribute: String, // 映射到 profile_name 的属性
+    pub group_attribute: String, // 用户所属组的属性
+    pub group_roles: HashMap<String, UserRole>, // 组 DN 到角色的映射，不区分大小写，属于多个组时取最高角色
+    pub default_role: UserRole,                 // 不属于任何映射组时的角色
+    pub auto_provision: bool,                   // 首次登录时自动创建本地用户
+    pub link_existing: bool,                    // 首次登录时关联同名的本地用户
+    pub sync_interval: u64,                     // 定期同步用户属性的间隔 (秒)，0 表示只在登录时同步
+    pub suspend_missing: bool,                  // 同步时暂停在目录中已不存在的用户
+    pub timeout: u64,                           // 连接与请求超时 (秒)
+}
+
+impl Default for LdapConfig {
+    fn default() -> Self {
+        Self {
+            url: "ldap://localhost:389".to_string(),
+            tls_ca_file: String::new(),
+            bind_dn: String::new(),
+            bind_password: String::new(),
+            base_dn: String::new(),
+            user_filter: "(&(objectClass=person)(uid={username}))".to_string(),
+            id_attribute: String::new(),
+            username_attribute: "uid".to_string(),
+            email_attribute: "mail".to_string(),
+            profile_name_attribute: "cn".to_string(),
+            group_attribute: "memberOf".to_string(),
+            group_roles: HashMap::new(),
+            default_role: UserRole::User,
+            auto_provision: true,
+            link_existing: false,
+            sync_interval: 3600,
+            suspend_missing: false,
+            timeout: 10,
+        }
+    }
+}
+
+/// 批量导入用户配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserImportConfig {
+    pub max_file_size: usize, // 导入文件最大字节数
+    pub max_rows: usize,      // 单次导入的最大行数 (不含表头)
+}
+
+impl Default for UserImportConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: 5 * 1024 * 1024,
+            max_rows: 1000,
+        }
+    }
 }
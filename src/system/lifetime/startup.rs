@@ -1,12 +1,17 @@
+use crate::blob_store::{BlobStore, register::get_blob_store_plugin};
 use crate::cache::{ObjectCache, register::get_object_cache_plugin};
+use crate::mailer::{Mailer, register::get_mailer_plugin};
 use crate::repository::{Storage, StorageFactory};
 use crate::system::app_config::AppConfig;
+use crate::utils::jwt_keys::JwtKeys;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
 pub struct StartupContext {
     pub storage: Arc<dyn Storage>,
     pub cache: Arc<dyn ObjectCache>,
+    pub mailer: Arc<dyn Mailer>,
+    pub blob_store: Arc<dyn BlobStore>,
 }
 
 /// 创建缓存实例
@@ -68,6 +73,54 @@ async fn create_cache() -> Result<Arc<dyn ObjectCache>, Box<dyn std::error::Erro
     Err(format!("No cache backend available (tried: {cache_type})").into())
 }
 
+/// 创建邮件发送实例
+async fn create_mailer() -> Result<Arc<dyn Mailer>, Box<dyn std::error::Error>> {
+    let config = AppConfig::get();
+    let transport = &config.mail.transport;
+
+    warn!("Attempting to create {} mailer", transport);
+
+    match get_mailer_plugin(transport) {
+        Some(constructor) => match constructor().await {
+            Ok(mailer) => {
+                warn!("Successfully created {} mailer", transport);
+                return Ok(Arc::from(mailer));
+            }
+            Err(e) => warn!("Failed to create {} mailer: {}", transport, e),
+        },
+        None => warn!("Mailer '{}' not found in registry", transport),
+    }
+
+    // 配置的发送方式不可用时回退到本地发件箱，邮件不会实际发出
+    if transport != "outbox" {
+        warn!("Falling back to outbox mailer, mails will NOT be delivered");
+        if let Some(fallback_constructor) = get_mailer_plugin("outbox") {
+            match fallback_constructor().await {
+                Ok(mailer) => return Ok(Arc::from(mailer)),
+                Err(fallback_e) => warn!("Failed to create fallback outbox mailer: {}", fallback_e),
+            }
+        }
+    }
+
+    Err(format!("No mailer available (tried: {transport})").into())
+}
+
+/// 创建文件存储实例
+///
+/// 不回退到其他后端，否则多个实例可能把文件写到不同的位置
+async fn create_blob_store() -> Result<Arc<dyn BlobStore>, Box<dyn std::error::Error>> {
+    let config = AppConfig::get();
+    let backend = &config.upload.backend;
+
+    warn!("Attempting to create {} blob store", backend);
+
+    let constructor = get_blob_store_plugin(backend)
+        .ok_or_else(|| format!("Blob store '{backend}' not found in registry"))?;
+    let store = constructor().await?;
+    warn!("Successfully created {} blob store", backend);
+    Ok(Arc::from(store))
+}
+
 /// 准备服务器启动的上下文
 /// 包括存储、缓存和路由配置等
 pub async fn prepare_server_startup() -> StartupContext {
@@ -78,9 +131,15 @@ pub async fn prepare_server_startup() -> StartupContext {
     if cfg!(debug_assertions) {
         crate::repository::register::debug_storage_registry();
         crate::cache::register::debug_object_cache_registry();
+        crate::mailer::register::debug_mailer_registry();
+        crate::blob_store::register::debug_blob_store_registry();
         debug!("Debug mode: Storage and cache registries are enabled");
     }
 
+    // 加载 JWT 签名密钥，密钥文件错误时拒绝启动
+    JwtKeys::init().expect("Failed to load JWT keys");
+    warn!("JWT keys loaded");
+
     let storage = StorageFactory::create()
         .await
         .expect("Failed to create storage backend");
@@ -90,5 +149,26 @@ pub async fn prepare_server_startup() -> StartupContext {
     let cache = create_cache().await.expect("Failed to create cache");
     warn!("Cache backend initialized");
 
-    StartupContext { storage, cache }
+    // 启用 LDAP 认证时定期同步用户属性
+    crate::domain::auth::ldap::start_sync(storage.clone(), cache.clone());
+
+    // 创建邮件发送实例
+    let mailer = create_mailer().await.expect("Failed to create mailer");
+    warn!("Mailer initialized");
+
+    // 创建文件存储实例
+    let blob_store = create_blob_store()
+        .await
+        .expect("Failed to create blob store");
+    warn!("Blob store initialized");
+
+    // 定期回收未被引用的文件
+    crate::domain::files::gc::start_gc(storage.clone(), blob_store.clone());
+
+    StartupContext {
+        storage,
+        cache,
+        mailer,
+        blob_store,
+    }
 }
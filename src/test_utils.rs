@@ -0,0 +1,165 @@
+//! 测试辅助工具
+//!
+//! 使用内存 SQLite、内存缓存与记录邮件的发送器构建与 `main` 相同路由的应用
+
+use std::sync::{Arc, Mutex};
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::{App, test, web};
+use async_trait::async_trait;
+
+use crate::blob_store::{BlobStore, backends::fs::FsBlobStore};
+use crate::cache::{ObjectCache, object_cache::moka::MokaCacheWrapper};
+use crate::errors::Result;
+use crate::mailer::{MailMessage, Mailer};
+use crate::models::AppStartTime;
+use crate::models::class_users::entities::ClassUserRole;
+use crate::models::classes::entities::Class;
+use crate::models::classes::requests::CreateClassRequest;
+use crate::models::users::entities::{User, UserProfile, UserRole, UserStatus};
+use crate::models::users::requests::CreateUserRequest;
+use crate::repository::Storage;
+use crate::repository::backends::sqlite::SqliteStorage;
+use crate::routes;
+use crate::system::app_config::AppConfig;
+use crate::utils::jwt::JwtUtils;
+use crate::utils::password::hash_password;
+use crate::utils::{json_error_handler, query_error_handler};
+
+/// 测试用户的密码
+pub const TEST_PASSWORD: &str = "Password123!";
+
+/// 记录已发送邮件而不实际发送
+#[derive(Default)]
+pub struct RecordingMailer {
+    sent: Mutex<Vec<MailMessage>>,
+}
+
+impl RecordingMailer {
+    /// 已发送的全部邮件
+    pub fn sent(&self) -> Vec<MailMessage> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    /// 从发给指定地址的最后一封邮件的链接中取出令牌
+    pub fn last_token_for(&self, to: &str) -> Option<String> {
+        let sent = self.sent.lock().unwrap();
+        let body = &sent.iter().rev().find(|message| message.to == to)?.body;
+        let token = body.split("token=").nth(1)?;
+        Some(token.split_whitespace().next()?.to_string())
+    }
+}
+
+#[async_trait]
+impl Mailer for RecordingMailer {
+    async fn send(&self, message: MailMessage) -> Result<()> {
+        self.sent.lock().unwrap().push(message);
+        Ok(())
+    }
+}
+
+/// 每个测试独立的存储、缓存与邮件发送器
+pub struct TestContext {
+    pub storage: Arc<dyn Storage>,
+    pub cache: Arc<dyn ObjectCache>,
+    pub mailer: Arc<RecordingMailer>,
+    pub blob_store: Arc<dyn BlobStore>,
+}
+
+impl TestContext {
+    pub async fn new() -> Self {
+        AppConfig::init_for_test();
+        let storage = SqliteStorage::new_in_memory()
+            .await
+            .expect("Failed to create in-memory storage");
+        Self {
+            storage: Arc::new(storage),
+            cache: Arc::new(MokaCacheWrapper::new().expect("Failed to create cache")),
+            mailer: Arc::new(RecordingMailer::default()),
+            blob_store: Arc::new(FsBlobStore::new().expect("Failed to create blob store")),
+        }
+    }
+
+    /// 按 `main` 的方式注册全部路由，不包含全局中间件
+    pub async fn app(
+        &self,
+    ) -> impl Service<
+        actix_http::Request,
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+    > {
+        let config = AppConfig::get();
+        let mailer: Arc<dyn Mailer> = self.mailer.clone();
+        test::init_service(
+            App::new()
+                .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+                .app_data(web::JsonConfig::default().error_handler(json_error_handler))
+                .app_data(web::Data::new(self.storage.clone()))
+                .app_data(web::Data::new(self.cache.clone()))
+                .app_data(web::Data::new(mailer))
+                .app_data(web::Data::new(self.blob_store.clone()))
+                .app_data(web::Data::new(AppStartTime {
+                    start_datetime: chrono::Utc::now(),
+                }))
+                .app_data(web::PayloadConfig::new(
+                    config.server.limits.max_payload_size,
+                ))
+                .configure(routes::configure_auth_routes)
+                .configure(routes::configure_user_routes)
+                .configure(routes::configure_class_users_routes)
+                .configure(routes::configure_grades_routes)
+                .configure(routes::configure_submissions_routes)
+                .configure(routes::configure_homeworks_routes)
+                .configure(routes::configure_classes_routes)
+                .configure(routes::configure_file_routes)
+                .configure(routes::configure_system_routes),
+        )
+        .await
+    }
+
+    /// 创建密码为 [`TEST_PASSWORD`] 的已激活用户
+    pub async fn create_user(&self, username: &str, role: UserRole) -> User {
+        self.storage
+            .create_user(CreateUserRequest {
+                username: username.to_string(),
+                email: format!("{username}@example.com"),
+                password: hash_password(TEST_PASSWORD).unwrap(),
+                role,
+                status: Some(UserStatus::Active),
+                profile: UserProfile {
+                    profile_name: username.to_string(),
+                    avatar_url: None,
+                },
+            })
+            .await
+            .expect("Failed to create user")
+    }
+
+    /// 创建由指定教师管理的班级，并把学生加入班级
+    pub async fn create_class(&self, teacher: &User, students: &[&User]) -> Class {
+        let class = self
+            .storage
+            .create_class(CreateClassRequest {
+                teacher_id: teacher.id,
+                class_name: format!("Class of {}", teacher.username),
+                description: None,
+            })
+            .await
+            .expect("Failed to create class");
+        for student in students {
+            self.storage
+                .join_class(student.id, class.id, ClassUserRole::Student)
+                .await
+                .expect("Failed to join class");
+        }
+        class
+    }
+}
+
+/// 为用户签发访问令牌，返回 `Authorization` 请求头的值
+pub fn bearer(user: &User) -> (&'static str, String) {
+    let token = JwtUtils::generate_access_token(user.id, &user.role.to_string(), None)
+        .expect("Failed to sign access token");
+    ("Authorization", format!("Bearer {token}"))
+}
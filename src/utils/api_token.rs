@@ -0,0 +1,47 @@
+use sha2::{Digest, Sha256};
+
+use super::random_code::generate_random_code;
+
+/// 个人访问令牌前缀，便于与 JWT 区分，也便于密钥扫描工具识别
+pub const API_TOKEN_PREFIX: &str = "hwpat_";
+const API_TOKEN_SECRET_LENGTH: usize = 40;
+// 保存并展示的令牌前缀长度，用于在列表中辨认令牌
+const DISPLAY_PREFIX_LENGTH: usize = 12;
+
+/// 只读权限，仅允许 GET/HEAD 请求
+pub const SCOPE_READ: &str = "read";
+/// 读写权限
+pub const SCOPE_WRITE: &str = "write";
+/// 可用的权限范围
+pub const SCOPES: &[&str] = &[SCOPE_READ, SCOPE_WRITE];
+
+/// 生成新的个人访问令牌，返回令牌明文、展示用前缀与哈希
+pub fn generate() -> (String, String, String) {
+    let token = format!(
+        "{API_TOKEN_PREFIX}{}",
+        generate_random_code(API_TOKEN_SECRET_LENGTH)
+    );
+    let display_prefix = token[..DISPLAY_PREFIX_LENGTH].to_string();
+    let token_hash = hash(&token);
+    (token, display_prefix, token_hash)
+}
+
+/// 令牌本身为高熵随机串，使用 SHA-256 哈希即可，每次请求校验无需 Argon2 的计算开销
+pub fn hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 判断 Bearer 令牌是否为个人访问令牌
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(API_TOKEN_PREFIX)
+}
+
+/// 判断权限范围是否允许该请求方法，未设置权限范围时拥有用户的全部权限
+pub fn scopes_allow(scopes: &[String], method: &actix_web::http::Method) -> bool {
+    scopes.is_empty()
+        || scopes.iter().any(|scope| scope == SCOPE_WRITE)
+        || (method.is_safe() && scopes.iter().any(|scope| scope == SCOPE_READ))
+}
@@ -0,0 +1,107 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, ImageResult, Limits};
+use std::io::Cursor;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = &[0xFF, 0xD8, 0xFF];
+const JPEG_QUALITY: u8 = 85;
+
+/// 头像的公开访问路径前缀
+pub const AVATAR_URL_PREFIX: &str = "/api/v1/avatars/";
+
+/// 头像文件名：`{user_id}-{version}-{size}.{ext}`，每次上传生成新的 version 以便长期缓存
+pub fn file_name(user_id: i64, version: &str, size: u32, ext: &str) -> String {
+    format!("{user_id}-{version}-{size}.{ext}")
+}
+
+/// 校验头像文件名格式，防止通过文件名访问头像目录之外的文件
+pub fn is_valid_file_name(name: &str) -> bool {
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return false;
+    };
+    if ext != "png" && ext != "jpg" {
+        return false;
+    }
+    let parts: Vec<&str> = stem.split('-').collect();
+    parts.len() == 3
+        && parts[0].chars().all(|c| c.is_ascii_digit())
+        && parts[1].chars().all(|c| c.is_ascii_hexdigit())
+        && parts[2].chars().all(|c| c.is_ascii_digit())
+        && parts.iter().all(|part| !part.is_empty())
+}
+
+/// 从 avatar_url 中取出同一次上传的文件名前缀 `{user_id}-{version}` 与扩展名
+pub fn uploaded_version(avatar_url: &str) -> Option<(&str, &str)> {
+    let name = avatar_url.strip_prefix(AVATAR_URL_PREFIX)?;
+    if !is_valid_file_name(name) {
+        return None;
+    }
+    let (stem, ext) = name.rsplit_once('.')?;
+    let (prefix, _size) = stem.rsplit_once('-')?;
+    Some((prefix, ext))
+}
+
+/// 根据文件头识别头像格式，只接受 PNG 与 JPEG，不信任客户端声明的 Content-Type
+pub fn detect_format(data: &[u8]) -> Option<ImageFormat> {
+    if data.starts_with(PNG_SIGNATURE) {
+        Some(ImageFormat::Png)
+    } else if data.starts_with(JPEG_SIGNATURE) {
+        Some(ImageFormat::Jpeg)
+    } else {
+        None
+    }
+}
+
+/// 头像文件扩展名
+pub fn extension(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "png",
+        _ => "jpg",
+    }
+}
+
+/// 解码头像并按给定尺寸居中裁剪、缩放为正方形，输出格式与原图一致
+///
+/// 重新编码后不会保留 EXIF 等元数据，JPEG 的方向信息在解码时直接应用到像素上
+pub fn process_avatar(
+    data: &[u8],
+    format: ImageFormat,
+    max_dimension: u32,
+    sizes: &[u32],
+) -> ImageResult<Vec<(u32, Vec<u8>)>> {
+    // 限制原图尺寸，避免解压炸弹耗尽内存
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(max_dimension);
+    limits.max_image_height = Some(max_dimension);
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    sizes
+        .iter()
+        .map(|&size| {
+            let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+            encode(&resized, format).map(|bytes| (size, bytes))
+        })
+        .collect()
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> ImageResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    match format {
+        ImageFormat::Png => {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_to(&mut Cursor::new(&mut buf), ImageFormat::Png)?;
+        }
+        _ => {
+            let encoder = JpegEncoder::new_with_quality(&mut buf, JPEG_QUALITY);
+            image.to_rgb8().write_with_encoder(encoder)?;
+        }
+    }
+    Ok(buf)
+}
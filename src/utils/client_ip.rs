@@ -0,0 +1,142 @@
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::HttpRequest;
+use actix_web::http::header::HeaderMap;
+
+use crate::system::app_config::AppConfig;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_REAL_IP: &str = "x-real-ip";
+
+/// 获取客户端 IP，用于登录锁定、限流与会话记录
+///
+/// 只有直接连接的对端在 `server.trusted_proxies` 中时才采用 `X-Forwarded-For` 或 `X-Real-IP`，
+/// 否则客户端可以伪造请求头绕过按 IP 的限制。通过 Unix 套接字连接时没有对端地址，
+/// 只有本机的反向代理能够连接，视为受信任的代理。
+pub fn client_ip(request: &HttpRequest) -> Option<IpAddr> {
+    resolve(
+        request.peer_addr(),
+        request.headers(),
+        &AppConfig::get().server.trusted_proxies,
+    )
+}
+
+fn resolve(
+    peer_addr: Option<SocketAddr>,
+    headers: &HeaderMap,
+    trusted_proxies: &[String],
+) -> Option<IpAddr> {
+    let peer_ip = peer_addr.map(|addr| addr.ip());
+    if peer_ip.is_some_and(|ip| !is_trusted(ip, trusted_proxies)) {
+        return peer_ip;
+    }
+
+    // 从右向左跳过受信任的代理，第一个不受信任的地址即为客户端
+    let forwarded: Vec<IpAddr> = headers
+        .get_all(X_FORWARDED_FOR)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded
+            .iter()
+            .rev()
+            .find(|ip| !is_trusted(**ip, trusted_proxies))
+            .or(forwarded.first())
+            .copied();
+    }
+
+    headers
+        .get(X_REAL_IP)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|ip| ip.trim().parse().ok())
+        .or(peer_ip)
+}
+
+// 受信任的代理可以是单个地址或 CIDR 网段，例如 "10.0.0.0/8"
+fn is_trusted(ip: IpAddr, trusted_proxies: &[String]) -> bool {
+    let ip = ip.to_canonical();
+    trusted_proxies.iter().any(|entry| {
+        let (network, prefix) = match entry.split_once('/') {
+            Some((network, prefix)) => (network, prefix.parse::<u32>().ok()),
+            None => (entry.as_str(), None),
+        };
+        let Ok(network) = network.trim().parse::<IpAddr>() else {
+            return false;
+        };
+        match (ip, network.to_canonical()) {
+            (IpAddr::V4(ip), IpAddr::V4(network)) => {
+                let prefix = prefix.unwrap_or(32).min(32);
+                let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+                u32::from(ip) & mask == u32::from(network) & mask
+            }
+            (IpAddr::V6(ip), IpAddr::V6(network)) => {
+                let prefix = prefix.unwrap_or(128).min(128);
+                let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+                u128::from(ip) & mask == u128::from(network) & mask
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+        headers
+    }
+
+    fn peer(ip: &str) -> Option<SocketAddr> {
+        Some(SocketAddr::new(ip.parse().unwrap(), 40000))
+    }
+
+    fn trusted() -> Vec<String> {
+        vec!["10.0.0.0/8".to_string(), "192.168.1.1".to_string()]
+    }
+
+    #[test]
+    fn forwarded_headers_from_untrusted_peer_are_ignored() {
+        let headers = headers(&[("x-forwarded-for", "1.1.1.1"), ("x-real-ip", "2.2.2.2")]);
+        let ip = resolve(peer("203.0.113.9"), &headers, &trusted());
+        assert_eq!(ip, "203.0.113.9".parse().ok());
+        let ip = resolve(peer("10.0.0.1"), &headers, &[]);
+        assert_eq!(ip, "10.0.0.1".parse().ok());
+    }
+
+    #[test]
+    fn client_is_first_untrusted_hop_from_the_right() {
+        // 客户端自行添加的 1.1.1.1 不会被采用
+        let headers = headers(&[("x-forwarded-for", "1.1.1.1, 203.0.113.9, 10.1.2.3")]);
+        let ip = resolve(peer("192.168.1.1"), &headers, &trusted());
+        assert_eq!(ip, "203.0.113.9".parse().ok());
+    }
+
+    #[test]
+    fn real_ip_and_unix_socket_fallbacks() {
+        let headers = headers(&[("x-real-ip", "203.0.113.9")]);
+        assert_eq!(
+            resolve(peer("10.9.9.9"), &headers, &trusted()),
+            "203.0.113.9".parse().ok()
+        );
+        assert_eq!(resolve(None, &headers, &[]), "203.0.113.9".parse().ok());
+        assert_eq!(resolve(None, &HeaderMap::new(), &[]), None);
+    }
+
+    #[test]
+    fn ipv4_mapped_peer_matches_ipv4_proxy() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.9")]);
+        let ip = resolve(peer("::ffff:10.0.0.5"), &headers, &trusted());
+        assert_eq!(ip, "203.0.113.9".parse().ok());
+    }
+}
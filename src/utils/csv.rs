@@ -0,0 +1,76 @@
+//! RFC 4180 CSV 的解析与输出
+
+use csv::{ReaderBuilder, Terminator, WriterBuilder};
+
+/// 解析 CSV 文本为行列表，忽略 UTF-8 BOM 与空行，支持 CRLF 与 LF 换行，各行列数可以不同
+pub fn parse(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        if !(record.len() == 1 && record[0].is_empty()) {
+            rows.push(record.iter().map(str::to_string).collect());
+        }
+    }
+    Ok(rows)
+}
+
+/// 输出一行 CSV（以 CRLF 结尾），必要时为字段加引号
+pub fn write_row<S: AsRef<str>>(fields: &[S]) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = WriterBuilder::new()
+        .terminator(Terminator::CRLF)
+        .from_writer(Vec::new());
+    writer.write_record(
+        fields
+            .iter()
+            .map(|field| sanitize_cell(field.as_ref()).into_owned()),
+    )?;
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+/// 以 = + - @ 开头的单元格会被电子表格软件当作公式执行，加单引号前缀作为文本处理
+pub fn sanitize_cell(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}").into()
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quotes_line_endings_and_bom() {
+        let text = "\u{feff}username,note\r\nalice,\"a, \"\"b\"\"\nc\"\n\nbob,\n";
+        assert_eq!(
+            parse(text).unwrap(),
+            vec![
+                vec!["username", "note"],
+                vec!["alice", "a, \"b\"\nc"],
+                vec!["bob", ""],
+            ]
+        );
+    }
+
+    #[test]
+    fn written_row_round_trips_and_neutralizes_formulas() {
+        let line = write_row(&["alice", "=HYPERLINK(\"x\")", "a,b"]).unwrap();
+        assert_eq!(
+            String::from_utf8(line.clone()).unwrap(),
+            "alice,\"'=HYPERLINK(\"\"x\"\")\",\"a,b\"\r\n"
+        );
+        assert_eq!(
+            parse(std::str::from_utf8(&line).unwrap()).unwrap(),
+            vec![vec!["alice", "'=HYPERLINK(\"x\")", "a,b"]]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::purpose_token::{PurposeClaims, PurposeToken, TokenPurpose};
+
+// 下载链接令牌携带的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadTokenData {
+    pub file: String, // 文件标识 (submission_token)
+}
+
+pub type DownloadTokenClaims = PurposeClaims<DownloadTokenData>;
+
+/// 短期下载链接中携带的签名令牌，只能用于下载签发时指定的文件
+pub struct DownloadTokenUtils;
+
+impl DownloadTokenUtils {
+    /// 签发下载令牌
+    pub fn generate(
+        user_id: i64,
+        submission_token: &str,
+        expiry: chrono::Duration,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let data = DownloadTokenData {
+            file: submission_token.to_string(),
+        };
+        PurposeToken::generate(TokenPurpose::FileDownload, user_id, data, expiry)
+    }
+
+    /// 校验令牌签名、有效期、用途以及是否属于该文件
+    pub fn decode(
+        token: &str,
+        submission_token: &str,
+    ) -> Result<DownloadTokenClaims, jsonwebtoken::errors::Error> {
+        // 链接有效期很短，不额外放宽
+        let claims: DownloadTokenClaims =
+            PurposeToken::decode(token, TokenPurpose::FileDownload, 0)?;
+
+        if claims.data.file != submission_token {
+            return Err(jsonwebtoken::errors::ErrorKind::InvalidToken.into());
+        }
+        Ok(claims)
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use super::purpose_token::{PurposeClaims, PurposeToken, TokenPurpose};
+use crate::models::users::entities::User;
+
+/// 邮件令牌用途
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmailTokenPurpose {
+    VerifyEmail,
+    ResetPassword,
+}
+
+impl From<EmailTokenPurpose> for TokenPurpose {
+    fn from(purpose: EmailTokenPurpose) -> Self {
+        match purpose {
+            EmailTokenPurpose::VerifyEmail => TokenPurpose::VerifyEmail,
+            EmailTokenPurpose::ResetPassword => TokenPurpose::ResetPassword,
+        }
+    }
+}
+
+// 邮件令牌携带的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailTokenData {
+    pub email: String, // 签发时的用户邮箱
+    pub fp: String,    // 签发时的账号状态指纹，状态变化后令牌失效
+}
+
+pub type EmailTokenClaims = PurposeClaims<EmailTokenData>;
+
+/// 邮件中使用的签名一次性令牌（邮箱验证、密码重置）
+///
+/// 令牌不在服务端存储，而是绑定签发时的账号状态：
+/// 邮箱验证令牌绑定邮箱与验证状态，密码重置令牌绑定当前密码哈希，使用一次后状态改变，令牌随即失效。
+pub struct EmailTokenUtils;
+
+impl EmailTokenUtils {
+    // 账号状态指纹
+    fn fingerprint(user: &User, purpose: EmailTokenPurpose) -> String {
+        let state = match purpose {
+            EmailTokenPurpose::VerifyEmail => format!("{}:{}", user.email, user.email_verified),
+            EmailTokenPurpose::ResetPassword => user.password_hash.clone(),
+        };
+        PurposeToken::fingerprint(&state)
+    }
+
+    /// 为用户签发指定用途的令牌
+    pub fn generate(
+        user: &User,
+        purpose: EmailTokenPurpose,
+        expiry: chrono::Duration,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let data = EmailTokenData {
+            email: user.email.clone(),
+            fp: Self::fingerprint(user, purpose),
+        };
+        PurposeToken::generate(purpose.into(), user.id, data, expiry)
+    }
+
+    /// 校验令牌签名、有效期与用途
+    pub fn decode(
+        token: &str,
+        purpose: EmailTokenPurpose,
+    ) -> Result<EmailTokenClaims, jsonwebtoken::errors::Error> {
+        PurposeToken::decode(token, purpose.into(), 60)
+    }
+
+    /// 检查令牌是否仍与用户当前状态一致（未被使用过）
+    pub fn matches_user(
+        claims: &EmailTokenClaims,
+        user: &User,
+        purpose: EmailTokenPurpose,
+    ) -> bool {
+        claims.sub == user.id.to_string()
+            && claims.data.email == user.email
+            && claims.data.fp == Self::fingerprint(user, purpose)
+    }
+}
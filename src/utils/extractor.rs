@@ -35,3 +35,5 @@ macro_rules! define_safe_i64_extractor {
 
 define_safe_i64_extractor!(SafeIDI64, "id");
 define_safe_i64_extractor!(SafeClassIdI64, "class_id");
+define_safe_i64_extractor!(SafeHomeworkIdI64, "homework_id");
+define_safe_i64_extractor!(SafeSubmissionIdI64, "submission_id");
@@ -1,7 +1,12 @@
+use crate::cache::{CacheResult, ObjectCache};
+use crate::errors::Result as StorageResult;
+use crate::repository::Storage;
 use crate::system::app_config::AppConfig;
 use actix_web::cookie::{Cookie, SameSite};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::jwt_keys::JwtKeys;
 
 // JWT Claims 结构体
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +16,10 @@ pub struct Claims {
     pub token_type: String, // token类型: "access" 或 "refresh"
     pub exp: usize,         // Expiration time (时间戳)
     pub iat: usize,         // Issued at (签发时间)
+    #[serde(default)]
+    pub jti: String, // JWT ID (令牌唯一标识)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fid: Option<String>, // 令牌族 ID，即登录会话 ID
 }
 
 // Token 响应结构体
@@ -18,70 +27,91 @@ pub struct Claims {
 pub struct TokenPair {
     pub access_token: String,
     pub refresh_token: String,
+    pub refresh_claims: Claims,
 }
 
 pub struct JwtUtils;
 
 impl JwtUtils {
-    // 获取 JWT 密钥
-    fn get_secret() -> String {
-        AppConfig::get().jwt.secret.clone()
-    }
-
-    // 生成 Access Token
+    // 生成 Access Token，携带所属会话 ID 以便按会话吊销
     pub fn generate_access_token(
         user_id: i64,
         role: &str,
+        session_id: Option<&str>,
     ) -> Result<String, jsonwebtoken::errors::Error> {
         let config = AppConfig::get();
-        Self::generate_token_with_expiry(
+        let mut claims = Self::build_claims(
             user_id,
             role,
             "access",
             chrono::Duration::minutes(config.jwt.access_token_expiry),
-        )
+        );
+        claims.fid = session_id.map(str::to_string);
+
+        Self::encode_claims(&claims)
     }
 
-    // 生成 Refresh Token
+    // 生成 Refresh Token，未指定令牌族时开启新的令牌族
     pub fn generate_refresh_token(
         user_id: i64,
         role: &str,
+        family_id: Option<&str>,
         token_expiry: Option<chrono::Duration>,
-    ) -> Result<String, jsonwebtoken::errors::Error> {
+    ) -> Result<(String, Claims), jsonwebtoken::errors::Error> {
         let config = AppConfig::get();
-        match token_expiry {
-            Some(expiry) => Self::generate_token_with_expiry(user_id, role, "refresh", expiry),
-            None => Self::generate_token_with_expiry(
-                user_id,
-                role,
-                "refresh",
-                chrono::Duration::days(config.jwt.refresh_token_expiry),
-            ),
-        }
+        let expiry =
+            token_expiry.unwrap_or_else(|| chrono::Duration::days(config.jwt.refresh_token_expiry));
+
+        let mut claims = Self::build_claims(user_id, role, "refresh", expiry);
+        claims.fid = Some(
+            family_id
+                .map(str::to_string)
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        );
+
+        let token = Self::encode_claims(&claims)?;
+        Ok((token, claims))
     }
 
-    // 生成带自定义过期时间的 Token
-    pub fn generate_token_with_expiry(
+    // 轮换 Refresh Token：沿用原令牌族与过期时间，轮换不会延长登录有效期
+    pub fn rotate_refresh_token(
+        claims: &Claims,
+        role: &str,
+    ) -> Result<(String, Claims), jsonwebtoken::errors::Error> {
+        let user_id = claims
+            .sub
+            .parse::<i64>()
+            .map_err(|_| jsonwebtoken::errors::ErrorKind::InvalidToken)?;
+        let remaining =
+            chrono::Duration::seconds(claims.exp as i64 - chrono::Utc::now().timestamp());
+
+        Self::generate_refresh_token(user_id, role, claims.fid.as_deref(), Some(remaining))
+    }
+
+    // 构建 Claims，每个令牌都会分配唯一的 jti
+    fn build_claims(
         user_id: i64,
         role: &str,
         token_type: &str,
         expiry_duration: chrono::Duration,
-    ) -> Result<String, jsonwebtoken::errors::Error> {
+    ) -> Claims {
         let now = chrono::Utc::now();
         let expiration = now + expiry_duration;
 
-        let claims = Claims {
+        Claims {
             sub: user_id.to_string(),
             role: role.to_string(),
             token_type: token_type.to_string(),
             exp: expiration.timestamp() as usize,
             iat: now.timestamp() as usize,
-        };
-
-        let secret = Self::get_secret();
-        let encoding_key = EncodingKey::from_secret(secret.as_ref());
+            jti: Uuid::new_v4().to_string(),
+            fid: None,
+        }
+    }
 
-        encode(&Header::default(), &claims, &encoding_key)
+    // 使用当前签名密钥签名 Claims
+    fn encode_claims(claims: &Claims) -> Result<String, jsonwebtoken::errors::Error> {
+        JwtKeys::get().encode(claims)
     }
 
     // 生成完整的 Token 响应（包含 access 和 refresh token）
@@ -90,22 +120,21 @@ impl JwtUtils {
         role: &str,
         refresh_token_expiry: Option<chrono::Duration>,
     ) -> Result<TokenPair, jsonwebtoken::errors::Error> {
-        let access_token = Self::generate_access_token(user_id, role)?;
-        let refresh_token = Self::generate_refresh_token(user_id, role, refresh_token_expiry)?;
+        let (refresh_token, refresh_claims) =
+            Self::generate_refresh_token(user_id, role, None, refresh_token_expiry)?;
+        let access_token =
+            Self::generate_access_token(user_id, role, refresh_claims.fid.as_deref())?;
 
         Ok(TokenPair {
             access_token,
             refresh_token,
+            refresh_claims,
         })
     }
 
-    // 验证 JWT token
+    // 验证 JWT token，按令牌头部的 kid 选择验证密钥
     pub fn verify_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let secret = Self::get_secret();
-        let decoding_key = DecodingKey::from_secret(secret.as_ref());
-        let validation = Validation::default();
-
-        decode::<Claims>(token, &decoding_key, &validation).map(|token_data| token_data.claims)
+        JwtKeys::get().decode(token)
     }
 
     // 验证 token 是否为指定类型
@@ -132,24 +161,87 @@ impl JwtUtils {
         Self::verify_token_type(token, "refresh")
     }
 
-    pub fn decode_token(token: &str) -> Result<Claims, jsonwebtoken::errors::Error> {
-        let secret = Self::get_secret();
-        let decoding_key = DecodingKey::from_secret(secret.as_ref());
-        let validation = Validation::default();
+    /// 吊销 Access Token，在其剩余有效期内拒绝使用
+    pub async fn revoke_access_token(cache: &dyn ObjectCache, claims: &Claims) {
+        let remaining = claims.exp as i64 - chrono::Utc::now().timestamp();
+        if claims.jti.is_empty() || remaining <= 0 {
+            return;
+        }
+        cache
+            .insert_raw(
+                format!("revoked_jti:{}", claims.jti),
+                "true".to_string(),
+                remaining as u64,
+            )
+            .await;
+    }
 
-        decode::<Claims>(token, &decoding_key, &validation).map(|token_data| token_data.claims)
+    /// 吊销用户此前签发的全部 Access Token（用于注销所有设备）
+    pub async fn revoke_user_access_tokens(cache: &dyn ObjectCache, user_id: i64) {
+        let config = AppConfig::get();
+        cache
+            .insert_raw(
+                format!("revoked_before:{user_id}"),
+                chrono::Utc::now().timestamp().to_string(),
+                (config.jwt.access_token_expiry * 60) as u64,
+            )
+            .await;
     }
 
-    // 使用 Refresh Token 生成新的 Access Token
-    pub fn refresh_access_token(
-        refresh_token: &str,
-    ) -> Result<String, jsonwebtoken::errors::Error> {
-        let claims = Self::verify_refresh_token(refresh_token)?;
-        let user_id = claims
-            .sub
-            .parse::<i64>()
-            .map_err(|_| jsonwebtoken::errors::ErrorKind::InvalidToken)?;
-        Self::generate_access_token(user_id, &claims.role)
+    /// 吊销指定会话签发的全部 Access Token（用于终止会话）
+    pub async fn revoke_session_access_tokens(cache: &dyn ObjectCache, session_id: &str) {
+        let config = AppConfig::get();
+        cache
+            .insert_raw(
+                format!("revoked_session:{session_id}"),
+                "true".to_string(),
+                (config.jwt.access_token_expiry * 60) as u64,
+            )
+            .await;
+    }
+
+    /// 检查 Access Token 是否已被吊销
+    ///
+    /// 缓存中的吊销记录只用于加速判断；属于登录会话的令牌以存储中的会话状态为准，
+    /// 缓存被清除或服务重启后，已终止会话签发的令牌仍然无效。
+    pub async fn is_access_token_revoked(
+        cache: &dyn ObjectCache,
+        storage: &dyn Storage,
+        claims: &Claims,
+    ) -> StorageResult<bool> {
+        if !claims.jti.is_empty()
+            && matches!(
+                cache.get_raw(&format!("revoked_jti:{}", claims.jti)).await,
+                CacheResult::Found(_)
+            )
+        {
+            return Ok(true);
+        }
+
+        if let Some(session_id) = &claims.fid {
+            if matches!(
+                cache
+                    .get_raw(&format!("revoked_session:{session_id}"))
+                    .await,
+                CacheResult::Found(_)
+            ) {
+                return Ok(true);
+            }
+            // 登出、注销所有设备、修改或重置密码都会在存储中终止会话
+            let session = storage.get_session_by_id(session_id).await?;
+            return Ok(session.is_none_or(|session| session.revoked_at.is_some()));
+        }
+
+        // 不属于会话的令牌按签发时间判断，同一秒内签发的令牌同样视为已吊销
+        match cache
+            .get_raw(&format!("revoked_before:{}", claims.sub))
+            .await
+        {
+            CacheResult::Found(value) => Ok(value
+                .parse::<usize>()
+                .is_ok_and(|revoked_before| claims.iat <= revoked_before)),
+            _ => Ok(false),
+        }
     }
 
     /// 创建 Refresh Token Cookie
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use super::purpose_token::{PurposeClaims, PurposeToken, TokenPurpose};
+use crate::models::auth::entities::MfaStage;
+use crate::models::users::entities::User;
+
+// 登录第二步令牌携带的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaTokenData {
+    pub stage: MfaStage,   // 需要完成的操作
+    pub remember_me: bool, // 第一步登录时是否选择记住我
+    pub fp: String,        // 签发时的密码哈希指纹，修改密码后令牌失效
+}
+
+pub type MfaTokenClaims = PurposeClaims<MfaTokenData>;
+
+/// 密码校验通过但仍需两步验证时签发的短期令牌，只能用于完成登录第二步
+pub struct MfaTokenUtils;
+
+impl MfaTokenUtils {
+    fn fingerprint(user: &User) -> String {
+        PurposeToken::fingerprint(&user.password_hash)
+    }
+
+    /// 签发登录第二步令牌
+    pub fn generate(
+        user: &User,
+        stage: MfaStage,
+        remember_me: bool,
+        expiry: chrono::Duration,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let data = MfaTokenData {
+            stage,
+            remember_me,
+            fp: Self::fingerprint(user),
+        };
+        PurposeToken::generate(TokenPurpose::MfaPending, user.id, data, expiry)
+    }
+
+    /// 校验令牌签名、有效期与用途
+    pub fn decode(token: &str) -> Result<MfaTokenClaims, jsonwebtoken::errors::Error> {
+        PurposeToken::decode(token, TokenPurpose::MfaPending, 60)
+    }
+
+    /// 检查令牌是否属于该用户且签发后密码未被修改
+    pub fn matches_user(claims: &MfaTokenClaims, user: &User) -> bool {
+        claims.sub == user.id.to_string() && claims.data.fp == Self::fingerprint(user)
+    }
+}
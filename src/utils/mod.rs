@@ -1,11 +1,24 @@
+pub mod api_token;
+pub mod avatar;
+pub mod client_ip;
+pub mod csv;
+pub mod download_token;
+pub mod email_token;
 pub mod extractor;
 pub mod jwt;
+pub mod jwt_keys;
+pub mod mfa_token;
+pub mod oidc;
 pub mod parameter_error_handler;
 pub mod password;
+pub mod purpose_token;
 pub mod random_code;
+pub mod sha256_state;
 pub mod sqlx_macros;
+pub mod totp;
 pub mod validate;
+pub mod xlsx;
 
-pub use extractor::{SafeClassIdI64, SafeIDI64};
+pub use extractor::{SafeClassIdI64, SafeHomeworkIdI64, SafeIDI64, SafeSubmissionIdI64};
 pub use parameter_error_handler::json_error_handler;
 pub use parameter_error_handler::query_error_handler;
@@ -0,0 +1,166 @@
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::system::app_config::AppConfig;
+
+/// 令牌用途，签发时写入 `aud`，校验时只接受相同用途的令牌
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenPurpose {
+    VerifyEmail,   // 邮箱验证链接
+    ResetPassword, // 密码重置链接
+    MfaPending,    // 登录第二步
+    FileDownload,  // 文件下载链接
+}
+
+impl TokenPurpose {
+    pub fn audience(self) -> &'static str {
+        match self {
+            TokenPurpose::VerifyEmail => "hwsystem:verify_email",
+            TokenPurpose::ResetPassword => "hwsystem:reset_password",
+            TokenPurpose::MfaPending => "hwsystem:mfa_pending",
+            TokenPurpose::FileDownload => "hwsystem:file_download",
+        }
+    }
+}
+
+/// 用途令牌 Claims，用途相关的字段平铺在 `data` 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurposeClaims<T> {
+    pub sub: String, // 用户 ID
+    pub aud: String, // 令牌用途
+    pub exp: usize,
+    pub iat: usize,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+/// 签名的短期用途令牌（邮件链接、登录第二步、下载链接）
+///
+/// 登录令牌不带 `aud`，解码时若令牌带有 `aud` 会被拒绝；用途令牌必须带有与用途一致的 `aud`，
+/// 因此各类令牌之间以及与登录令牌之间都无法互相冒用。
+pub struct PurposeToken;
+
+impl PurposeToken {
+    fn get_secret() -> String {
+        AppConfig::get().jwt.secret.clone()
+    }
+
+    /// 为指定用途签发令牌
+    pub fn generate<T: Serialize>(
+        purpose: TokenPurpose,
+        subject: i64,
+        data: T,
+        expiry: chrono::Duration,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let now = chrono::Utc::now();
+        let claims = PurposeClaims {
+            sub: subject.to_string(),
+            aud: purpose.audience().to_string(),
+            exp: (now + expiry).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            data,
+        };
+
+        let secret = Self::get_secret();
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_ref()),
+        )
+    }
+
+    /// 校验令牌签名、有效期与用途，`leeway` 为允许的时钟偏差秒数
+    pub fn decode<T: DeserializeOwned>(
+        token: &str,
+        purpose: TokenPurpose,
+        leeway: u64,
+    ) -> Result<PurposeClaims<T>, jsonwebtoken::errors::Error> {
+        let secret = Self::get_secret();
+        let mut validation = Validation::default();
+        validation.leeway = leeway;
+        validation.set_audience(&[purpose.audience()]);
+        validation.set_required_spec_claims(&["exp", "aud", "sub"]);
+
+        decode::<PurposeClaims<T>>(
+            token,
+            &DecodingKey::from_secret(secret.as_ref()),
+            &validation,
+        )
+        .map(|data| data.claims)
+    }
+
+    /// 账号状态指纹，状态变化后绑定该指纹的令牌随即失效
+    pub fn fingerprint(state: &str) -> String {
+        let digest = Sha256::digest(state.as_bytes());
+        digest[..16].iter().map(|b| format!("{b:02x}")).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::jwt::JwtUtils;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Payload {
+        file: String,
+    }
+
+    fn payload() -> Payload {
+        Payload {
+            file: "abc".to_string(),
+        }
+    }
+
+    #[test]
+    fn token_is_only_accepted_for_its_purpose() {
+        AppConfig::init_for_test();
+        let token = PurposeToken::generate(
+            TokenPurpose::FileDownload,
+            7,
+            payload(),
+            chrono::Duration::minutes(5),
+        )
+        .unwrap();
+
+        let claims =
+            PurposeToken::decode::<Payload>(&token, TokenPurpose::FileDownload, 0).unwrap();
+        assert_eq!(claims.sub, "7");
+        assert_eq!(claims.data.file, "abc");
+        assert!(PurposeToken::decode::<Payload>(&token, TokenPurpose::MfaPending, 0).is_err());
+    }
+
+    #[test]
+    fn login_and_purpose_tokens_are_not_interchangeable() {
+        AppConfig::init_for_test();
+        let purpose_token = PurposeToken::generate(
+            TokenPurpose::VerifyEmail,
+            7,
+            payload(),
+            chrono::Duration::minutes(5),
+        )
+        .unwrap();
+        assert!(JwtUtils::verify_access_token(&purpose_token).is_err());
+
+        let access_token = JwtUtils::generate_access_token(7, "user", None).unwrap();
+        assert!(
+            PurposeToken::decode::<serde_json::Value>(&access_token, TokenPurpose::VerifyEmail, 0)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        AppConfig::init_for_test();
+        let token = PurposeToken::generate(
+            TokenPurpose::ResetPassword,
+            7,
+            payload(),
+            chrono::Duration::seconds(-5),
+        )
+        .unwrap();
+        assert!(PurposeToken::decode::<Payload>(&token, TokenPurpose::ResetPassword, 0).is_err());
+    }
+}
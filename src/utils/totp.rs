@@ -0,0 +1,98 @@
+use hmac::{Hmac, Mac};
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
+use rand::Rng;
+use sha1::Sha1;
+
+/// RFC 6238 时间步长 (秒)
+pub const TOTP_STEP: u64 = 30;
+/// 验证码位数
+pub const TOTP_DIGITS: u32 = 6;
+
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const SECRET_LENGTH: usize = 20;
+
+/// 生成 160 位随机密钥，返回 Base32 编码（无填充），与常见验证器 App 兼容
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::rng().fill(&mut secret[..]);
+    base32_encode(&secret)
+}
+
+/// 生成验证器 App 扫码使用的 otpauth URI
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = utf8_percent_encode(issuer, NON_ALPHANUMERIC).to_string();
+    let account = utf8_percent_encode(account, NON_ALPHANUMERIC).to_string();
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_STEP}"
+    )
+}
+
+/// 校验验证码，允许前后 `skew` 个时间步的偏差
+///
+/// 校验通过时返回匹配的时间步，调用方需记录该时间步防止验证码被重放
+pub fn verify(secret: &str, code: &str, unix_time: u64, skew: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = unix_time / TOTP_STEP;
+
+    (current.saturating_sub(skew)..=current + skew).find(|&step| hotp(&key, step) == code)
+}
+
+// RFC 4226 HOTP
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    output
+}
+
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.bytes().filter(|c| *c != b'=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
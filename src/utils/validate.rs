@@ -19,3 +19,12 @@ pub fn validate_email(email: &str) -> Result<(), &'static str> {
     }
     Ok(())
 }
+
+pub fn validate_password(password: &str) -> Result<(), &'static str> {
+    // 密码长度校验：8 <= x <= 128
+    let length = password.chars().count();
+    if !(8..=128).contains(&length) {
+        return Err("Password length must be between 8 and 128 characters");
+    }
+    Ok(())
+}
@@ -0,0 +1,167 @@
+//! XLSX 读写，只处理第一个工作表中的单元格文本，不处理样式与公式
+//!
+//! 读取使用 calamine，写入使用 rust_xlsxwriter。
+
+use calamine::{Data, Reader, Xlsx};
+use rust_xlsxwriter::{Workbook, XlsxError};
+use std::io::{Cursor, Read};
+
+// 解压后所有部件的最大总字节数，防止压缩炸弹
+const MAX_UNCOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+// 最多读取的列数，防止 r="XFD1" 之类的单元格引用生成超长的行
+const MAX_COLUMNS: usize = 256;
+
+/// 文件头是否为 ZIP (XLSX)
+pub fn is_xlsx(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+}
+
+/// 读取第一个工作表的全部行，空单元格为空字符串，空行被忽略
+pub fn read_rows(data: &[u8]) -> Result<Vec<Vec<String>>, String> {
+    check_uncompressed_size(data)?;
+
+    let mut workbook =
+        Xlsx::new(Cursor::new(data)).map_err(|e| format!("Invalid XLSX file: {e}"))?;
+    let sheet = workbook
+        .sheet_names()
+        .into_iter()
+        .next()
+        .ok_or("Workbook has no worksheet")?;
+    let mut cells = workbook
+        .worksheet_cells_reader(&sheet)
+        .map_err(|e| format!("Failed to read worksheet: {e}"))?;
+
+    // 单元格按行顺序给出，行号变化时收集上一行
+    let mut rows = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut current_row = None;
+    while let Some(cell) = cells
+        .next_cell()
+        .map_err(|e| format!("Failed to read worksheet: {e}"))?
+    {
+        let (row_index, column) = cell.get_position();
+        let column = column as usize;
+        if column >= MAX_COLUMNS {
+            return Err("Worksheet has too many columns".to_string());
+        }
+        if current_row != Some(row_index) {
+            push_row(&mut rows, std::mem::take(&mut row));
+            current_row = Some(row_index);
+        }
+        if row.len() <= column {
+            row.resize(column + 1, String::new());
+        }
+        row[column] = Data::from(cell.get_value().clone()).to_string();
+    }
+    push_row(&mut rows, row);
+
+    Ok(rows)
+}
+
+fn push_row(rows: &mut Vec<Vec<String>>, row: Vec<String>) {
+    if row.iter().any(|cell| !cell.trim().is_empty()) {
+        rows.push(row);
+    }
+}
+
+// 实际解压一遍统计大小，文件头中记录的大小不可信
+fn check_uncompressed_size(data: &[u8]) -> Result<(), String> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Invalid XLSX file: {e}"))?;
+
+    let mut remaining = MAX_UNCOMPRESSED_SIZE;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Invalid XLSX file: {e}"))?;
+        let size = std::io::copy(&mut entry.take(remaining + 1), &mut std::io::sink())
+            .map_err(|e| format!("Invalid XLSX file: {e}"))?;
+        if size > remaining {
+            return Err("XLSX content is too large".to_string());
+        }
+        remaining -= size;
+    }
+    Ok(())
+}
+
+/// XLSX 写入器，只生成一个工作表，所有单元格都是文本
+///
+/// 行数据暂存在临时文件中，`finish` 时生成完整的文件。
+pub struct XlsxWriter {
+    workbook: Workbook,
+    row: u32,
+}
+
+impl XlsxWriter {
+    pub fn new(sheet_name: &str) -> Result<Self, XlsxError> {
+        let mut workbook = Workbook::new();
+        workbook
+            .add_worksheet_with_constant_memory()
+            .set_name(sheet_name)?;
+        Ok(Self { workbook, row: 0 })
+    }
+
+    /// 写入一行
+    pub fn write_row<S: AsRef<str>>(&mut self, cells: &[S]) -> Result<(), XlsxError> {
+        let worksheet = self.workbook.worksheet_from_index(0)?;
+        for (column, cell) in cells.iter().enumerate() {
+            worksheet.write_string(self.row, column as u16, cell.as_ref())?;
+        }
+        self.row += 1;
+        Ok(())
+    }
+
+    /// 生成文件内容
+    pub fn finish(mut self) -> Result<Vec<u8>, XlsxError> {
+        self.workbook.save_to_buffer()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn written_rows_are_read_back() {
+        let mut writer = XlsxWriter::new("users").unwrap();
+        writer.write_row(&["username", "email"]).unwrap();
+        writer.write_row(&["", ""]).unwrap();
+        writer
+            .write_row(&["alice", "=1+1 <a&b> \"quoted\""])
+            .unwrap();
+        let data = writer.finish().unwrap();
+
+        assert!(is_xlsx(&data));
+        assert_eq!(
+            read_rows(&data).unwrap(),
+            vec![
+                vec!["username".to_string(), "email".to_string()],
+                vec!["alice".to_string(), "=1+1 <a&b> \"quoted\"".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn oversized_archive_is_rejected() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("xl/sharedStrings.xml", SimpleFileOptions::default())
+            .unwrap();
+        let block = vec![b' '; 1024 * 1024];
+        for _ in 0..=MAX_UNCOMPRESSED_SIZE / block.len() as u64 {
+            zip.write_all(&block).unwrap();
+        }
+        let data = zip.finish().unwrap().into_inner();
+
+        assert_eq!(
+            read_rows(&data).unwrap_err(),
+            "XLSX content is too large".to_string()
+        );
+    }
+
+    #[test]
+    fn non_zip_data_is_rejected() {
+        assert!(read_rows(b"PK\x03\x04 truncated").is_err());
+    }
+}